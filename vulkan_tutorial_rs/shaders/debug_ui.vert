@@ -0,0 +1,30 @@
+#version 450
+
+// whether the target's format is sRGB, i.e. encodes what's written to it; UNORM targets are
+// presented as sRGB without conversion, so egui's colours are written as they are
+layout(constant_id = 0) const bool srgb_target = true;
+
+layout(push_constant) uniform PushConstants {
+    vec2 screen_size;
+} push_constants;
+
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 uv;
+layout(location = 2) in vec4 color;
+
+layout(location = 0) out vec2 frag_uv;
+layout(location = 1) out vec4 frag_color;
+
+// egui's vertex colours are sRGB encoded, with premultiplied alpha
+vec3 linear_from_srgb(vec3 srgb) {
+    bvec3 cutoff = lessThan(srgb, vec3(0.04045));
+    vec3 lower = srgb / vec3(12.92);
+    vec3 higher = pow((srgb + vec3(0.055)) / vec3(1.055), vec3(2.4));
+    return mix(higher, lower, cutoff);
+}
+
+void main() {
+    gl_Position = vec4(2.0 * position / push_constants.screen_size - 1.0, 0.0, 1.0);
+    frag_uv = uv;
+    frag_color = srgb_target ? vec4(linear_from_srgb(color.rgb), color.a) : color;
+}
@@ -0,0 +1,21 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform FrameData {
+    mat4 model;
+    mat4 view;
+    mat4 projection;
+    float time;
+} frame;
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 color;
+layout(location = 2) in vec2 uv;
+
+layout(location = 0) out vec3 frag_color;
+layout(location = 1) out vec2 frag_uv;
+
+void main() {
+    gl_Position = frame.projection * frame.view * frame.model * vec4(position, 1.0);
+    frag_color = color;
+    frag_uv = uv;
+}
@@ -1,51 +1,542 @@
 pub mod config;
 
-mod init;
+mod app_builder;
+mod app_window;
+mod application;
+pub mod camera;
+mod context;
+pub mod debug_ui;
+pub mod debug_utils;
+mod demo;
+mod descriptor_set_cache;
+mod frame;
+mod frame_stats;
+pub mod golden;
+pub mod init;
+mod init_error;
+pub mod input;
 mod logical_device;
+mod loop_timer;
+pub mod mesh;
+pub mod model;
+pub mod paths;
 mod physical_device_info;
+pub mod pipeline_cache;
+mod present_policy;
+pub mod profiler;
 mod queue_families;
-mod swapchain_info;
+pub mod raw_command_buffer;
+pub mod render_target;
+mod renderer;
+pub mod screenshot;
+pub mod shader;
+mod shader_watcher;
+pub mod swapchain_info;
+pub mod texture;
+mod uniforms;
+mod uploader;
+mod window_config;
+mod window_registry;
 
+pub use app_builder::{AppBuilder, DeviceSelector};
+pub use app_window::{AppWindow, FrameError};
+pub use application::Application;
+pub use camera::fly::FlyController;
+pub use camera::orbit::OrbitController;
+pub use camera::{Camera, Projection};
+pub use context::Context;
+pub use debug_ui::DebugUi;
+pub use demo::MeshDemo;
+pub use descriptor_set_cache::DescriptorSetCache;
+pub use frame::{Frame, FrameCommandBuffer};
+pub use frame_stats::{FrameStats, FrameTimeSummary};
+pub use init_error::InitError;
+pub use input::bindings::{AxisBinding, ButtonBinding, InputBindings};
+pub use input::Input;
 pub use logical_device::LogicalDevice;
+pub use loop_timer::LoopTimer;
+pub use mesh::{Mesh, MeshData, Vertex};
+pub use model::{Material, Model, ModelData, ModelError};
 pub use physical_device_info::PhysicalDeviceInfo;
+pub use present_policy::PresentPolicy;
+pub use profiler::overlay::ProfilerOverlay;
+pub use profiler::{FrameProfile, ProfileHistory, Profiler, RegionProfile};
 pub use queue_families::QueueFamilies;
+pub use render_target::{OffscreenTarget, RenderTarget};
+pub use renderer::Renderer;
+pub use screenshot::{ScreenshotCapture, ScreenshotError};
+pub use shader::{ShaderCompiler, ShaderError};
+pub use shader_watcher::ShaderWatcher;
 pub use swapchain_info::SwapchainInfo;
+pub use texture::{Texture, TextureError};
+pub use uniforms::{FrameUniforms, UniformBuffer, UniformSubbuffer};
+pub use uploader::{Pod, Uploader};
+pub use window_config::WindowConfig;
+pub use window_registry::WindowRegistry;
 
 use vulkano::instance::debug::DebugCallback;
 use vulkano::instance::Instance;
 
-use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
+use winit::event::{ElementState, Event, KeyboardInput, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget};
 
-use std::sync::Arc;
+use vulkano::device::Features;
+
+use std::sync::{Arc, Weak};
+use std::time::Instant;
 use vulkano::swapchain::Surface;
-use winit::window::Window;
+use winit::window::{Window, WindowId};
 
+// the Vulkan objects are optional only so that `shutdown` can destroy them in order; they are
+// always present until then
 pub struct App {
-    _instance: Arc<Instance>,
-    _debug_callback: Option<DebugCallback>,
-    _event_loop: EventLoop<()>,
-    _surface: Arc<Surface<Window>>,
-    _physical_device_info: PhysicalDeviceInfo,
-    _logical_device: LogicalDevice,
-    _swapchain_info: SwapchainInfo,
+    instance: Option<Arc<Instance>>,
+    debug_callback: Option<DebugCallback>,
+    event_loop: Option<EventLoop<()>>,
+    physical_device_info: Option<PhysicalDeviceInfo>,
+    logical_device: Option<LogicalDevice>,
+    windows: WindowRegistry,
+    // kept to redo device selection and swapchain creation after a device loss
+    device_selector: DeviceSelector,
+    device_features: Features,
+    present_policy: PresentPolicy,
+    loop_timer: LoopTimer,
+    frame_stats: FrameStats,
+    input: Input,
+    last_title_update: Option<Instant>,
 }
 
 impl App {
-    pub fn new() -> App {
-        init::new_app()
-    }
-
-    pub fn run(self) {
-        self._event_loop
-            .run(move |event, _, control_flow| match event {
-                Event::WindowEvent {
-                    event: WindowEvent::CloseRequested,
-                    ..
-                } => {
-                    *control_flow = ControlFlow::Exit;
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
+
+    pub fn context(&self) -> Context {
+        Context::new(
+            self.physical_device_info
+                .as_ref()
+                .expect("app has been shut down"),
+            self.logical_device
+                .as_ref()
+                .expect("app has been shut down"),
+            &self.windows,
+            &self.frame_stats,
+            &self.input,
+        )
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.logical_device.is_none()
+    }
+
+    // waits for the GPU to finish all submitted work, then destroys the swapchains, device,
+    // debug callback, surfaces and instance in that order, warning about any that are still
+    // referenced elsewhere; does nothing if already shut down
+    pub fn shutdown(&mut self) {
+        let logical_device = match self.logical_device.take() {
+            Some(logical_device) => logical_device,
+            None => return,
+        };
+        println!("Shutting down...");
+        wait_idle(&logical_device);
+        logical_device.save_pipeline_cache();
+
+        let mut swapchains = Vec::new();
+        let mut surfaces = Vec::new();
+        for window in self.windows.drain() {
+            let (_, surface, swapchain_info) = window.into_parts();
+            swapchains.push(Arc::downgrade(swapchain_info._swapchain()));
+            surfaces.push(surface);
+        }
+        report_alive("swapchain", &swapchains);
+
+        // holds references to the surfaces and the instance
+        self.physical_device_info = None;
+
+        let device = Arc::downgrade(logical_device.device());
+        drop(logical_device);
+        report_alive("device", &[device]);
+
+        self.debug_callback = None;
+
+        let surface_refs: Vec<_> = surfaces.iter().map(Arc::downgrade).collect();
+        drop(surfaces);
+        report_alive("surface", &surface_refs);
+
+        if let Some(instance) = self.instance.take() {
+            let instance_ref = Arc::downgrade(&instance);
+            drop(instance);
+            report_alive("instance", &[instance_ref]);
+        }
+        println!("Shutdown complete");
+    }
+
+    pub fn run<A: Application + 'static>(mut self, mut application: A) {
+        let event_loop = match self.event_loop.take() {
+            Some(event_loop) => event_loop,
+            None => {
+                println!("App is headless or already running; nothing to run");
+                return;
+            }
+        };
+        application.init(&self.context());
+        event_loop.run(move |event, target, control_flow| {
+            // a failed recovery leaves no device behind, only the exit to wait for; without a
+            // device there's no context to pass to `Application::shutdown`, so it's skipped on
+            // LoopDestroyed too. The application has already dropped its device resources in
+            // `on_device_lost`
+            if self.is_shut_down() {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+            self.input.handle_event(&event);
+            application.on_event(&self.context(), &event);
+            match event {
+                Event::WindowEvent { window_id, event } => {
+                    self.handle_window_event(&mut application, window_id, event);
+                    if self.windows.is_empty() {
+                        *control_flow = ControlFlow::Exit;
+                    }
+                }
+                Event::MainEventsCleared => {
+                    let now = Instant::now();
+                    if self.loop_timer.is_frame_due(now) {
+                        if let Err(e) = self.run_frame(&mut application, now, target) {
+                            println!(
+                                "ERROR: Failed to recover from a lost device or surface: {}",
+                                e
+                            );
+                            *control_flow = ControlFlow::Exit;
+                            return;
+                        }
+                    }
+                    if *control_flow != ControlFlow::Exit {
+                        *control_flow = match self.loop_timer.next_frame_at() {
+                            Some(at) => ControlFlow::WaitUntil(at),
+                            None => ControlFlow::Poll,
+                        };
+                    }
+                }
+                Event::LoopDestroyed => {
+                    application.shutdown(&self.context());
+                    // winit exits the process after this event without dropping the app
+                    self.shutdown();
                 }
                 _ => (),
-            });
+            }
+        });
+    }
+
+    fn run_frame<A: Application>(
+        &mut self,
+        application: &mut A,
+        now: Instant,
+        target: &EventLoopWindowTarget<()>,
+    ) -> Result<(), InitError> {
+        let frame_time = self.loop_timer.begin_frame(now);
+        if let Some(frame_time) = frame_time {
+            self.frame_stats.record(frame_time);
+        }
+        let steps = self
+            .loop_timer
+            .advance(frame_time.unwrap_or_else(|| self.loop_timer.timestep()));
+        let dt = self.loop_timer.timestep();
+        application.update_input_bindings(self.input.bindings_mut());
+        for _ in 0..steps {
+            application.update(&self.context(), dt);
+            self.input.end_frame();
+        }
+        self.draw_frames(application, self.loop_timer.alpha(), target)?;
+        self.update_titles(now);
+        Ok(())
+    }
+
+    fn update_titles(&mut self, now: Instant) {
+        if !crate::app::config::SHOW_FPS_IN_TITLE {
+            return;
+        }
+        if let Some(last) = self.last_title_update {
+            if now - last < crate::app::config::FPS_TITLE_INTERVAL {
+                return;
+            }
+        }
+        let summary = match self.frame_stats.summary() {
+            Some(summary) => summary,
+            None => return,
+        };
+        self.last_title_update = Some(now);
+        for window in self.windows.iter() {
+            window.window().set_title(&format!(
+                "{title} - {fps:.0} FPS ({average:.2} ms, p99 {p99:.2} ms)",
+                title = window.config().title,
+                fps = summary.fps(),
+                average = summary.average.as_secs_f64() * 1000.0,
+                p99 = summary.p99.as_secs_f64() * 1000.0
+            ));
+        }
+    }
+
+    fn draw_frames<A: Application>(
+        &mut self,
+        application: &mut A,
+        alpha: f64,
+        target: &EventLoopWindowTarget<()>,
+    ) -> Result<(), InitError> {
+        let mut device_lost = false;
+        let mut lost_surfaces = Vec::new();
+        let mut resized = Vec::new();
+        for window in self.windows.iter_mut() {
+            match window.recreate_swapchain_if_requested() {
+                Ok(true) => resized.push((window.id(), window.swapchain_info()._dimensions())),
+                Ok(false) => (),
+                Err(FrameError::DeviceLost) => {
+                    device_lost = true;
+                    break;
+                }
+                Err(FrameError::SurfaceLost) => lost_surfaces.push(window.id()),
+            }
+        }
+        if device_lost {
+            return self.recover_from_device_loss(application);
+        }
+        for (window_id, dimensions) in resized {
+            application.on_resize(&self.context(), window_id, dimensions);
+        }
+        let logical_device = self
+            .logical_device
+            .as_ref()
+            .expect("app has been shut down");
+        let physical_device_info = self
+            .physical_device_info
+            .as_ref()
+            .expect("app has been shut down");
+        for window in self.windows.iter_mut() {
+            // windows whose surface was lost while recreating still want a new swapchain, so
+            // `draw_frame` skips them
+            match window.draw_frame(
+                logical_device,
+                physical_device_info,
+                &self.frame_stats,
+                application,
+                alpha,
+            ) {
+                Ok(()) => (),
+                Err(FrameError::DeviceLost) => {
+                    device_lost = true;
+                    break;
+                }
+                Err(FrameError::SurfaceLost) => lost_surfaces.push(window.id()),
+            }
+        }
+        if device_lost {
+            return self.recover_from_device_loss(application);
+        }
+        for window_id in lost_surfaces {
+            self.recover_from_surface_loss(application, window_id, target)?;
+        }
+        Ok(())
+    }
+
+    fn recover_from_device_loss<A: Application>(
+        &mut self,
+        application: &mut A,
+    ) -> Result<(), InitError> {
+        println!("WARNING: Device lost! Recreating the device and swapchains...");
+        let windows = self.release_device(application);
+        self.recreate_device(application, windows)
+    }
+
+    // notifies the application, then destroys every swapchain and the device; returns the
+    // windows' configs and surfaces so they can be given new swapchains
+    fn release_device<A: Application>(
+        &mut self,
+        application: &mut A,
+    ) -> Vec<(WindowConfig, Arc<Surface<Window>>)> {
+        application.on_device_lost();
+        let windows = self
+            .windows
+            .drain()
+            .map(|window| {
+                let (config, surface, _) = window.into_parts();
+                (config, surface)
+            })
+            .collect();
+        // a lost device has nothing left to wait for, but the pipeline cache is host memory and
+        // can still be saved
+        if let Some(ref logical_device) = self.logical_device {
+            logical_device.save_pipeline_cache();
+        }
+        self.logical_device = None;
+        self.physical_device_info = None;
+        windows
+    }
+
+    // redoes device selection against the surfaces, since the GPU may have changed
+    fn recreate_device<A: Application>(
+        &mut self,
+        application: &mut A,
+        windows: Vec<(WindowConfig, Arc<Surface<Window>>)>,
+    ) -> Result<(), InitError> {
+        let surfaces: Vec<_> = windows.iter().map(|(_, s)| s.clone()).collect();
+        let instance = self.instance.as_ref().expect("app has been shut down");
+        let physical_device_info = init::pick_physical_device_info(
+            init::get_physical_device_infos(instance, &surfaces),
+            &self.device_features,
+            &self.device_selector,
+        )
+        .ok_or(InitError::NoSuitableDevice)?;
+        println!("New device:");
+        physical_device_info.print("    ", "    ");
+
+        let logical_device = LogicalDevice::new(&physical_device_info, &self.device_features)?;
+        for (config, surface) in windows {
+            self.windows.insert(AppWindow::new(
+                config,
+                surface,
+                &logical_device,
+                self.present_policy,
+            )?);
+        }
+        self.physical_device_info = Some(physical_device_info);
+        self.logical_device = Some(logical_device);
+
+        application.on_device_recreated(&self.context());
+        Ok(())
+    }
+
+    // the window owning a lost surface can't be given a new one, so it is closed and a new
+    // window is opened with the same config
+    fn recover_from_surface_loss<A: Application>(
+        &mut self,
+        application: &mut A,
+        window_id: WindowId,
+        target: &EventLoopWindowTarget<()>,
+    ) -> Result<(), InitError> {
+        println!("WARNING: Surface lost! Recreating the window...");
+        let config = match self.close_window(application, window_id) {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let instance = self.instance.as_ref().expect("app has been shut down");
+        let surface = init::create_surface(instance, target, &config)?;
+
+        let logical_device = self
+            .logical_device
+            .as_ref()
+            .expect("app has been shut down");
+        let present_family = logical_device.present_queue().family();
+        if !surface.is_supported(present_family).unwrap_or(false) {
+            println!("WARNING: The current device can't present to the new surface!");
+            let mut windows = self.release_device(application);
+            windows.push((config, surface));
+            return self.recreate_device(application, windows);
+        }
+
+        self.windows.insert(AppWindow::new(
+            config,
+            surface.clone(),
+            logical_device,
+            self.present_policy,
+        )?);
+        self.physical_device_info
+            .as_mut()
+            .expect("app has been shut down")
+            .add_surface(surface);
+        Ok(())
+    }
+
+    // returns the closed window's config, or `None` if there is no such window
+    fn close_window<A: Application>(
+        &mut self,
+        application: &mut A,
+        window_id: WindowId,
+    ) -> Option<WindowConfig> {
+        // the window's swapchain may still be in use by submitted frames
+        if let Some(logical_device) = self.logical_device.as_ref() {
+            wait_idle(logical_device);
+        }
+        if let Some(physical_device_info) = self.physical_device_info.as_mut() {
+            physical_device_info.remove_surface(window_id);
+        }
+        let (config, _, _) = self.windows.remove(window_id)?.into_parts();
+        application.on_window_closed(&self.context(), window_id);
+        Some(config)
+    }
+
+    fn handle_window_event<A: Application>(
+        &mut self,
+        application: &mut A,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        if let WindowEvent::CloseRequested = event {
+            self.close_window(application, window_id);
+            return;
+        }
+        let window = match self.windows.get_mut(window_id) {
+            Some(window) => window,
+            None => return,
+        };
+        if let Some(debug_ui) = window.debug_ui_mut() {
+            debug_ui.handle_event(&event);
+        }
+        match event {
+            WindowEvent::Resized(_) => {
+                window.request_swapchain_recreation();
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                println!("Scale factor changed to {}", scale_factor);
+                window.request_swapchain_recreation();
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } if Some(key) == config::SCREENSHOT_KEY => {
+                window.request_screenshot();
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } if Some(key) == config::DEBUG_UI_KEY => {
+                if let Some(debug_ui) = window.debug_ui_mut() {
+                    debug_ui.toggle_visible();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Drop for App {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn wait_idle(logical_device: &LogicalDevice) {
+    if let Err(e) = logical_device.wait_idle() {
+        println!("WARNING: Failed to wait for the device to be idle: {:?}", e);
+    }
+}
+
+fn report_alive<T>(kind: &str, refs: &[Weak<T>]) {
+    let alive = refs.iter().filter(|r| r.strong_count() > 0).count();
+    if alive > 0 {
+        println!(
+            "WARNING: {alive} {kind}(s) still alive after shutdown; something outside the app \
+             still holds a reference",
+            alive = alive,
+            kind = kind
+        );
     }
 }
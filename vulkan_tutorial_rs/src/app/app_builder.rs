@@ -0,0 +1,202 @@
+use crate::app::init;
+use crate::app::{
+    App, AppWindow, FrameStats, InitError, Input, InputBindings, LogicalDevice, LoopTimer,
+    PhysicalDeviceInfo, PresentPolicy, WindowConfig, WindowRegistry,
+};
+
+use vulkano::device::Features;
+
+use winit::event_loop::EventLoop;
+
+use std::cmp::Ordering;
+
+// orders two candidate devices; the greatest valid device is picked
+pub type DeviceSelector = Box<dyn Fn(&PhysicalDeviceInfo, &PhysicalDeviceInfo) -> Ordering>;
+
+pub struct AppBuilder {
+    windows: Vec<WindowConfig>,
+    validation: bool,
+    device_selector: DeviceSelector,
+    device_features: Features,
+    present_policy: PresentPolicy,
+    update_rate: u32,
+    frame_rate_cap: Option<u32>,
+    input_bindings: InputBindings,
+    headless: bool,
+}
+
+impl Default for AppBuilder {
+    fn default() -> AppBuilder {
+        AppBuilder::new()
+    }
+}
+
+impl AppBuilder {
+    pub fn new() -> AppBuilder {
+        AppBuilder {
+            windows: Vec::new(),
+            validation: crate::app::config::ENABLE_VALIDATION_LAYERS,
+            device_selector: Box::new(|a, b| a.cmp(b)),
+            device_features: Features::none(),
+            present_policy: PresentPolicy::default(),
+            update_rate: crate::app::config::UPDATE_RATE,
+            frame_rate_cap: crate::app::config::FRAME_RATE_CAP,
+            input_bindings: crate::app::config::input_bindings(),
+            headless: false,
+        }
+    }
+
+    // may be called several times to open several windows; if never called, the windows from
+    // `config::window_configs` are opened
+    pub fn with_window(mut self, window: WindowConfig) -> AppBuilder {
+        self.windows.push(window);
+        self
+    }
+
+    pub fn with_validation(mut self, validation: bool) -> AppBuilder {
+        self.validation = validation;
+        self
+    }
+
+    pub fn with_device_selector<F>(mut self, selector: F) -> AppBuilder
+    where
+        F: Fn(&PhysicalDeviceInfo, &PhysicalDeviceInfo) -> Ordering + 'static,
+    {
+        self.device_selector = Box::new(selector);
+        self
+    }
+
+    // devices that don't support all of these features are not considered
+    pub fn with_device_features(mut self, features: Features) -> AppBuilder {
+        self.device_features = features;
+        self
+    }
+
+    pub fn with_present_policy(mut self, present_policy: PresentPolicy) -> AppBuilder {
+        self.present_policy = present_policy;
+        self
+    }
+
+    // fixed updates per second
+    pub fn with_update_rate(mut self, update_rate: u32) -> AppBuilder {
+        self.update_rate = update_rate;
+        self
+    }
+
+    pub fn with_frame_rate_cap(mut self, frame_rate_cap: Option<u32>) -> AppBuilder {
+        self.frame_rate_cap = frame_rate_cap;
+        self
+    }
+
+    // replaces the bindings from `config::input_bindings`
+    pub fn with_input_bindings(mut self, input_bindings: InputBindings) -> AppBuilder {
+        self.input_bindings = input_bindings;
+        self
+    }
+
+    // no event loop, windows or swapchains; only an instance and a logical device
+    pub fn headless(mut self) -> AppBuilder {
+        self.headless = true;
+        self
+    }
+
+    pub fn build(self) -> Result<App, InitError> {
+        println!("Initialising app...");
+
+        let app_info = init::create_app_info();
+        println!("App info:\n\t{:?}", app_info);
+
+        let supported_instance_extensions = init::supported_instance_extensions()?;
+        println!(
+            "Supported instance extensions:\n\t{:?}",
+            supported_instance_extensions
+        );
+
+        let required_instance_extensions =
+            init::required_instance_extensions(self.headless, self.validation);
+        println!(
+            "Required instance extensions:\n\t{:?}",
+            required_instance_extensions
+        );
+
+        let instance =
+            init::create_instance(&app_info, &required_instance_extensions, self.validation)?;
+        println!("Instance created");
+
+        let debug_callback = init::setup_debug_callback(&instance, self.validation);
+        println!("Debug callback set up");
+
+        let window_configs = if self.headless {
+            Vec::new()
+        } else if self.windows.is_empty() {
+            crate::app::config::window_configs()
+        } else {
+            self.windows
+        };
+
+        let event_loop = if self.headless {
+            None
+        } else {
+            let event_loop = EventLoop::new();
+            println!("Event loop created");
+            Some(event_loop)
+        };
+
+        let surfaces = match event_loop {
+            Some(ref event_loop) => window_configs
+                .iter()
+                .map(|c| init::create_surface(&instance, event_loop, c))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        println!("{} surface(s) created", surfaces.len());
+
+        let physical_device_infos = init::get_physical_device_infos(&instance, &surfaces);
+        println!("Physical devices:");
+        for d in physical_device_infos.iter() {
+            d.print("    ", "    ");
+        }
+
+        let physical_device_info = init::pick_physical_device_info(
+            physical_device_infos,
+            &self.device_features,
+            &self.device_selector,
+        )
+        .ok_or(InitError::NoSuitableDevice)?;
+        println!("Best device:");
+        physical_device_info.print("    ", "    ");
+
+        let logical_device = LogicalDevice::new(&physical_device_info, &self.device_features)?;
+
+        let mut windows = WindowRegistry::new();
+        for (config, surface) in window_configs.into_iter().zip(surfaces) {
+            windows.insert(AppWindow::new(
+                config,
+                surface,
+                &logical_device,
+                self.present_policy,
+            )?);
+        }
+        println!("{} swapchain(s) created", windows.len());
+
+        Ok(App {
+            instance: Some(instance),
+            debug_callback,
+            event_loop,
+            physical_device_info: Some(physical_device_info),
+            logical_device: Some(logical_device),
+            windows,
+            device_selector: self.device_selector,
+            device_features: self.device_features,
+            present_policy: self.present_policy,
+            loop_timer: LoopTimer::new(
+                self.update_rate,
+                crate::app::config::MAX_UPDATES_PER_FRAME,
+                self.frame_rate_cap,
+            ),
+            frame_stats: FrameStats::new(crate::app::config::FRAME_HISTORY_LENGTH),
+            input: Input::new(self.input_bindings),
+            last_title_update: None,
+        })
+    }
+}
@@ -0,0 +1,313 @@
+use crate::app::config;
+use crate::app::debug_ui::panels::PanelData;
+use crate::app::frame::FrameCommandBuffer;
+use crate::app::paths;
+use crate::app::screenshot::ScreenshotCapture;
+use crate::app::{
+    Application, DebugUi, Frame, FrameStats, LogicalDevice, PhysicalDeviceInfo, PresentPolicy,
+    RenderTarget, SwapchainInfo, WindowConfig,
+};
+
+use vulkano::command_buffer::AutoCommandBuffer;
+use vulkano::swapchain::{AcquireError, Surface, SwapchainCreationError};
+use vulkano::sync::{FlushError, GpuFuture};
+
+use winit::window::{Window, WindowId};
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    DeviceLost,
+    SurfaceLost,
+}
+
+pub struct AppWindow {
+    config: WindowConfig,
+    surface: Arc<Surface<Window>>,
+    swapchain_info: SwapchainInfo,
+    recreate_swapchain: bool,
+    screenshot_requested: bool,
+    debug_ui: Option<DebugUi>,
+    previous_frame_end: Option<Box<dyn GpuFuture>>,
+}
+
+impl AppWindow {
+    pub fn new(
+        config: WindowConfig,
+        surface: Arc<Surface<Window>>,
+        logical_device: &LogicalDevice,
+        present_policy: PresentPolicy,
+    ) -> Result<AppWindow, SwapchainCreationError> {
+        let swapchain_info = SwapchainInfo::new(
+            &surface,
+            &logical_device.device().physical_device(),
+            logical_device,
+            surface.window().inner_size().into(),
+            config.transparent,
+            present_policy,
+        )?;
+        let debug_ui = if config::DEBUG_UI {
+            Some(DebugUi::new(
+                logical_device,
+                &swapchain_info,
+                surface.window().scale_factor(),
+            ))
+        } else {
+            None
+        };
+        Ok(AppWindow {
+            config,
+            surface,
+            swapchain_info,
+            recreate_swapchain: false,
+            screenshot_requested: false,
+            debug_ui,
+            previous_frame_end: Some(
+                Box::new(vulkano::sync::now(logical_device.device().clone())) as Box<_>,
+            ),
+        })
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.surface.window().id()
+    }
+
+    pub fn config(&self) -> &WindowConfig {
+        &self.config
+    }
+
+    pub fn _surface(&self) -> &Arc<Surface<Window>> {
+        &self.surface
+    }
+
+    pub fn window(&self) -> &Window {
+        self.surface.window()
+    }
+
+    pub fn swapchain_info(&self) -> &SwapchainInfo {
+        &self.swapchain_info
+    }
+
+    // `None` unless `config::DEBUG_UI` is set
+    pub fn debug_ui(&self) -> Option<&DebugUi> {
+        self.debug_ui.as_ref()
+    }
+
+    pub fn debug_ui_mut(&mut self) -> Option<&mut DebugUi> {
+        self.debug_ui.as_mut()
+    }
+
+    // drops any pending frame future so nothing but the returned values keeps the swapchain
+    // or surface alive
+    pub fn into_parts(self) -> (WindowConfig, Arc<Surface<Window>>, SwapchainInfo) {
+        (self.config, self.surface, self.swapchain_info)
+    }
+
+    pub fn request_swapchain_recreation(&mut self) {
+        self.recreate_swapchain = true;
+    }
+
+    // the next frame drawn is saved to `config::SCREENSHOT_DIRECTORY`
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    // returns whether the swapchain was recreated; if the window currently has no area, or
+    // recreation fails for a reason other than a lost device or surface, the request is kept for
+    // a later frame
+    pub fn recreate_swapchain_if_requested(&mut self) -> Result<bool, FrameError> {
+        if !self.recreate_swapchain {
+            return Ok(false);
+        }
+        match self
+            .swapchain_info
+            .recreate(self.window().inner_size().into())
+        {
+            Ok(()) => {
+                self.recreate_swapchain = false;
+                if let Some(ref mut debug_ui) = self.debug_ui {
+                    debug_ui.recreate_framebuffers(&self.swapchain_info);
+                }
+                Ok(true)
+            }
+            Err(SwapchainCreationError::UnsupportedDimensions) => Ok(false),
+            Err(SwapchainCreationError::DeviceLost) => Err(FrameError::DeviceLost),
+            Err(SwapchainCreationError::SurfaceLost) => Err(FrameError::SurfaceLost),
+            Err(e) => {
+                println!("WARNING: Failed to recreate swapchain: {:?}", e);
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn draw_frame<A: Application>(
+        &mut self,
+        logical_device: &LogicalDevice,
+        physical_device_info: &PhysicalDeviceInfo,
+        frame_stats: &FrameStats,
+        application: &mut A,
+        alpha: f64,
+    ) -> Result<(), FrameError> {
+        if let Some(previous_frame_end) = self.previous_frame_end.as_mut() {
+            previous_frame_end.cleanup_finished();
+        }
+        if self.recreate_swapchain {
+            // still waiting for the window to have an area again
+            return Ok(());
+        }
+
+        let swapchain = self.swapchain_info._swapchain().clone();
+        let (image_num, suboptimal, acquire_future) =
+            match vulkano::swapchain::acquire_next_image(swapchain.clone(), None) {
+                Ok(r) => r,
+                Err(AcquireError::OutOfDate) => {
+                    self.recreate_swapchain = true;
+                    return Ok(());
+                }
+                Err(AcquireError::DeviceLost) => return Err(FrameError::DeviceLost),
+                Err(AcquireError::SurfaceLost) => return Err(FrameError::SurfaceLost),
+                Err(e) => {
+                    println!(
+                        "WARNING: Failed to acquire next image, skipping frame: {:?}",
+                        e
+                    );
+                    return Ok(());
+                }
+            };
+        if suboptimal {
+            self.recreate_swapchain = true;
+        }
+
+        let mut frame = Frame::new(
+            logical_device,
+            self.id(),
+            &self.swapchain_info,
+            image_num,
+            alpha,
+        );
+        application.render(&mut frame);
+        if let Some(ref mut debug_ui) = self.debug_ui {
+            debug_ui.draw(
+                &mut frame,
+                &PanelData {
+                    physical_device_info,
+                    swapchain_info: &self.swapchain_info,
+                    frame_stats,
+                },
+            );
+        }
+
+        let mut future = Box::new(
+            self.previous_frame_end
+                .take()
+                .unwrap_or_else(|| {
+                    Box::new(vulkano::sync::now(logical_device.device().clone())) as Box<_>
+                })
+                .join(acquire_future),
+        ) as Box<dyn GpuFuture>;
+        let (mut command_buffers, dependencies, screenshot_requested, submitted) =
+            frame.into_parts();
+        for dependency in dependencies {
+            future = Box::new(future.join(dependency));
+        }
+        let screenshot = if self.screenshot_requested || screenshot_requested {
+            self.record_screenshot(logical_device, image_num)
+                .map(|(capture, command_buffer)| {
+                    command_buffers.push(FrameCommandBuffer::Auto(command_buffer));
+                    capture
+                })
+        } else {
+            None
+        };
+        // kept until the capture is queued, so a frame that fails to flush takes it again;
+        // captures that couldn't be recorded aren't retried
+        self.screenshot_requested = screenshot.is_some();
+        let queue = logical_device.graphics_queue();
+        for command_buffer in command_buffers {
+            let executed = match command_buffer {
+                FrameCommandBuffer::Auto(command_buffer) => future
+                    .then_execute(queue.clone(), command_buffer)
+                    .map(|future| Box::new(future) as Box<dyn GpuFuture>),
+                FrameCommandBuffer::Raw(command_buffer) => future
+                    .then_execute(queue.clone(), command_buffer)
+                    .map(|future| Box::new(future) as Box<dyn GpuFuture>),
+            };
+            future = match executed {
+                Ok(future) => future,
+                Err(e) => {
+                    println!(
+                        "WARNING: Failed to execute command buffer, skipping frame: {:?}",
+                        e
+                    );
+                    // the acquired image is never presented; recreating the swapchain releases it
+                    self.recreate_swapchain = true;
+                    self.previous_frame_end = Some(Box::new(vulkano::sync::now(
+                        logical_device.device().clone(),
+                    )) as Box<_>);
+                    return Ok(());
+                }
+            };
+        }
+        let future = future
+            .then_swapchain_present(logical_device.present_queue().clone(), swapchain, image_num)
+            .then_signal_fence_and_flush();
+
+        self.previous_frame_end = match future {
+            Ok(future) => {
+                submitted.store(true, Ordering::Relaxed);
+                self.screenshot_requested = false;
+                if let Some(screenshot) = screenshot {
+                    // screenshots are rare enough that stalling for this frame is fine
+                    match future.wait(None) {
+                        Ok(()) => save_screenshot(&screenshot),
+                        Err(e) => println!("WARNING: Failed to take screenshot: {:?}", e),
+                    }
+                }
+                Some(Box::new(future) as Box<_>)
+            }
+            Err(FlushError::OutOfDate) => {
+                self.recreate_swapchain = true;
+                Some(Box::new(vulkano::sync::now(logical_device.device().clone())) as Box<_>)
+            }
+            Err(FlushError::DeviceLost) => return Err(FrameError::DeviceLost),
+            Err(FlushError::SurfaceLost) => return Err(FrameError::SurfaceLost),
+            Err(e) => {
+                println!("Failed to flush future: {:?}", e);
+                Some(Box::new(vulkano::sync::now(logical_device.device().clone())) as Box<_>)
+            }
+        };
+        Ok(())
+    }
+
+    fn record_screenshot(
+        &self,
+        logical_device: &LogicalDevice,
+        image_num: usize,
+    ) -> Option<(ScreenshotCapture, AutoCommandBuffer)> {
+        if !self.swapchain_info.supports_capture() {
+            println!(
+                "WARNING: The surface doesn't allow copying from its images; no screenshot taken"
+            );
+            return None;
+        }
+        ScreenshotCapture::record(
+            logical_device.device(),
+            logical_device.graphics_queue().family(),
+            self.swapchain_info._images()[image_num].clone(),
+            *self.swapchain_info._format(),
+            self.swapchain_info._dimensions(),
+            self.swapchain_info.is_opaque(),
+        )
+        .map_err(|e| println!("WARNING: Failed to take screenshot: {}", e))
+        .ok()
+    }
+}
+
+fn save_screenshot(screenshot: &ScreenshotCapture) {
+    match screenshot.save(&paths::data_path(config::SCREENSHOT_DIRECTORY)) {
+        Ok(path) => println!("Saved screenshot to {}", path.display()),
+        Err(e) => println!("WARNING: Failed to save screenshot: {}", e),
+    }
+}
@@ -0,0 +1,46 @@
+use crate::app::{Context, Frame, InputBindings};
+
+use winit::event::Event;
+use winit::window::WindowId;
+
+use std::time::Duration;
+
+// hooks driven by `App::run`; every method has an empty default so implementors only override
+// what they need
+pub trait Application {
+    // called once before the first event is handled
+    fn init(&mut self, _context: &Context) {}
+
+    // called for every winit event before the app handles it
+    fn on_event(&mut self, _context: &Context, _event: &Event<()>) {}
+
+    // called once per frame before the updates, with the bindings `Context::input` maps
+    // actions and axes through; change them here to rebind, e.g. from a settings menu
+    fn update_input_bindings(&mut self, _bindings: &mut InputBindings) {}
+
+    // called zero or more times per frame, always advancing by the fixed timestep
+    fn update(&mut self, _context: &Context, _dt: Duration) {}
+
+    // called once per window per loop iteration; command buffers submitted to the frame are
+    // executed on the graphics queue before its image is presented
+    fn render(&mut self, _frame: &mut Frame) {}
+
+    // called after a window's swapchain (and so its images) has been recreated
+    fn on_resize(&mut self, _context: &Context, _window_id: WindowId, _dimensions: [u32; 2]) {}
+
+    // called after a window has been closed and its swapchain destroyed; a window whose surface
+    // was lost is closed and reopened under a new id
+    fn on_window_closed(&mut self, _context: &Context, _window_id: WindowId) {}
+
+    // called before the device is destroyed because it was lost (or, rarely, can't present to a
+    // recreated window); drop everything created from it
+    fn on_device_lost(&mut self) {}
+
+    // called once a new device (possibly a different GPU) and swapchains have been created after
+    // a device loss; recreate and re-upload device resources here
+    fn on_device_recreated(&mut self, _context: &Context) {}
+
+    // called once when the event loop is about to exit; skipped if the app is exiting because a
+    // lost device couldn't be recreated, in which case `on_device_lost` was the last call
+    fn shutdown(&mut self, _context: &Context) {}
+}
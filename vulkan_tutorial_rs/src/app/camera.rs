@@ -0,0 +1,165 @@
+pub mod fly;
+pub mod orbit;
+
+use cgmath::{Angle, InnerSpace, Matrix4, Point3, Rad, Vector3, Vector4};
+
+// cameras never roll, so this is always up on screen
+pub const UP: Vector3<f32> = Vector3 {
+    x: 0.0,
+    y: 1.0,
+    z: 0.0,
+};
+
+// just short of straight up or down, where the view direction would be parallel to `UP`
+const MAX_PITCH: Rad<f32> = Rad(1.553_343);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    // `fovy` is the vertical field of view
+    Perspective { fovy: Rad<f32>, near: f32, far: f32 },
+    // `height` is the visible height in world units; the width follows the aspect ratio
+    Orthographic { height: f32, near: f32, far: f32 },
+}
+
+impl Projection {
+    pub fn matrix(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        match *self {
+            Projection::Perspective { fovy, near, far } => {
+                perspective(fovy, aspect_ratio, near, far)
+            }
+            Projection::Orthographic { height, near, far } => {
+                let top = height / 2.0;
+                let right = top * aspect_ratio;
+                orthographic(-right, right, -top, top, near, far)
+            }
+        }
+    }
+}
+
+// these map right-handed view space, looking down -Z with +Y up, to Vulkan's clip space, where
+// +Y points down the screen and depth runs from 0 at `near` to 1 at `far`. cgmath's own
+// `perspective` and `ortho` follow OpenGL's conventions instead
+pub fn perspective(fovy: Rad<f32>, aspect_ratio: f32, near: f32, far: f32) -> Matrix4<f32> {
+    let focal_length = 1.0 / (fovy / 2.0).tan();
+    Matrix4::from_cols(
+        Vector4::new(focal_length / aspect_ratio, 0.0, 0.0, 0.0),
+        Vector4::new(0.0, -focal_length, 0.0, 0.0),
+        Vector4::new(0.0, 0.0, far / (near - far), -1.0),
+        Vector4::new(0.0, 0.0, near * far / (near - far), 0.0),
+    )
+}
+
+pub fn orthographic(
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+    near: f32,
+    far: f32,
+) -> Matrix4<f32> {
+    Matrix4::from_cols(
+        Vector4::new(2.0 / (right - left), 0.0, 0.0, 0.0),
+        Vector4::new(0.0, -2.0 / (top - bottom), 0.0, 0.0),
+        Vector4::new(0.0, 0.0, -1.0 / (far - near), 0.0),
+        Vector4::new(
+            -(right + left) / (right - left),
+            (top + bottom) / (top - bottom),
+            -near / (far - near),
+            1.0,
+        ),
+    )
+}
+
+// a position and view direction; with zero yaw and pitch it looks down -Z. Positive yaw turns
+// left and positive pitch looks up
+#[derive(Clone, Debug)]
+pub struct Camera {
+    pub position: Point3<f32>,
+    pub projection: Projection,
+    yaw: Rad<f32>,
+    pitch: Rad<f32>,
+    aspect_ratio: f32,
+}
+
+impl Camera {
+    pub fn new(position: Point3<f32>, projection: Projection) -> Camera {
+        Camera {
+            position,
+            projection,
+            yaw: Rad(0.0),
+            pitch: Rad(0.0),
+            aspect_ratio: 1.0,
+        }
+    }
+
+    pub fn yaw(&self) -> Rad<f32> {
+        self.yaw
+    }
+
+    pub fn pitch(&self) -> Rad<f32> {
+        self.pitch
+    }
+
+    // `pitch` is clamped short of straight up or down
+    pub fn set_orientation(&mut self, yaw: Rad<f32>, pitch: Rad<f32>) {
+        self.yaw = yaw.normalize();
+        self.pitch = clamp_pitch(pitch);
+    }
+
+    // does nothing if `target` is the camera's position
+    pub fn look_at(&mut self, target: Point3<f32>) {
+        let direction = target - self.position;
+        if direction.magnitude2() == 0.0 {
+            return;
+        }
+        let horizontal = (direction.x * direction.x + direction.z * direction.z).sqrt();
+        self.set_orientation(
+            Rad::atan2(-direction.x, -direction.z),
+            Rad::atan2(direction.y, horizontal),
+        );
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    // call with `SwapchainInfo::_dimensions` whenever the swapchain is recreated; zero-sized
+    // (minimised) windows keep the previous aspect ratio
+    pub fn resize(&mut self, dimensions: [u32; 2]) {
+        if dimensions[0] > 0 && dimensions[1] > 0 {
+            self.aspect_ratio = dimensions[0] as f32 / dimensions[1] as f32;
+        }
+    }
+
+    pub fn forward(&self) -> Vector3<f32> {
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        let (pitch_sin, pitch_cos) = self.pitch.sin_cos();
+        Vector3::new(-yaw_sin * pitch_cos, pitch_sin, -yaw_cos * pitch_cos)
+    }
+
+    // always horizontal
+    pub fn right(&self) -> Vector3<f32> {
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        Vector3::new(yaw_cos, 0.0, -yaw_sin)
+    }
+
+    pub fn up(&self) -> Vector3<f32> {
+        self.right().cross(self.forward())
+    }
+
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_dir(self.position, self.forward(), UP)
+    }
+
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        self.projection.matrix(self.aspect_ratio)
+    }
+
+    pub fn view_projection(&self) -> Matrix4<f32> {
+        self.projection_matrix() * self.view()
+    }
+}
+
+fn clamp_pitch(pitch: Rad<f32>) -> Rad<f32> {
+    Rad(pitch.0.clamp(-MAX_PITCH.0, MAX_PITCH.0))
+}
@@ -0,0 +1,38 @@
+use crate::app::camera::{Camera, UP};
+use crate::app::config;
+use crate::app::Input;
+
+use cgmath::{InnerSpace, Rad, Vector3, Zero};
+
+use std::time::Duration;
+
+// moves a camera freely: the `move_y` axis along the view direction, `move_x` sideways and
+// `move_z` straight up and down. Moving the mouse with the `look` action held looks around, by
+// the `look_x` and `look_y` axes
+pub struct FlyController {
+    // world units per second
+    pub speed: f32,
+}
+
+impl FlyController {
+    pub fn new(speed: f32) -> FlyController {
+        FlyController { speed }
+    }
+
+    // applies the mouse movement since the last update, then moves for `dt`
+    pub fn update(&self, input: &Input, camera: &mut Camera, dt: Duration) {
+        if input.is_held("look") {
+            camera.set_orientation(
+                camera.yaw() - Rad(input.axis("look_x") * config::MOUSE_SENSITIVITY),
+                camera.pitch() - Rad(input.axis("look_y") * config::MOUSE_SENSITIVITY),
+            );
+        }
+
+        let direction = camera.forward() * input.axis("move_y")
+            + camera.right() * input.axis("move_x")
+            + UP * input.axis("move_z");
+        if direction != Vector3::zero() {
+            camera.position += direction.normalize() * self.speed * dt.as_secs_f32();
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::app::camera::{self, Camera};
+use crate::app::config;
+use crate::app::Input;
+
+use cgmath::{Point3, Rad};
+
+// keeps a camera looking at `target` from `distance` away. Moving the mouse with the `select`
+// action held turns the camera around the target, by the `look_x` and `look_y` axes, and the
+// `zoom` axis zooms
+pub struct OrbitController {
+    pub target: Point3<f32>,
+    distance: f32,
+    yaw: Rad<f32>,
+    pitch: Rad<f32>,
+}
+
+impl OrbitController {
+    pub fn new(target: Point3<f32>, distance: f32) -> OrbitController {
+        OrbitController {
+            target,
+            distance: clamp_distance(distance),
+            yaw: Rad(0.0),
+            pitch: Rad(0.0),
+        }
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn yaw(&self) -> Rad<f32> {
+        self.yaw
+    }
+
+    pub fn pitch(&self) -> Rad<f32> {
+        self.pitch
+    }
+
+    // by a mouse movement of `dx` and `dy` pixels; dragging right brings the target's left side
+    // into view and dragging down its top
+    pub fn rotate(&mut self, dx: f32, dy: f32) {
+        self.yaw -= Rad(dx * config::MOUSE_SENSITIVITY);
+        self.pitch = camera::clamp_pitch(self.pitch - Rad(dy * config::MOUSE_SENSITIVITY));
+    }
+
+    // positive `lines` move closer
+    pub fn zoom(&mut self, lines: f32) {
+        self.distance = clamp_distance(self.distance * (1.0 - config::ORBIT_ZOOM_STEP).powf(lines));
+    }
+
+    // applies the input since the last update, then places the camera
+    pub fn update(&mut self, input: &Input, camera: &mut Camera) {
+        if input.is_held("select") {
+            self.rotate(input.axis("look_x"), input.axis("look_y"));
+        }
+        self.zoom(input.axis("zoom"));
+        camera.set_orientation(self.yaw, self.pitch);
+        camera.position = self.target - camera.forward() * self.distance;
+    }
+}
+
+fn clamp_distance(distance: f32) -> f32 {
+    distance.clamp(config::ORBIT_MIN_DISTANCE, config::ORBIT_MAX_DISTANCE)
+}
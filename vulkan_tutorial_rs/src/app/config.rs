@@ -1,10 +1,119 @@
+use crate::app::input::bindings::{AxisBinding, ButtonBinding, InputBindings};
+use crate::app::WindowConfig;
+
 use vulkano::device::DeviceExtensions;
+use vulkano::format::Format;
+
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use std::time::Duration;
 
 pub const VALIDATION_LAYERS: &[&str] = &["VK_LAYER_LUNARG_standard_validation"];
 
+pub const WINDOW_TITLE: &str = "Vulkan Tutorial";
+
 pub const DEFAULT_WIDTH: u32 = 1024;
 pub const DEFAULT_HEIGHT: u32 = 768;
 
+pub const MIN_WIDTH: u32 = 320;
+pub const MIN_HEIGHT: u32 = 240;
+
+pub const RESIZABLE: bool = true;
+pub const DECORATIONS: bool = true;
+
+// requires a compositor that honours the swapchain's alpha; falls back to opaque otherwise
+pub const TRANSPARENT: bool = false;
+
+// relative to `paths::resource_path`
+pub const WINDOW_ICON_PATH: Option<&str> = Some("assets/icon.png");
+
+// the directory under the user's data directory that `paths::data_path` files go in
+pub const DATA_DIRECTORY_NAME: &str = "vulkan_tutorial_rs";
+
+// position of the window's top-left corner relative to the chosen monitor, in physical pixels;
+// `None` centres the window on that monitor
+pub const WINDOW_POSITION: Option<[i32; 2]> = None;
+
+// index into the event loop's available monitors; `None` uses the primary monitor
+pub const WINDOW_MONITOR: Option<usize> = None;
+
+// in order of preference; the first one the device supports as a depth attachment is used
+pub const DEPTH_FORMATS: &[Format] = &[
+    Format::D32Sfloat,
+    Format::D32Sfloat_S8Uint,
+    Format::D24Unorm_S8Uint,
+];
+
+// MSAA sample count; lowered to what the device supports, 1 disables MSAA
+pub const MSAA_SAMPLES: u32 = 4;
+
+// relative to `paths::resource_path`
+pub const SHADER_DIRECTORY: &str = "shaders";
+// watch `SHADER_DIRECTORY` and rebuild pipelines when a shader they use changes
+pub const SHADER_HOT_RELOAD: bool = cfg!(debug_assertions);
+pub const SHADER_RELOAD_DELAY: Duration = Duration::from_millis(200);
+
+// pipeline caches are loaded from and saved to this directory, relative to `paths::data_path`,
+// one file per device and driver; `None` keeps them in memory only
+pub const PIPELINE_CACHE_DIRECTORY: Option<&str> = Some("pipeline_cache");
+
+// pressing this key in a window saves its next frame as a PNG; `None` disables the binding
+pub const SCREENSHOT_KEY: Option<VirtualKeyCode> = Some(VirtualKeyCode::F12);
+// relative to `paths::data_path`
+pub const SCREENSHOT_DIRECTORY: &str = "screenshots";
+
+// an egui overlay with device, swapchain and frame timing panels, shown and hidden with the
+// key; `DEBUG_UI_KEY` of `None` leaves it hidden
+pub const DEBUG_UI: bool = cfg!(debug_assertions);
+pub const DEBUG_UI_KEY: Option<VirtualKeyCode> = Some(VirtualKeyCode::F1);
+
+// reference images for the golden image tests, relative to `paths::resource_path`, and where
+// mismatches are written for inspection, relative to `paths::data_path`
+pub const GOLDEN_DIRECTORY: &str = "tests/golden";
+pub const GOLDEN_OUTPUT_DIRECTORY: &str = "golden";
+
+// frames are profiled with this many frames in flight before their timestamps are read back
+pub const PROFILER_LATENCY: usize = 4;
+// regions per frame with GPU timings; later ones are only timed on the CPU
+pub const PROFILER_MAX_REGIONS: u32 = 32;
+pub const PROFILER_HISTORY_LENGTH: usize = 120;
+pub const SHOW_PROFILER_OVERLAY: bool = cfg!(debug_assertions);
+// the time the overlay's bars are scaled to; longer ones are drawn red
+pub const PROFILER_OVERLAY_BUDGET: Duration = Duration::from_micros(16_667);
+
+// pixel scrolling (e.g. from touchpads) is converted to lines of mouse wheel scrolling at this
+// rate, and the debug UI scrolls this many points per line
+pub const PIXELS_PER_SCROLL_LINE: f32 = 24.0;
+
+// camera controls; mouse movement turns cameras this many radians per pixel of raw motion
+pub const MOUSE_SENSITIVITY: f32 = 0.005;
+// world units per second
+pub const FLY_SPEED: f32 = 2.0;
+// each line of mouse wheel scrolling moves an orbiting camera this fraction of its distance
+pub const ORBIT_ZOOM_STEP: f32 = 0.1;
+pub const ORBIT_MIN_DISTANCE: f32 = 0.1;
+pub const ORBIT_MAX_DISTANCE: f32 = 100.0;
+
+// fixed updates per second
+pub const UPDATE_RATE: u32 = 60;
+// updates beyond this in one frame are dropped instead of being caught up
+pub const MAX_UPDATES_PER_FRAME: u32 = 8;
+// `None` renders as fast as the present mode allows
+pub const FRAME_RATE_CAP: Option<u32> = None;
+pub const FRAME_HISTORY_LENGTH: usize = 240;
+pub const SHOW_FPS_IN_TITLE: bool = true;
+pub const FPS_TITLE_INTERVAL: Duration = Duration::from_millis(500);
+
+// the uploader's ring of staging buffers; larger uploads get a buffer of their own
+pub const STAGING_BUFFER_COUNT: usize = 4;
+pub const STAGING_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+// enables the `sampler_anisotropy` feature on devices that support it, even if the app doesn't
+// require it
+pub const ENABLE_SAMPLER_ANISOTROPY: bool = true;
+// clamped to the device's limit; only used when the `sampler_anisotropy` feature is enabled
+pub const MAX_ANISOTROPY: f32 = 16.0;
+
 #[cfg(all(debug_assertions))]
 pub const ENABLE_VALIDATION_LAYERS: bool = true;
 #[cfg(not(debug_assertions))]
@@ -16,3 +125,40 @@ pub fn required_device_extensions() -> DeviceExtensions {
         ..DeviceExtensions::none()
     }
 }
+
+// one OS window (with its own surface and swapchain) is opened per entry
+pub fn window_configs() -> Vec<WindowConfig> {
+    vec![WindowConfig::default()]
+}
+
+// the actions and axes `Context::input` starts with, unless `AppBuilder::with_input_bindings`
+// replaces them; see `Application::update_input_bindings` for changing them while running
+pub fn input_bindings() -> InputBindings {
+    let key = ButtonBinding::Key;
+    let mut bindings = InputBindings::new();
+    bindings.bind_action("confirm", key(VirtualKeyCode::Return));
+    bindings.bind_action("cancel", key(VirtualKeyCode::Escape));
+    // held to drag an orbiting camera around its target
+    bindings.bind_action("select", ButtonBinding::Mouse(MouseButton::Left));
+    // held to look around with a flying camera
+    bindings.bind_action("look", ButtonBinding::Mouse(MouseButton::Right));
+    for &(axis, positive, negative) in &[
+        ("move_x", VirtualKeyCode::D, VirtualKeyCode::A),
+        ("move_x", VirtualKeyCode::Right, VirtualKeyCode::Left),
+        ("move_y", VirtualKeyCode::W, VirtualKeyCode::S),
+        ("move_y", VirtualKeyCode::Up, VirtualKeyCode::Down),
+        ("move_z", VirtualKeyCode::Space, VirtualKeyCode::LControl),
+    ] {
+        bindings.bind_axis(
+            axis,
+            AxisBinding::Buttons {
+                positive: key(positive),
+                negative: key(negative),
+            },
+        );
+    }
+    bindings.bind_axis("look_x", AxisBinding::MouseX);
+    bindings.bind_axis("look_y", AxisBinding::MouseY);
+    bindings.bind_axis("zoom", AxisBinding::ScrollY);
+    bindings
+}
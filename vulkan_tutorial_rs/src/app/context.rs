@@ -0,0 +1,54 @@
+use crate::app::{AppWindow, FrameStats, Input, LogicalDevice, PhysicalDeviceInfo, WindowRegistry};
+
+use winit::window::WindowId;
+
+pub struct Context<'a> {
+    physical_device_info: &'a PhysicalDeviceInfo,
+    logical_device: &'a LogicalDevice,
+    windows: &'a WindowRegistry,
+    frame_stats: &'a FrameStats,
+    input: &'a Input,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(
+        physical_device_info: &'a PhysicalDeviceInfo,
+        logical_device: &'a LogicalDevice,
+        windows: &'a WindowRegistry,
+        frame_stats: &'a FrameStats,
+        input: &'a Input,
+    ) -> Context<'a> {
+        Context {
+            physical_device_info,
+            logical_device,
+            windows,
+            frame_stats,
+            input,
+        }
+    }
+
+    pub fn physical_device_info(&self) -> &'a PhysicalDeviceInfo {
+        self.physical_device_info
+    }
+
+    pub fn logical_device(&self) -> &'a LogicalDevice {
+        self.logical_device
+    }
+
+    pub fn windows(&self) -> &'a WindowRegistry {
+        self.windows
+    }
+
+    pub fn frame_stats(&self) -> &'a FrameStats {
+        self.frame_stats
+    }
+
+    // the keyboard, mouse and device axis state since the last frame
+    pub fn input(&self) -> &'a Input {
+        self.input
+    }
+
+    pub fn window(&self, id: WindowId) -> Option<&'a AppWindow> {
+        self.windows.get(id)
+    }
+}
@@ -0,0 +1,94 @@
+pub mod input;
+mod painter;
+pub mod panels;
+
+use crate::app::debug_ui::input::DebugUiInput;
+use crate::app::debug_ui::painter::Painter;
+use crate::app::debug_ui::panels::PanelData;
+use crate::app::debug_utils;
+use crate::app::{Frame, LogicalDevice, SwapchainInfo};
+
+use egui::CtxRef;
+
+use winit::event::WindowEvent;
+
+use std::time::Instant;
+
+// an egui overlay with built-in panels about the device, the window's swapchain and frame
+// timings, drawn in a render pass of its own after the application's command buffers
+pub struct DebugUi {
+    context: CtxRef,
+    input: DebugUiInput,
+    painter: Painter,
+    visible: bool,
+    start: Instant,
+}
+
+impl DebugUi {
+    pub fn new(
+        logical_device: &LogicalDevice,
+        swapchain_info: &SwapchainInfo,
+        scale_factor: f64,
+    ) -> DebugUi {
+        DebugUi {
+            context: CtxRef::default(),
+            input: DebugUiInput::new(scale_factor),
+            painter: Painter::new(logical_device, swapchain_info),
+            visible: false,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visible(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    // whether the last frame's UI is using the pointer or keyboard, e.g. while dragging a
+    // window; applications can check this to ignore the input themselves
+    pub fn wants_input(&self) -> bool {
+        self.visible && (self.context.wants_pointer_input() || self.context.wants_keyboard_input())
+    }
+
+    // events are only collected while the UI is visible
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.input.set_scale_factor(scale_factor)
+            }
+            _ if self.visible => self.input.handle_event(event),
+            _ => (),
+        }
+    }
+
+    // must be called whenever the window's swapchain is recreated
+    pub fn recreate_framebuffers(&mut self, swapchain_info: &SwapchainInfo) {
+        self.painter.recreate_framebuffers(swapchain_info);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, data: &PanelData) {
+        if !self.visible {
+            return;
+        }
+        let input = self
+            .input
+            .take(frame.dimensions(), self.start.elapsed().as_secs_f64());
+        self.context.begin_frame(input);
+        panels::show(&self.context, data);
+        let (_, shapes) = self.context.end_frame();
+        let meshes = self.context.tessellate(shapes);
+
+        debug_utils::begin_label(frame, "debug UI", [0.0; 4]);
+        let command_buffer = self.painter.draw(
+            frame,
+            &self.context.texture(),
+            &meshes,
+            self.context.pixels_per_point(),
+        );
+        frame.submit(command_buffer);
+        debug_utils::end_label(frame);
+    }
+}
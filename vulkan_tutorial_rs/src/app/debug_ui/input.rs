@@ -0,0 +1,190 @@
+use crate::app::config;
+
+use egui::{Event, Key, Modifiers, PointerButton, Pos2, RawInput, Rect, Vec2};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{
+    ElementState, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
+
+// collects a window's winit events between frames into egui's input; egui works in points,
+// which are physical pixels divided by the window's scale factor
+pub struct DebugUiInput {
+    scale_factor: f64,
+    pointer_position: Option<Pos2>,
+    modifiers: Modifiers,
+    events: Vec<Event>,
+    scroll_delta: Vec2,
+}
+
+impl DebugUiInput {
+    pub fn new(scale_factor: f64) -> DebugUiInput {
+        DebugUiInput {
+            scale_factor,
+            pointer_position: None,
+            modifiers: Modifiers::default(),
+            events: Vec::new(),
+            scroll_delta: Vec2::default(),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::CursorMoved { position, .. } => self.cursor_moved(position),
+            WindowEvent::CursorLeft { .. } => self.cursor_left(),
+            WindowEvent::MouseInput { state, button, .. } => self.mouse_button(button, state),
+            WindowEvent::MouseWheel { delta, .. } => self.mouse_wheel(delta),
+            WindowEvent::ReceivedCharacter(character) => self.character(character),
+            WindowEvent::KeyboardInput { input, .. } => {
+                if let Some(key) = input.virtual_keycode {
+                    self.key(key, input.state);
+                }
+            }
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers_changed(modifiers),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.set_scale_factor(scale_factor)
+            }
+            _ => (),
+        }
+    }
+
+    pub fn cursor_moved(&mut self, position: PhysicalPosition<f64>) {
+        let position = Pos2::new(
+            (position.x / self.scale_factor) as f32,
+            (position.y / self.scale_factor) as f32,
+        );
+        self.pointer_position = Some(position);
+        self.events.push(Event::PointerMoved(position));
+    }
+
+    pub fn cursor_left(&mut self) {
+        self.pointer_position = None;
+        self.events.push(Event::PointerGone);
+    }
+
+    // ignored until the cursor has moved over the window, since egui needs a position
+    pub fn mouse_button(&mut self, button: MouseButton, state: ElementState) {
+        let (pos, button) = match (self.pointer_position, pointer_button(button)) {
+            (Some(pos), Some(button)) => (pos, button),
+            _ => return,
+        };
+        self.events.push(Event::PointerButton {
+            pos,
+            button,
+            pressed: state == ElementState::Pressed,
+            modifiers: self.modifiers,
+        });
+    }
+
+    pub fn mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        self.scroll_delta += match delta {
+            MouseScrollDelta::LineDelta(x, y) => Vec2::new(x, y) * config::PIXELS_PER_SCROLL_LINE,
+            MouseScrollDelta::PixelDelta(delta) => Vec2::new(delta.x as f32, delta.y as f32),
+        };
+    }
+
+    // control characters arrive as key events instead
+    pub fn character(&mut self, character: char) {
+        if !character.is_control() {
+            self.events.push(Event::Text(character.to_string()));
+        }
+    }
+
+    pub fn key(&mut self, key: VirtualKeyCode, state: ElementState) {
+        let pressed = state == ElementState::Pressed;
+        if pressed && self.modifiers.command {
+            match key {
+                VirtualKeyCode::C => self.events.push(Event::Copy),
+                VirtualKeyCode::X => self.events.push(Event::Cut),
+                _ => (),
+            }
+        }
+        if let Some(key) = egui_key(key) {
+            self.events.push(Event::Key {
+                key,
+                pressed,
+                modifiers: self.modifiers,
+            });
+        }
+    }
+
+    pub fn modifiers_changed(&mut self, modifiers: ModifiersState) {
+        self.modifiers = egui_modifiers(modifiers);
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    // the input for the next egui frame, for a window of `dimensions` physical pixels; the
+    // events collected so far are handed over
+    pub fn take(&mut self, dimensions: [u32; 2], time: f64) -> RawInput {
+        let points_per_pixel = 1.0 / self.scale_factor as f32;
+        RawInput {
+            scroll_delta: std::mem::take(&mut self.scroll_delta),
+            screen_rect: Some(Rect::from_min_size(
+                Pos2::new(0.0, 0.0),
+                Vec2::new(dimensions[0] as f32, dimensions[1] as f32) * points_per_pixel,
+            )),
+            pixels_per_point: Some(self.scale_factor as f32),
+            time: Some(time),
+            modifiers: self.modifiers,
+            events: std::mem::take(&mut self.events),
+            ..RawInput::default()
+        }
+    }
+}
+
+pub fn pointer_button(button: MouseButton) -> Option<PointerButton> {
+    match button {
+        MouseButton::Left => Some(PointerButton::Primary),
+        MouseButton::Right => Some(PointerButton::Secondary),
+        MouseButton::Middle => Some(PointerButton::Middle),
+        MouseButton::Other(_) => None,
+    }
+}
+
+pub fn egui_modifiers(modifiers: ModifiersState) -> Modifiers {
+    Modifiers {
+        alt: modifiers.alt(),
+        ctrl: modifiers.ctrl(),
+        shift: modifiers.shift(),
+        mac_cmd: cfg!(target_os = "macos") && modifiers.logo(),
+        command: if cfg!(target_os = "macos") {
+            modifiers.logo()
+        } else {
+            modifiers.ctrl()
+        },
+    }
+}
+
+// only the keys egui handles itself
+pub fn egui_key(key: VirtualKeyCode) -> Option<Key> {
+    Some(match key {
+        VirtualKeyCode::Down => Key::ArrowDown,
+        VirtualKeyCode::Left => Key::ArrowLeft,
+        VirtualKeyCode::Right => Key::ArrowRight,
+        VirtualKeyCode::Up => Key::ArrowUp,
+        VirtualKeyCode::Escape => Key::Escape,
+        VirtualKeyCode::Tab => Key::Tab,
+        VirtualKeyCode::Back => Key::Backspace,
+        VirtualKeyCode::Return => Key::Enter,
+        VirtualKeyCode::Space => Key::Space,
+        VirtualKeyCode::Insert => Key::Insert,
+        VirtualKeyCode::Delete => Key::Delete,
+        VirtualKeyCode::Home => Key::Home,
+        VirtualKeyCode::End => Key::End,
+        VirtualKeyCode::PageUp => Key::PageUp,
+        VirtualKeyCode::PageDown => Key::PageDown,
+        VirtualKeyCode::A => Key::A,
+        VirtualKeyCode::K => Key::K,
+        VirtualKeyCode::U => Key::U,
+        VirtualKeyCode::W => Key::W,
+        VirtualKeyCode::Z => Key::Z,
+        _ => return None,
+    })
+}
@@ -0,0 +1,299 @@
+use crate::app::debug_utils;
+use crate::app::render_target::RenderTarget;
+use crate::app::{DescriptorSetCache, Frame, LogicalDevice, Texture};
+
+use egui::{ClippedMesh, Rect, TextureId};
+
+use vulkano::buffer::{BufferUsage, CpuBufferPool};
+use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::DescriptorSet;
+use vulkano::device::Device;
+use vulkano::format::{ClearValue, Format};
+use vulkano::framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract, Subpass};
+use vulkano::pipeline::blend::{AttachmentBlend, BlendFactor};
+use vulkano::pipeline::viewport::{Scissor, Viewport};
+use vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+
+use std::sync::Arc;
+
+mod vertex_shader {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        path: "shaders/debug_ui.vert"
+    }
+}
+
+mod fragment_shader {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        path: "shaders/debug_ui.frag"
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct DebugUiVertex {
+    position: [f32; 2],
+    uv: [f32; 2],
+    color: [f32; 4],
+}
+
+vulkano::impl_vertex!(DebugUiVertex, position, uv, color);
+
+// egui's font atlas, re-uploaded whenever egui bumps its version
+struct FontTexture {
+    version: u64,
+    _texture: Texture,
+    descriptor_set: Arc<dyn DescriptorSet + Send + Sync>,
+}
+
+// draws egui's meshes over what has already been rendered into the target's images. Colours
+// are only converted to linear in the shaders if the target's format is sRGB
+pub struct Painter {
+    render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
+    pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+    framebuffers: Vec<Arc<dyn FramebufferAbstract + Send + Sync>>,
+    vertices: CpuBufferPool<DebugUiVertex>,
+    indices: CpuBufferPool<u32>,
+    descriptor_sets: DescriptorSetCache,
+    font_texture: Option<FontTexture>,
+}
+
+impl Painter {
+    pub fn new<T: RenderTarget>(logical_device: &LogicalDevice, target: &T) -> Painter {
+        let device = logical_device.device();
+        let render_pass = create_render_pass(device, target);
+        let vertex_shader =
+            vertex_shader::Shader::load(device.clone()).expect("failed to load vertex shader");
+        let fragment_shader =
+            fragment_shader::Shader::load(device.clone()).expect("failed to load fragment shader");
+        // egui's colours have premultiplied alpha
+        let blend = AttachmentBlend {
+            color_source: BlendFactor::One,
+            color_destination: BlendFactor::OneMinusSrcAlpha,
+            alpha_source: BlendFactor::OneMinusDstAlpha,
+            alpha_destination: BlendFactor::One,
+            ..AttachmentBlend::alpha_blending()
+        };
+        let pipeline = Arc::new(
+            GraphicsPipeline::start()
+                .vertex_input_single_buffer::<DebugUiVertex>()
+                .vertex_shader(
+                    vertex_shader.main_entry_point(),
+                    vertex_shader::SpecializationConstants {
+                        srgb_target: u32::from(is_srgb(target.format())),
+                    },
+                )
+                .triangle_list()
+                .viewports_scissors_dynamic(1)
+                .fragment_shader(fragment_shader.main_entry_point(), ())
+                .blend_collective(blend)
+                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+                .build_with_cache(logical_device.pipeline_cache().clone())
+                .build(device.clone())
+                .expect("failed to create debug UI pipeline"),
+        );
+        debug_utils::set_pipeline_name(device, &*pipeline, "debug UI pipeline");
+        let mut painter = Painter {
+            render_pass,
+            pipeline,
+            framebuffers: Vec::new(),
+            vertices: CpuBufferPool::vertex_buffer(device.clone()),
+            indices: CpuBufferPool::new(device.clone(), BufferUsage::index_buffer()),
+            descriptor_sets: DescriptorSetCache::new(),
+            font_texture: None,
+        };
+        painter.recreate_framebuffers(target);
+        painter
+    }
+
+    // must be called whenever the window's swapchain is recreated
+    pub fn recreate_framebuffers<T: RenderTarget>(&mut self, target: &T) {
+        self.framebuffers = target
+            .images()
+            .into_iter()
+            .map(|image| {
+                Arc::new(
+                    Framebuffer::start(self.render_pass.clone())
+                        .add(image)
+                        .unwrap()
+                        .build()
+                        .expect("failed to create framebuffer"),
+                ) as Arc<dyn FramebufferAbstract + Send + Sync>
+            })
+            .collect();
+    }
+
+    // meshes with textures other than egui's own are skipped
+    pub fn draw(
+        &mut self,
+        frame: &mut Frame,
+        font_texture: &egui::Texture,
+        meshes: &[ClippedMesh],
+        pixels_per_point: f32,
+    ) -> AutoCommandBuffer {
+        self.update_font_texture(frame, font_texture);
+        let descriptor_set = self.font_texture.as_ref().unwrap().descriptor_set.clone();
+        let logical_device = frame.logical_device();
+        let dimensions = frame.dimensions();
+        let push_constants = vertex_shader::ty::PushConstants {
+            screen_size: [
+                dimensions[0] as f32 / pixels_per_point,
+                dimensions[1] as f32 / pixels_per_point,
+            ],
+        };
+        let mut dynamic_state = DynamicState {
+            viewports: Some(vec![Viewport {
+                origin: [0.0, 0.0],
+                dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+                depth_range: 0.0..1.0,
+            }]),
+            ..DynamicState::none()
+        };
+
+        let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(
+            logical_device.device().clone(),
+            logical_device.graphics_queue().family(),
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                self.framebuffers[frame.image_num()].clone(),
+                false,
+                vec![ClearValue::None],
+            )
+            .unwrap();
+        for ClippedMesh(clip_rect, mesh) in meshes {
+            if mesh.texture_id != TextureId::Egui || mesh.indices.is_empty() {
+                continue;
+            }
+            let scissor = match scissor(*clip_rect, pixels_per_point, dimensions) {
+                Some(scissor) => scissor,
+                None => continue,
+            };
+            dynamic_state.scissors = Some(vec![scissor]);
+            let vertices = self
+                .vertices
+                .chunk(mesh.vertices.iter().map(|vertex| DebugUiVertex {
+                    position: [vertex.pos.x, vertex.pos.y],
+                    uv: [vertex.uv.x, vertex.uv.y],
+                    color: [
+                        f32::from(vertex.color.r()) / 255.0,
+                        f32::from(vertex.color.g()) / 255.0,
+                        f32::from(vertex.color.b()) / 255.0,
+                        f32::from(vertex.color.a()) / 255.0,
+                    ],
+                }))
+                .expect("failed to allocate debug UI vertices");
+            let indices = self
+                .indices
+                .chunk(mesh.indices.iter().cloned())
+                .expect("failed to allocate debug UI indices");
+            builder
+                .draw_indexed(
+                    self.pipeline.clone(),
+                    &dynamic_state,
+                    vec![Arc::new(vertices)],
+                    indices,
+                    descriptor_set.clone(),
+                    push_constants,
+                )
+                .unwrap();
+        }
+        builder.end_render_pass().unwrap();
+        builder.build().unwrap()
+    }
+
+    // the upload is joined into `frame`, so it completes before the frame draws with it
+    fn update_font_texture(&mut self, frame: &mut Frame, font_texture: &egui::Texture) {
+        if let Some(ref current) = self.font_texture {
+            if current.version == font_texture.version {
+                return;
+            }
+        }
+        let pixels: Vec<u8> = font_texture
+            .srgba_pixels()
+            .flat_map(|pixel| pixel.to_array())
+            .collect();
+        let mut uploader = frame.logical_device().uploader();
+        let texture = Texture::from_rgba8(
+            &mut uploader,
+            &pixels,
+            [font_texture.width as u32, font_texture.height as u32],
+        );
+        frame.join(uploader.flush());
+        debug_utils::set_image_name(
+            frame.logical_device().device(),
+            &**texture.image(),
+            "debug UI font",
+        );
+        let descriptor_set = Arc::new(
+            self.descriptor_sets
+                .pool(&*self.pipeline, 0)
+                .next()
+                .add_sampled_image(texture.image().clone(), texture.sampler().clone())
+                .unwrap()
+                .build()
+                .expect("failed to create descriptor set"),
+        );
+        self.font_texture = Some(FontTexture {
+            version: font_texture.version,
+            _texture: texture,
+            descriptor_set,
+        });
+    }
+}
+
+fn is_srgb(format: Format) -> bool {
+    matches!(
+        format,
+        Format::R8Srgb
+            | Format::R8G8Srgb
+            | Format::R8G8B8Srgb
+            | Format::B8G8R8Srgb
+            | Format::R8G8B8A8Srgb
+            | Format::B8G8R8A8Srgb
+            | Format::A8B8G8R8SrgbPack32
+    )
+}
+
+// keeps what was rendered before, so it has to draw into the resolved image
+fn create_render_pass<T: RenderTarget>(
+    device: &Arc<Device>,
+    target: &T,
+) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    Arc::new(
+        vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    load: Load,
+                    store: Store,
+                    format: target.format(),
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .expect("failed to create render pass"),
+    )
+}
+
+// `clip_rect` is in points; `None` if nothing of it is inside the target
+fn scissor(clip_rect: Rect, pixels_per_point: f32, dimensions: [u32; 2]) -> Option<Scissor> {
+    let clamp =
+        |value: f32, max: u32| (value * pixels_per_point).round().clamp(0.0, max as f32) as u32;
+    let left = clamp(clip_rect.min.x, dimensions[0]);
+    let top = clamp(clip_rect.min.y, dimensions[1]);
+    let right = clamp(clip_rect.max.x, dimensions[0]);
+    let bottom = clamp(clip_rect.max.y, dimensions[1]);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some(Scissor {
+        origin: [left as i32, top as i32],
+        dimensions: [right - left, bottom - top],
+    })
+}
@@ -0,0 +1,161 @@
+use crate::app::{FrameStats, PhysicalDeviceInfo, SwapchainInfo};
+
+use egui::{CollapsingHeader, Color32, CtxRef, Grid, Sense, Ui, Vec2};
+
+use std::fmt::Display;
+use std::time::Duration;
+
+// frame times are graphed against this; longer frames are clipped and drawn red
+const GRAPH_BUDGET: Duration = Duration::from_micros(33_333);
+const GRAPH_SIZE: [f32; 2] = [240.0, 48.0];
+
+// what the built-in panel shows about a window
+pub struct PanelData<'a> {
+    pub physical_device_info: &'a PhysicalDeviceInfo,
+    pub swapchain_info: &'a SwapchainInfo,
+    pub frame_stats: &'a FrameStats,
+}
+
+pub fn show(context: &CtxRef, data: &PanelData) {
+    egui::Window::new("Debug")
+        .default_pos([16.0, 16.0])
+        .resizable(false)
+        .show(context, |ui| {
+            CollapsingHeader::new("Device")
+                .default_open(true)
+                .show(ui, |ui| device(ui, data.physical_device_info));
+            CollapsingHeader::new("Swapchain")
+                .default_open(true)
+                .show(ui, |ui| swapchain(ui, data.swapchain_info));
+            CollapsingHeader::new("Frame timings")
+                .default_open(true)
+                .show(ui, |ui| frame_timings(ui, data.frame_stats));
+        });
+}
+
+fn device(ui: &mut Ui, info: &PhysicalDeviceInfo) {
+    let physical_device = info.physical_device();
+    let api_version = physical_device.api_version();
+    Grid::new("device").show(ui, |ui| {
+        row(ui, "Name", physical_device.name());
+        row(ui, "Type", format!("{:?}", physical_device.ty()));
+        row(
+            ui,
+            "API version",
+            format!(
+                "{}.{}.{}",
+                api_version.major, api_version.minor, api_version.patch
+            ),
+        );
+        row(ui, "Driver version", physical_device.driver_version());
+        row(
+            ui,
+            "Vendor ID",
+            format!("{:#06x}", physical_device.pci_vendor_id()),
+        );
+        row(
+            ui,
+            "Device ID",
+            format!("{:#06x}", physical_device.pci_device_id()),
+        );
+        row(
+            ui,
+            "Graphics queue family",
+            family(info.graphics_family().map(|f| f.id())),
+        );
+        row(
+            ui,
+            "Present queue family",
+            family(info.present_family().map(|f| f.id())),
+        );
+        row(
+            ui,
+            "Transfer queue family",
+            family(info.transfer_family().map(|f| f.id())),
+        );
+        row(ui, "Max 2D image size", info.max_image_dimension_2d());
+    });
+}
+
+fn swapchain(ui: &mut Ui, info: &SwapchainInfo) {
+    let dimensions = info._dimensions();
+    Grid::new("swapchain").show(ui, |ui| {
+        row(ui, "Format", format!("{:?}", info._format()));
+        row(
+            ui,
+            "Present mode",
+            format!("{:?}", info._swapchain().present_mode()),
+        );
+        row(ui, "Images", info._images().len());
+        row(
+            ui,
+            "Dimensions",
+            format!("{}x{}", dimensions[0], dimensions[1]),
+        );
+        row(
+            ui,
+            "Composite alpha",
+            format!("{:?}", info._composite_alpha()),
+        );
+        row(ui, "Depth format", format!("{:?}", info.depth_format()));
+        row(ui, "MSAA samples", info.samples());
+    });
+}
+
+fn frame_timings(ui: &mut Ui, stats: &FrameStats) {
+    let summary = match stats.summary() {
+        Some(summary) => summary,
+        None => {
+            ui.label("No frames yet");
+            return;
+        }
+    };
+    Grid::new("frame_timings").show(ui, |ui| {
+        row(ui, "FPS", format!("{:.0}", summary.fps()));
+        row(ui, "Average", milliseconds(summary.average));
+        row(ui, "Min", milliseconds(summary.min));
+        row(ui, "Max", milliseconds(summary.max));
+        row(ui, "99th percentile", milliseconds(summary.p99));
+    });
+    frame_time_graph(ui, stats);
+}
+
+// one bar per recorded frame, oldest on the left
+fn frame_time_graph(ui: &mut Ui, stats: &FrameStats) {
+    let size = Vec2::new(GRAPH_SIZE[0], GRAPH_SIZE[1]);
+    let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 0.0, Color32::from_black_alpha(128));
+    let bar_width = rect.width() / stats.len().max(1) as f32;
+    for (i, frame_time) in stats.frame_times().enumerate() {
+        let fraction = frame_time.as_secs_f32() / GRAPH_BUDGET.as_secs_f32();
+        let color = if fraction > 1.0 {
+            Color32::RED
+        } else {
+            Color32::LIGHT_GREEN
+        };
+        let left = rect.left() + i as f32 * bar_width;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left, rect.bottom() - rect.height() * fraction.min(1.0)),
+            egui::pos2(left + bar_width, rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, color);
+    }
+}
+
+fn row(ui: &mut Ui, key: &str, value: impl Display) {
+    ui.label(key);
+    ui.label(value.to_string());
+    ui.end_row();
+}
+
+fn family(id: Option<u32>) -> String {
+    match id {
+        Some(id) => id.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
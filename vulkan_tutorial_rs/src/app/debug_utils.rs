@@ -0,0 +1,225 @@
+use crate::app::raw_command_buffer::RawCommandBuffer;
+use crate::app::Frame;
+
+use vulkano::buffer::sys::UnsafeBuffer;
+use vulkano::buffer::BufferAccess;
+use vulkano::command_buffer::pool::standard::StandardCommandPoolBuilder;
+use vulkano::command_buffer::sys::UnsafeCommandBufferBuilder;
+use vulkano::device::{Device, Queue};
+use vulkano::image::sys::UnsafeImage;
+use vulkano::image::ImageAccess;
+use vulkano::instance::QueueFamily;
+use vulkano::pipeline::{GraphicsPipelineAbstract, GraphicsPipelineSys};
+use vulkano::swapchain::Swapchain;
+use vulkano::VulkanObject;
+
+use std::ffi::CString;
+use std::ptr;
+use std::sync::Arc;
+
+// names and labels only do something when the instance was created with `ext_debug_utils`,
+// which `init::required_instance_extensions` enables along with validation; otherwise every
+// function here is a no-op
+
+// the Vulkan objects that can be named
+pub trait DebugName {
+    fn object_type(&self) -> vk_sys::ObjectType;
+    fn handle(&self) -> u64;
+}
+
+impl DebugName for Device {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_DEVICE
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object() as u64
+    }
+}
+
+impl DebugName for Queue {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_QUEUE
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object() as u64
+    }
+}
+
+impl<W> DebugName for Swapchain<W> {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_SWAPCHAIN_KHR
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object()
+    }
+}
+
+impl DebugName for UnsafeImage {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_IMAGE
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object()
+    }
+}
+
+impl DebugName for UnsafeBuffer {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_BUFFER
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object()
+    }
+}
+
+impl<'a> DebugName for GraphicsPipelineSys<'a> {
+    fn object_type(&self) -> vk_sys::ObjectType {
+        vk_sys::OBJECT_TYPE_PIPELINE
+    }
+
+    fn handle(&self) -> u64 {
+        self.internal_object()
+    }
+}
+
+pub fn is_enabled(device: &Device) -> bool {
+    device.instance().loaded_extensions().ext_debug_utils
+}
+
+pub fn set_name<T: DebugName + ?Sized>(device: &Device, object: &T, name: &str) {
+    if !is_enabled(device) {
+        return;
+    }
+    let c_name = c_string(name);
+    let info = vk_sys::DebugUtilsObjectNameInfoEXT {
+        sType: vk_sys::STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT,
+        pNext: ptr::null(),
+        objectType: object.object_type(),
+        objectHandle: object.handle(),
+        pObjectName: c_name.as_ptr(),
+    };
+    let result = unsafe {
+        device
+            .instance()
+            .pointers()
+            .SetDebugUtilsObjectNameEXT(device.internal_object(), &info)
+    };
+    if result != vk_sys::SUCCESS {
+        println!("WARNING: Failed to name \"{}\": error {}", name, result);
+    }
+}
+
+// names the image itself, whichever wrapper it is accessed through
+pub fn set_image_name<I: ImageAccess + ?Sized>(device: &Device, image: &I, name: &str) {
+    set_name(device, image.inner().image, name);
+}
+
+pub fn set_buffer_name<B: BufferAccess + ?Sized>(device: &Device, buffer: &B, name: &str) {
+    set_name(device, buffer.inner().buffer, name);
+}
+
+pub fn set_pipeline_name<P: GraphicsPipelineAbstract + ?Sized>(
+    device: &Device,
+    pipeline: &P,
+    name: &str,
+) {
+    set_name(device, &pipeline.inner(), name);
+}
+
+// a label region spans from `Begin` to the matching `End`, possibly across command buffers;
+// `Insert` marks a single point. A colour of all zeroes leaves the choice to the tool
+#[derive(Clone, Copy, Debug)]
+pub enum Label<'a> {
+    Begin(&'a str, [f32; 4]),
+    End,
+    Insert(&'a str, [f32; 4]),
+}
+
+// `None` when labels are disabled
+pub fn record_label(
+    device: &Arc<Device>,
+    queue_family: QueueFamily,
+    label: Label,
+) -> Option<RawCommandBuffer> {
+    if !is_enabled(device) {
+        return None;
+    }
+    // labels touch no buffers or images and refer to nothing once recorded
+    let command_buffer = unsafe {
+        RawCommandBuffer::new(
+            device,
+            queue_family,
+            Vec::new(),
+            label_commands(device, label),
+        )
+    };
+    Some(command_buffer)
+}
+
+fn label_commands(
+    device: &Arc<Device>,
+    label: Label,
+) -> impl FnOnce(&mut UnsafeCommandBufferBuilder<StandardCommandPoolBuilder>) + 'static {
+    let (name, color) = match label {
+        Label::Begin(name, color) | Label::Insert(name, color) => (c_string(name), color),
+        Label::End => (CString::default(), [0.0; 4]),
+    };
+    let (begin, end) = (
+        matches!(label, Label::Begin(..)),
+        matches!(label, Label::End),
+    );
+    let device = device.clone();
+    move |builder| {
+        let info = vk_sys::DebugUtilsLabelEXT {
+            sType: vk_sys::STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+            pNext: ptr::null(),
+            pLabelName: name.as_ptr(),
+            color,
+        };
+        let pointers = device.instance().pointers();
+        let command_buffer = builder.internal_object();
+        unsafe {
+            if begin {
+                pointers.CmdBeginDebugUtilsLabelEXT(command_buffer, &info);
+            } else if end {
+                pointers.CmdEndDebugUtilsLabelEXT(command_buffer);
+            } else {
+                pointers.CmdInsertDebugUtilsLabelEXT(command_buffer, &info);
+            }
+        }
+    }
+}
+
+// around the command buffers submitted to `frame` until the matching `end_label`
+pub fn begin_label(frame: &mut Frame, name: &str, color: [f32; 4]) {
+    submit_label(frame, Label::Begin(name, color));
+}
+
+pub fn end_label(frame: &mut Frame) {
+    submit_label(frame, Label::End);
+}
+
+pub fn insert_label(frame: &mut Frame, name: &str, color: [f32; 4]) {
+    submit_label(frame, Label::Insert(name, color));
+}
+
+fn submit_label(frame: &mut Frame, label: Label) {
+    let device = frame.logical_device().device();
+    if !is_enabled(device) {
+        return;
+    }
+    // see `record_label`
+    unsafe {
+        frame.record_raw(Vec::new(), label_commands(device, label));
+    }
+}
+
+// names can't contain NUL, so any are dropped
+fn c_string(name: &str) -> CString {
+    CString::new(name.replace('\0', "")).unwrap()
+}
@@ -0,0 +1,314 @@
+use crate::app::config;
+use crate::app::mesh::builtin;
+use crate::app::paths;
+use crate::app::{
+    Application, ButtonBinding, Camera, Context, FlyController, Frame, FrameUniforms,
+    InputBindings, Mesh, OrbitController, Profiler, ProfilerOverlay, Projection, Renderer,
+    ShaderCompiler, ShaderWatcher, Texture, Vertex,
+};
+
+use cgmath::{Deg, Matrix4, Point3, Rad};
+
+use vulkano::sync::GpuFuture;
+
+use winit::event::{Event, VirtualKeyCode, WindowEvent};
+use winit::window::WindowId;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// relative to `paths::resource_path`
+const TEXTURE_PATH: &str = "assets/icon.png";
+// switches the focused window's camera between orbiting the quad and flying freely
+const CAMERA_MODE_ACTION: &str = "toggle_camera_mode";
+const CAMERA_MODE_KEY: VirtualKeyCode = VirtualKeyCode::C;
+
+enum CameraController {
+    Orbit(OrbitController),
+    Fly(FlyController),
+}
+
+// each window has a camera of its own, matching its aspect ratio
+struct WindowCamera {
+    camera: Camera,
+    controller: CameraController,
+}
+
+impl WindowCamera {
+    fn new() -> WindowCamera {
+        WindowCamera {
+            camera: Camera::new(
+                Point3::new(0.0, 0.0, 2.0),
+                Projection::Perspective {
+                    fovy: Deg(60.0).into(),
+                    near: 0.1,
+                    far: 100.0,
+                },
+            ),
+            controller: CameraController::Orbit(orbit_controller()),
+        }
+    }
+
+    fn toggle_mode(&mut self) {
+        self.controller = match self.controller {
+            CameraController::Orbit(_) => {
+                CameraController::Fly(FlyController::new(config::FLY_SPEED))
+            }
+            // the orbit starts over from in front of the quad
+            CameraController::Fly(_) => CameraController::Orbit(orbit_controller()),
+        };
+    }
+}
+
+// the binary's application: a spinning textured quad in every window, with a camera each
+pub struct MeshDemo {
+    meshes: Vec<Mesh<Vertex>>,
+    texture: Option<Texture>,
+    renderers: HashMap<WindowId, Renderer>,
+    profilers: HashMap<WindowId, Profiler>,
+    overlays: HashMap<WindowId, ProfilerOverlay>,
+    cameras: HashMap<WindowId, WindowCamera>,
+    // the window whose camera input controls
+    focused_window: Option<WindowId>,
+    shader_compiler: ShaderCompiler,
+    shader_watcher: Option<ShaderWatcher>,
+    start: Instant,
+}
+
+impl Default for MeshDemo {
+    fn default() -> MeshDemo {
+        MeshDemo::new()
+    }
+}
+
+impl MeshDemo {
+    pub fn new() -> MeshDemo {
+        MeshDemo {
+            meshes: Vec::new(),
+            texture: None,
+            renderers: HashMap::new(),
+            profilers: HashMap::new(),
+            overlays: HashMap::new(),
+            cameras: HashMap::new(),
+            focused_window: None,
+            shader_compiler: ShaderCompiler::new(),
+            shader_watcher: None,
+            start: Instant::now(),
+        }
+    }
+
+    // the configured bindings, plus the demo's own actions
+    pub fn input_bindings() -> InputBindings {
+        let mut input_bindings = config::input_bindings();
+        input_bindings.bind_action(CAMERA_MODE_ACTION, ButtonBinding::Key(CAMERA_MODE_KEY));
+        input_bindings
+    }
+
+    fn reload_changed_shaders(&mut self) {
+        let changed = match self.shader_watcher {
+            Some(ref watcher) => watcher.changed_files(),
+            None => return,
+        };
+        if changed.is_empty() {
+            return;
+        }
+        for renderer in self.renderers.values_mut() {
+            if !renderer.uses_any_shader_source(&changed) {
+                continue;
+            }
+            // a broken shader, or one that no longer fits the pipeline, keeps the previous
+            // pipeline, so the app can be fixed while it runs
+            match renderer.reload_shaders(&mut self.shader_compiler) {
+                Ok(()) => println!("Reloaded shaders"),
+                Err(e) => println!(
+                    "WARNING: Shader reload failed, keeping the old pipeline: {}",
+                    e
+                ),
+            }
+        }
+    }
+
+    fn upload_resources(&mut self, context: &Context) {
+        let mut uploader = context.logical_device().uploader();
+        self.meshes = vec![Mesh::upload(&mut uploader, &builtin::quad())];
+        self.texture = Some(
+            Texture::load(&mut uploader, &paths::resource_path(TEXTURE_PATH))
+                .expect("failed to load texture"),
+        );
+        // every window draws these, so wait for the upload here rather than joining it
+        // into a single window's frame
+        uploader
+            .flush()
+            .then_signal_fence_and_flush()
+            .expect("failed to flush uploads")
+            .wait(None)
+            .expect("failed to upload resources");
+    }
+}
+
+impl Application for MeshDemo {
+    fn init(&mut self, context: &Context) {
+        self.upload_resources(context);
+        if config::SHADER_HOT_RELOAD {
+            self.shader_watcher = ShaderWatcher::new(
+                &paths::resource_path(config::SHADER_DIRECTORY),
+                config::SHADER_RELOAD_DELAY,
+            )
+            .map_err(|e| println!("WARNING: Failed to watch shaders: {}", e))
+            .ok();
+        }
+        for window in context.windows().iter() {
+            self.cameras
+                .entry(window.id())
+                .or_insert_with(WindowCamera::new)
+                .camera
+                .resize(window.swapchain_info()._dimensions());
+        }
+        // until a window reports gaining focus
+        self.focused_window = context.windows().iter().next().map(|window| window.id());
+    }
+
+    fn on_event(&mut self, _context: &Context, event: &Event<()>) {
+        if let Event::WindowEvent {
+            window_id,
+            event: WindowEvent::Focused(focused),
+        } = *event
+        {
+            if focused {
+                self.focused_window = Some(window_id);
+            } else if self.focused_window == Some(window_id) {
+                self.focused_window = None;
+            }
+        }
+    }
+
+    fn update(&mut self, context: &Context, dt: Duration) {
+        self.reload_changed_shaders();
+        let window_id = match self.focused_window {
+            Some(window_id) => window_id,
+            None => return,
+        };
+        // the debug UI gets input it is using, e.g. while a panel is being dragged
+        let ui_wants_input = context
+            .window(window_id)
+            .and_then(|window| window.debug_ui())
+            .map_or(false, |debug_ui| debug_ui.wants_input());
+        if ui_wants_input {
+            return;
+        }
+        let input = context.input();
+        let camera = self
+            .cameras
+            .entry(window_id)
+            .or_insert_with(WindowCamera::new);
+        if input.was_pressed(CAMERA_MODE_ACTION) {
+            camera.toggle_mode();
+        }
+        match camera.controller {
+            CameraController::Orbit(ref mut orbit) => orbit.update(input, &mut camera.camera),
+            CameraController::Fly(ref fly) => fly.update(input, &mut camera.camera, dt),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let texture = match self.texture {
+            Some(ref texture) => texture,
+            None => return,
+        };
+        let renderer = self
+            .renderers
+            .entry(frame.window_id())
+            .or_insert_with(|| Renderer::new(frame.logical_device(), frame.swapchain_info()));
+        let profiler = self
+            .profilers
+            .entry(frame.window_id())
+            .or_insert_with(|| Profiler::new(frame.logical_device()));
+        let camera = &self
+            .cameras
+            .entry(frame.window_id())
+            .or_insert_with(WindowCamera::new)
+            .camera;
+        profiler.begin_frame(frame);
+        let time = self.start.elapsed().as_secs_f32();
+        let uniforms = FrameUniforms::new(
+            Matrix4::from_angle_z(Rad(time)),
+            camera.view(),
+            camera.projection_matrix(),
+            time,
+        );
+        let meshes: Vec<_> = self.meshes.iter().collect();
+        profiler.begin_region(frame, "meshes");
+        let command_buffer = renderer.draw(frame, &meshes, uniforms, texture);
+        frame.submit(command_buffer);
+        profiler.end_region(frame);
+
+        if config::SHOW_PROFILER_OVERLAY {
+            if let Some(profile) = profiler.history().average() {
+                let overlay = self.overlays.entry(frame.window_id()).or_insert_with(|| {
+                    ProfilerOverlay::new(frame.logical_device(), frame.swapchain_info())
+                });
+                profiler.begin_region(frame, "overlay");
+                let command_buffer = overlay.draw(frame, &profile);
+                frame.submit(command_buffer);
+                profiler.end_region(frame);
+            }
+        }
+        profiler.end_frame(frame);
+    }
+
+    fn on_resize(&mut self, context: &Context, window_id: WindowId, _dimensions: [u32; 2]) {
+        if let Some(window) = context.window(window_id) {
+            self.cameras
+                .entry(window_id)
+                .or_insert_with(WindowCamera::new)
+                .camera
+                .resize(window.swapchain_info()._dimensions());
+        }
+        if let (Some(renderer), Some(window)) = (
+            self.renderers.get_mut(&window_id),
+            context.window(window_id),
+        ) {
+            renderer.recreate_framebuffers(window.swapchain_info());
+        }
+        if let (Some(overlay), Some(window)) =
+            (self.overlays.get_mut(&window_id), context.window(window_id))
+        {
+            overlay.recreate_framebuffers(window.swapchain_info());
+        }
+    }
+
+    fn on_window_closed(&mut self, _context: &Context, window_id: WindowId) {
+        self.renderers.remove(&window_id);
+        self.profilers.remove(&window_id);
+        self.overlays.remove(&window_id);
+        self.cameras.remove(&window_id);
+        if self.focused_window == Some(window_id) {
+            self.focused_window = None;
+        }
+    }
+
+    fn on_device_lost(&mut self) {
+        self.renderers.clear();
+        self.profilers.clear();
+        self.overlays.clear();
+        self.meshes.clear();
+        self.texture = None;
+    }
+
+    fn on_device_recreated(&mut self, context: &Context) {
+        self.upload_resources(context);
+    }
+
+    fn shutdown(&mut self, _context: &Context) {
+        // the framebuffers reference the swapchain images, which keep the swapchains alive
+        self.renderers.clear();
+        self.overlays.clear();
+        self.profilers.clear();
+        self.meshes.clear();
+        self.texture = None;
+    }
+}
+
+fn orbit_controller() -> OrbitController {
+    OrbitController::new(Point3::new(0.0, 0.0, 0.0), 2.0)
+}
@@ -0,0 +1,47 @@
+use vulkano::descriptor::descriptor_set::{FixedSizeDescriptorSetsPool, UnsafeDescriptorSetLayout};
+use vulkano::descriptor::PipelineLayoutAbstract;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// one descriptor set pool per descriptor set layout, created on first use; sets allocated
+// from a pool are recycled once the GPU no longer uses them
+#[derive(Default)]
+pub struct DescriptorSetCache {
+    // keyed by the layout's address; the pool keeps the layout alive, so it can't be reused
+    pools: HashMap<usize, FixedSizeDescriptorSetsPool>,
+}
+
+impl DescriptorSetCache {
+    pub fn new() -> DescriptorSetCache {
+        DescriptorSetCache {
+            pools: HashMap::new(),
+        }
+    }
+
+    // panics if `layout` has no descriptor set `set`
+    pub fn pool<L>(&mut self, layout: &L, set: usize) -> &mut FixedSizeDescriptorSetsPool
+    where
+        L: PipelineLayoutAbstract + ?Sized,
+    {
+        let set_layout: &Arc<UnsafeDescriptorSetLayout> = layout
+            .descriptor_set_layout(set)
+            .expect("pipeline layout has no such descriptor set");
+        let key = Arc::as_ptr(set_layout) as usize;
+        self.pools
+            .entry(key)
+            .or_insert_with(|| FixedSizeDescriptorSetsPool::new(set_layout.clone()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pools.clear();
+    }
+}
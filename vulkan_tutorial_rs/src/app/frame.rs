@@ -0,0 +1,177 @@
+use crate::app::raw_command_buffer::RawCommandBuffer;
+use crate::app::{LogicalDevice, SwapchainInfo};
+
+use vulkano::command_buffer::pool::standard::StandardCommandPoolBuilder;
+use vulkano::command_buffer::sys::UnsafeCommandBufferBuilder;
+use vulkano::command_buffer::AutoCommandBuffer;
+use vulkano::image::SwapchainImage;
+use vulkano::sync::GpuFuture;
+
+use winit::window::{Window, WindowId};
+
+use std::any::Any;
+use std::mem;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+type RawCommands = Box<dyn FnOnce(&mut UnsafeCommandBufferBuilder<StandardCommandPoolBuilder>)>;
+
+pub enum FrameCommandBuffer {
+    Auto(AutoCommandBuffer),
+    Raw(RawCommandBuffer),
+}
+
+pub struct Frame<'a> {
+    logical_device: &'a LogicalDevice,
+    window_id: WindowId,
+    swapchain_info: &'a SwapchainInfo,
+    image_num: usize,
+    alpha: f64,
+    command_buffers: Vec<FrameCommandBuffer>,
+    // recorded since the last command buffer was submitted, and submitted together as one raw
+    // command buffer in front of the next
+    raw_commands: Vec<RawCommands>,
+    raw_resources: Vec<Arc<dyn Any + Send + Sync>>,
+    dependencies: Vec<Box<dyn GpuFuture>>,
+    screenshot_requested: bool,
+    submitted: Arc<AtomicBool>,
+}
+
+impl<'a> Frame<'a> {
+    pub fn new(
+        logical_device: &'a LogicalDevice,
+        window_id: WindowId,
+        swapchain_info: &'a SwapchainInfo,
+        image_num: usize,
+        alpha: f64,
+    ) -> Frame<'a> {
+        Frame {
+            logical_device,
+            window_id,
+            swapchain_info,
+            image_num,
+            alpha,
+            command_buffers: Vec::new(),
+            raw_commands: Vec::new(),
+            raw_resources: Vec::new(),
+            dependencies: Vec::new(),
+            screenshot_requested: false,
+            submitted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn logical_device(&self) -> &'a LogicalDevice {
+        self.logical_device
+    }
+
+    pub fn window_id(&self) -> WindowId {
+        self.window_id
+    }
+
+    pub fn swapchain_info(&self) -> &'a SwapchainInfo {
+        self.swapchain_info
+    }
+
+    pub fn image_num(&self) -> usize {
+        self.image_num
+    }
+
+    // how far between the last fixed update and the next one this frame is, in [0, 1); use it
+    // to interpolate state for smooth rendering
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn image(&self) -> &'a Arc<SwapchainImage<Window>> {
+        &self.swapchain_info._images()[self.image_num]
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.swapchain_info._dimensions()
+    }
+
+    // command buffers are executed in submission order
+    pub fn submit(&mut self, command_buffer: AutoCommandBuffer) {
+        self.flush_raw_commands();
+        self.command_buffers
+            .push(FrameCommandBuffer::Auto(command_buffer));
+    }
+
+    pub fn submit_raw(&mut self, command_buffer: RawCommandBuffer) {
+        self.flush_raw_commands();
+        self.command_buffers
+            .push(FrameCommandBuffer::Raw(command_buffer));
+    }
+
+    /// Records commands vulkano's builder doesn't offer, such as timestamps and debug labels,
+    /// between the command buffers submitted before and after. Consecutive calls share one raw
+    /// command buffer.
+    ///
+    /// # Safety
+    ///
+    /// The same as for `RawCommandBuffer::new`.
+    pub unsafe fn record_raw<F>(&mut self, resources: Vec<Arc<dyn Any + Send + Sync>>, record: F)
+    where
+        F: FnOnce(&mut UnsafeCommandBufferBuilder<StandardCommandPoolBuilder>) + 'static,
+    {
+        self.raw_commands.push(Box::new(record));
+        self.raw_resources.extend(resources);
+    }
+
+    fn flush_raw_commands(&mut self) {
+        if self.raw_commands.is_empty() {
+            return;
+        }
+        let commands = mem::take(&mut self.raw_commands);
+        let resources = mem::take(&mut self.raw_resources);
+        // each of the commands' callers upheld the contract in `record_raw`
+        let command_buffer = unsafe {
+            RawCommandBuffer::new(
+                self.logical_device.device(),
+                self.logical_device.graphics_queue().family(),
+                resources,
+                |builder| {
+                    for record in commands {
+                        record(builder);
+                    }
+                },
+            )
+        };
+        self.command_buffers
+            .push(FrameCommandBuffer::Raw(command_buffer));
+    }
+
+    // the frame's command buffers won't execute until `future` has completed, e.g. an upload
+    // of resources they use
+    pub fn join(&mut self, future: Box<dyn GpuFuture>) {
+        self.dependencies.push(future);
+    }
+
+    // saves the swapchain image once this frame's command buffers have rendered into it
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    // set once the frame's command buffers have been submitted, e.g. so that queries written
+    // by them are only read if they were
+    pub fn submitted(&self) -> &Arc<AtomicBool> {
+        &self.submitted
+    }
+
+    pub fn into_parts(
+        mut self,
+    ) -> (
+        Vec<FrameCommandBuffer>,
+        Vec<Box<dyn GpuFuture>>,
+        bool,
+        Arc<AtomicBool>,
+    ) {
+        self.flush_raw_commands();
+        (
+            self.command_buffers,
+            self.dependencies,
+            self.screenshot_requested,
+            self.submitted,
+        )
+    }
+}
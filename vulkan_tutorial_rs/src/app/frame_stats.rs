@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTimeSummary {
+    pub min: Duration,
+    pub average: Duration,
+    pub max: Duration,
+    pub p99: Duration,
+}
+
+impl FrameTimeSummary {
+    pub fn fps(&self) -> f64 {
+        if self.average == Duration::from_secs(0) {
+            0.0
+        } else {
+            1.0 / self.average.as_secs_f64()
+        }
+    }
+}
+
+// the last `capacity` frame times, oldest first
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl FrameStats {
+    pub fn new(capacity: usize) -> FrameStats {
+        assert!(capacity > 0, "frame history must hold at least one frame");
+        FrameStats {
+            frame_times: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub fn frame_times(&self) -> impl Iterator<Item = &Duration> {
+        self.frame_times.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_times.is_empty()
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.frame_times.back().cloned()
+    }
+
+    // `None` until at least one frame has been recorded
+    pub fn summary(&self) -> Option<FrameTimeSummary> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.frame_times.iter().cloned().collect();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+        Some(FrameTimeSummary {
+            min: sorted[0],
+            average: total / sorted.len() as u32,
+            max: sorted[sorted.len() - 1],
+            p99: percentile(&sorted, 0.99),
+        })
+    }
+}
+
+// nearest-rank percentile of an already sorted, non-empty slice
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.max(1) - 1]
+}
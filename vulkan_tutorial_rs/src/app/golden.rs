@@ -0,0 +1,256 @@
+use image::{Rgba, RgbaImage};
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// setting this environment variable to 1 makes `check` create or overwrite the references
+// with the rendered images instead of comparing against them
+pub const UPDATE_ENVIRONMENT_VARIABLE: &str = "UPDATE_GOLDEN";
+// the golden image tests are skipped on machines with no software rasterizer, and for scenes
+// with no reference yet; setting this environment variable to 1 makes them fail instead, e.g. on
+// CI machines with lavapipe
+pub const REQUIRE_ENVIRONMENT_VARIABLE: &str = "REQUIRE_GOLDEN";
+
+// the largest possible YIQ distance between two colours
+const MAX_YIQ_DISTANCE: f32 = 35215.0;
+
+// how different a rendering may be from its reference; rasterizers disagree slightly on
+// edges and filtering, so an exact match is rarely wanted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    // a pixel differs if any channel differs by more than this
+    pub channel: u8,
+    // ... or if its perceptual distance (see `perceptual_distance`) is above this, in [0, 1]
+    pub perceptual: f32,
+    // the comparison fails once more pixels than this differ
+    pub max_differing_pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance {
+            channel: 16,
+            perceptual: 0.1,
+            max_differing_pixels: 0,
+        }
+    }
+}
+
+pub struct Comparison {
+    pub differing_pixels: usize,
+    pub max_channel_difference: u8,
+    pub max_perceptual_distance: f32,
+    // differing pixels in red over a faded copy of the expected image
+    pub diff: RgbaImage,
+}
+
+impl Comparison {
+    pub fn passes(&self, tolerance: &Tolerance) -> bool {
+        self.differing_pixels <= tolerance.max_differing_pixels
+    }
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    DimensionMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    Mismatch {
+        differing_pixels: usize,
+        max_channel_difference: u8,
+        max_perceptual_distance: f32,
+        output_directory: PathBuf,
+    },
+    // the rendered image was written to `actual` for review
+    MissingReference {
+        reference: PathBuf,
+        actual: PathBuf,
+    },
+    Image(PathBuf, image::ImageError),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GoldenError::DimensionMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            GoldenError::Mismatch {
+                differing_pixels,
+                max_channel_difference,
+                max_perceptual_distance,
+                output_directory,
+            } => write!(
+                f,
+                "{} pixel(s) differ (largest channel difference {}, largest perceptual distance \
+                 {:.3}); actual, expected and diff images are in {}",
+                differing_pixels,
+                max_channel_difference,
+                max_perceptual_distance,
+                output_directory.display()
+            ),
+            GoldenError::MissingReference { reference, actual } => write!(
+                f,
+                "there is no reference image {}; the rendered image is in {}, and running with \
+                 {}=1 makes it the reference",
+                reference.display(),
+                actual.display(),
+                UPDATE_ENVIRONMENT_VARIABLE
+            ),
+            GoldenError::Image(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for GoldenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GoldenError::Image(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+pub fn compare(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    tolerance: &Tolerance,
+) -> Result<Comparison, GoldenError> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(GoldenError::DimensionMismatch {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        });
+    }
+    let (width, height) = expected.dimensions();
+    let mut comparison = Comparison {
+        differing_pixels: 0,
+        max_channel_difference: 0,
+        max_perceptual_distance: 0.0,
+        diff: RgbaImage::new(width, height),
+    };
+    for ((e, a), d) in expected
+        .pixels()
+        .zip(actual.pixels())
+        .zip(comparison.diff.pixels_mut())
+    {
+        let channel_difference =
+            e.0.iter()
+                .zip(a.0.iter())
+                .map(|(&e, &a)| (i16::from(e) - i16::from(a)).unsigned_abs() as u8)
+                .max()
+                .unwrap();
+        let perceptual_distance = perceptual_distance(e, a);
+        comparison.max_channel_difference =
+            comparison.max_channel_difference.max(channel_difference);
+        comparison.max_perceptual_distance =
+            comparison.max_perceptual_distance.max(perceptual_distance);
+        if channel_difference > tolerance.channel || perceptual_distance > tolerance.perceptual {
+            comparison.differing_pixels += 1;
+            *d = Rgba([255, 0, 0, 255]);
+        } else {
+            let faded = (255.0 - (255.0 - luma(e)) * 0.1) as u8;
+            *d = Rgba([faded, faded, faded, 255]);
+        }
+    }
+    Ok(comparison)
+}
+
+// the distance between two colours in YIQ space, blended over white, from 0 (identical) to 1
+// (the most different colours); differences in brightness weigh more than differences in hue, roughly
+// as they do to the eye
+pub fn perceptual_distance(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let (y1, i1, q1) = yiq(a);
+    let (y2, i2, q2) = yiq(b);
+    let (y, i, q) = (y1 - y2, i1 - i2, q1 - q2);
+    let distance = 0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q;
+    (distance / MAX_YIQ_DISTANCE).sqrt().min(1.0)
+}
+
+fn yiq(pixel: &Rgba<u8>) -> (f32, f32, f32) {
+    let alpha = f32::from(pixel[3]) / 255.0;
+    let blend = |c: u8| 255.0 + (f32::from(c) - 255.0) * alpha;
+    let (r, g, b) = (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]));
+    (
+        r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_2,
+        r * 0.595_978 - g * 0.274_176_1 - b * 0.321_801_9,
+        r * 0.211_470_2 - g * 0.522_617_3 + b * 0.311_147_1,
+    )
+}
+
+fn luma(pixel: &Rgba<u8>) -> f32 {
+    yiq(pixel).0
+}
+
+// whether an environment variable such as `UPDATE_ENVIRONMENT_VARIABLE` is set to 1
+pub fn is_flag_set(variable: &str) -> bool {
+    env::var(variable).ok().as_deref() == Some("1")
+}
+
+// compares `actual` with `<reference_directory>/<name>.png`. On a mismatch the actual,
+// expected and diff images are written to `output_directory` as `<name>.actual.png` etc. A
+// missing reference is an error too, unless `UPDATE_GOLDEN=1` creates or replaces the
+// references from the rendered images; those must be reviewed before they are committed
+pub fn check(
+    name: &str,
+    actual: &RgbaImage,
+    tolerance: &Tolerance,
+    reference_directory: &Path,
+    output_directory: &Path,
+) -> Result<(), GoldenError> {
+    let reference_path = reference_directory.join(format!("{}.png", name));
+    let actual_path = output_directory.join(format!("{}.actual.png", name));
+    if is_flag_set(UPDATE_ENVIRONMENT_VARIABLE) {
+        println!(
+            "WARNING: Writing golden image {}; review it before committing",
+            reference_path.display()
+        );
+        return save(actual, &reference_path);
+    }
+    if !reference_path.exists() {
+        save(actual, &actual_path)?;
+        return Err(GoldenError::MissingReference {
+            reference: reference_path,
+            actual: actual_path,
+        });
+    }
+
+    let expected = image::open(&reference_path)
+        .map_err(|e| GoldenError::Image(reference_path.clone(), e))?
+        .into_rgba8();
+    let comparison = compare(&expected, actual, tolerance)?;
+    if comparison.passes(tolerance) {
+        return Ok(());
+    }
+    save(actual, &actual_path)?;
+    save(
+        &expected,
+        &output_directory.join(format!("{}.expected.png", name)),
+    )?;
+    save(
+        &comparison.diff,
+        &output_directory.join(format!("{}.diff.png", name)),
+    )?;
+    Err(GoldenError::Mismatch {
+        differing_pixels: comparison.differing_pixels,
+        max_channel_difference: comparison.max_channel_difference,
+        max_perceptual_distance: comparison.max_perceptual_distance,
+        output_directory: output_directory.to_path_buf(),
+    })
+}
+
+fn save(image: &RgbaImage, path: &Path) -> Result<(), GoldenError> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)
+            .map_err(|e| GoldenError::Image(path.to_path_buf(), e.into()))?;
+    }
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| GoldenError::Image(path.to_path_buf(), e))
+}
@@ -2,149 +2,107 @@ pub mod indented_printer;
 
 pub use indented_printer::IndentedPrinter;
 
-use crate::app::{LogicalDevice, PhysicalDeviceInfo, SwapchainInfo};
+use crate::app::{DeviceSelector, PhysicalDeviceInfo, WindowConfig};
 use crate::vulkano_ext::{message_severity_to_string, message_type_to_string};
 
+use vulkano::device::Features;
 use vulkano::instance::debug::{DebugCallback, MessageSeverity, MessageType};
-use vulkano::instance::ApplicationInfo;
+use vulkano::instance::{ApplicationInfo, InstanceCreationError, SupportedExtensionsError};
 use vulkano::instance::{Instance, InstanceExtensions, PhysicalDevice};
 use vulkano::swapchain::Surface;
 
 use vulkano_win::VkSurfaceBuild;
 
-use winit::event_loop::EventLoop;
-use winit::window::{Window, WindowBuilder};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::window::Window;
 
 use std::sync::Arc;
 
-pub fn new_app() -> crate::App {
-    println!("Initialising app...");
-
-    let app_info = create_app_info();
-    println!("App info:\n\t{:?}", app_info);
-
-    let supported_instance_extensions = supported_instance_extensions();
-    println!(
-        "Supported instance extensions:\n\t{:?}",
-        supported_instance_extensions
-    );
-
-    let required_instance_extensions = required_instance_extensions();
-    println!(
-        "Required instance extensions:\n\t{:?}",
-        required_instance_extensions
-    );
-
-    let instance = create_instance(&app_info, &required_instance_extensions);
-    println!("Instance created");
-
-    let debug_callback = setup_debug_callback(&instance);
-    println!("Debug callback set up");
-
-    let event_loop = EventLoop::new();
-    println!("Event loop created");
-
-    let surface = create_surface(&instance, &event_loop);
-    println!("Surface created");
-
-    let physical_device_infos = get_physical_device_infos(&instance, &surface);
-    println!("Physical devices:");
-    for d in physical_device_infos.iter() {
-        d.print("    ", "    ");
-    }
-
-    let physical_device_info = pick_physical_device_info(physical_device_infos);
-    println!("Best device:");
-    physical_device_info.print("    ", "    ");
-
-    let logical_device = LogicalDevice::new(&physical_device_info);
-
-    let swapchain_info = SwapchainInfo::new(
-        &surface,
-        &physical_device_info.physical_device(),
-        &logical_device,
-    );
-
-    crate::App {
-        _instance: instance,
-        _debug_callback: debug_callback,
-        _event_loop: event_loop,
-        _surface: surface,
-        _physical_device_info: physical_device_info,
-        _logical_device: logical_device,
-        _swapchain_info: swapchain_info,
-    }
+// fails if there is no Vulkan loader to ask
+pub fn supported_instance_extensions() -> Result<InstanceExtensions, SupportedExtensionsError> {
+    InstanceExtensions::supported_by_core()
 }
 
-fn supported_instance_extensions() -> InstanceExtensions {
-    InstanceExtensions::supported_by_core().unwrap()
-}
-
-fn create_app_info() -> ApplicationInfo<'static> {
+pub fn create_app_info() -> ApplicationInfo<'static> {
     vulkano::app_info_from_cargo_toml!()
 }
 
-fn required_instance_extensions() -> InstanceExtensions {
-    let mut extensions = vulkano_win::required_extensions();
-    if crate::app::config::ENABLE_VALIDATION_LAYERS {
+pub fn required_instance_extensions(headless: bool, validation: bool) -> InstanceExtensions {
+    let mut extensions = if headless {
+        InstanceExtensions::none()
+    } else {
+        vulkano_win::required_extensions()
+    };
+    if validation {
         extensions.ext_debug_utils = true;
     }
     extensions
 }
 
-fn create_instance(
+pub fn create_instance(
     app_info: &ApplicationInfo,
     required_instance_extensions: &InstanceExtensions,
-) -> Arc<Instance> {
-    if crate::app::config::ENABLE_VALIDATION_LAYERS && check_validation_layer_support() {
+    validation: bool,
+) -> Result<Arc<Instance>, InstanceCreationError> {
+    if validation && check_validation_layer_support() {
         Instance::new(
             Some(&app_info),
             required_instance_extensions,
             crate::app::config::VALIDATION_LAYERS.iter().cloned(),
         )
-        .expect("failed to create Vulkan instance")
     } else {
-        println!("WARNING: Validation layers requested, but not available!");
+        if validation {
+            println!("WARNING: Validation layers requested, but not available!");
+        }
         Instance::new(Some(&app_info), required_instance_extensions, None)
-            .expect("failed to create Vulkan instance")
     }
 }
 
-fn create_surface(instance: &Arc<Instance>, event_loop: &EventLoop<()>) -> Arc<Surface<Window>> {
-    WindowBuilder::new()
-        .build_vk_surface(event_loop, instance.clone())
-        .unwrap()
+pub fn create_surface(
+    instance: &Arc<Instance>,
+    event_loop: &EventLoopWindowTarget<()>,
+    window_config: &WindowConfig,
+) -> Result<Arc<Surface<Window>>, vulkano_win::CreationError> {
+    let surface = window_config
+        .window_builder()
+        .build_vk_surface(event_loop, instance.clone())?;
+    window_config.place(surface.window(), event_loop);
+    Ok(surface)
 }
 
-fn get_physical_device_infos(
+pub fn get_physical_device_infos(
     instance: &Arc<Instance>,
-    surface: &Arc<Surface<Window>>,
+    surfaces: &[Arc<Surface<Window>>],
 ) -> Vec<PhysicalDeviceInfo> {
     PhysicalDevice::enumerate(instance)
-        .map(|d| PhysicalDeviceInfo::new(&d, surface))
+        .map(|d| PhysicalDeviceInfo::new(&d, surfaces))
         .collect()
 }
 
-fn pick_physical_device_info(devices: Vec<PhysicalDeviceInfo>) -> PhysicalDeviceInfo {
+pub fn pick_physical_device_info(
+    devices: Vec<PhysicalDeviceInfo>,
+    features: &Features,
+    selector: &DeviceSelector,
+) -> Option<PhysicalDeviceInfo> {
     devices
         .into_iter()
-        .filter(|d| d.is_valid())
-        .max_by(|a, b| a.cmp(&b))
-        .expect("no valid physical devices found")
+        .filter(|d| d.is_valid() && d.supports_features(features))
+        .max_by(|a, b| selector(a, b))
 }
 
-fn check_validation_layer_support() -> bool {
-    let layers: Vec<_> = vulkano::instance::layers_list()
-        .unwrap()
-        .map(|l| l.name().to_owned())
-        .collect();
+// false if the layers can't be listed
+pub fn check_validation_layer_support() -> bool {
+    let layers: Vec<_> = match vulkano::instance::layers_list() {
+        Ok(layers) => layers.map(|l| l.name().to_owned()).collect(),
+        Err(_) => return false,
+    };
     crate::app::config::VALIDATION_LAYERS
         .iter()
         .all(|layer_name| layers.contains(&layer_name.to_string()))
 }
 
-fn setup_debug_callback(instance: &Arc<Instance>) -> Option<DebugCallback> {
-    if !crate::app::config::ENABLE_VALIDATION_LAYERS {
+pub fn setup_debug_callback(instance: &Arc<Instance>, validation: bool) -> Option<DebugCallback> {
+    if !validation {
         return None;
     }
 
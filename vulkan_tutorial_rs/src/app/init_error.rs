@@ -0,0 +1,73 @@
+use vulkano::device::DeviceCreationError;
+use vulkano::instance::{InstanceCreationError, SupportedExtensionsError};
+use vulkano::swapchain::SwapchainCreationError;
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum InitError {
+    // usually means no Vulkan loader is installed
+    Loading(SupportedExtensionsError),
+    InstanceCreation(InstanceCreationError),
+    WindowCreation(vulkano_win::CreationError),
+    NoSuitableDevice,
+    DeviceCreation(DeviceCreationError),
+    SwapchainCreation(SwapchainCreationError),
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InitError::Loading(e) => write!(f, "failed to load Vulkan: {}", e),
+            InitError::InstanceCreation(e) => write!(f, "failed to create Vulkan instance: {}", e),
+            InitError::WindowCreation(e) => write!(f, "failed to create window surface: {}", e),
+            InitError::NoSuitableDevice => write!(f, "no valid physical devices found"),
+            InitError::DeviceCreation(e) => write!(f, "failed to create logical device: {}", e),
+            InitError::SwapchainCreation(e) => write!(f, "failed to create swapchain: {}", e),
+        }
+    }
+}
+
+impl Error for InitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InitError::Loading(e) => Some(e),
+            InitError::InstanceCreation(e) => Some(e),
+            InitError::WindowCreation(e) => Some(e),
+            InitError::NoSuitableDevice => None,
+            InitError::DeviceCreation(e) => Some(e),
+            InitError::SwapchainCreation(e) => Some(e),
+        }
+    }
+}
+
+impl From<SupportedExtensionsError> for InitError {
+    fn from(e: SupportedExtensionsError) -> InitError {
+        InitError::Loading(e)
+    }
+}
+
+impl From<InstanceCreationError> for InitError {
+    fn from(e: InstanceCreationError) -> InitError {
+        InitError::InstanceCreation(e)
+    }
+}
+
+impl From<vulkano_win::CreationError> for InitError {
+    fn from(e: vulkano_win::CreationError) -> InitError {
+        InitError::WindowCreation(e)
+    }
+}
+
+impl From<DeviceCreationError> for InitError {
+    fn from(e: DeviceCreationError) -> InitError {
+        InitError::DeviceCreation(e)
+    }
+}
+
+impl From<SwapchainCreationError> for InitError {
+    fn from(e: SwapchainCreationError) -> InitError {
+        InitError::SwapchainCreation(e)
+    }
+}
@@ -0,0 +1,280 @@
+pub mod bindings;
+
+use crate::app::config;
+use crate::app::input::bindings::{AxisBinding, ButtonBinding, InputBindings};
+
+use cgmath::{Vector2, Zero};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{
+    AxisId, DeviceEvent, ElementState, Event, MouseButton, MouseScrollDelta, VirtualKeyCode,
+    WindowEvent,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+// which buttons are down, and which went down or up since the last frame
+struct ButtonStates<T> {
+    held: HashSet<T>,
+    pressed: HashSet<T>,
+    released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> ButtonStates<T> {
+    fn new() -> ButtonStates<T> {
+        ButtonStates {
+            held: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+        }
+    }
+
+    // key repeats arrive as further presses and are ignored
+    fn set(&mut self, button: T, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                if self.held.insert(button) {
+                    self.pressed.insert(button);
+                }
+            }
+            ElementState::Released => {
+                if self.held.remove(&button) {
+                    self.released.insert(button);
+                }
+            }
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.released.extend(self.held.drain());
+    }
+
+    fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+}
+
+// the keyboard, mouse and device axis state collected from winit events, queried directly or
+// through the named actions and axes of its bindings. `App::run` feeds it every event before
+// the application's `on_event` and ends its frame after each fixed update, so "pressed",
+// "released" and movement mean since the previous update: each is seen by exactly one update,
+// including input from frames that ran none. Events can also be injected through the methods
+// `handle_event` calls
+pub struct Input {
+    bindings: InputBindings,
+    keys: ButtonStates<VirtualKeyCode>,
+    mouse_buttons: ButtonStates<MouseButton>,
+    cursor_position: Option<PhysicalPosition<f64>>,
+    cursor_delta: Vector2<f32>,
+    mouse_delta: Vector2<f32>,
+    scroll_delta: Vector2<f32>,
+    device_axes: HashMap<AxisId, f64>,
+}
+
+impl Input {
+    pub fn new(bindings: InputBindings) -> Input {
+        Input {
+            bindings,
+            keys: ButtonStates::new(),
+            mouse_buttons: ButtonStates::new(),
+            cursor_position: None,
+            cursor_delta: Vector2::zero(),
+            mouse_delta: Vector2::zero(),
+            scroll_delta: Vector2::zero(),
+            device_axes: HashMap::new(),
+        }
+    }
+
+    pub fn bindings(&self) -> &InputBindings {
+        &self.bindings
+    }
+
+    // rebinding takes effect immediately, including for buttons that are already held
+    pub fn bindings_mut(&mut self) -> &mut InputBindings {
+        &mut self.bindings
+    }
+
+    pub fn handle_event(&mut self, event: &Event<()>) {
+        match *event {
+            Event::WindowEvent { ref event, .. } => self.handle_window_event(event),
+            Event::DeviceEvent { ref event, .. } => self.handle_device_event(event),
+            _ => (),
+        }
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::KeyboardInput { input, .. } => {
+                if let Some(key) = input.virtual_keycode {
+                    self.key(key, input.state);
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => self.mouse_button(button, state),
+            WindowEvent::CursorMoved { position, .. } => self.cursor_moved(position),
+            WindowEvent::CursorLeft { .. } => self.cursor_left(),
+            WindowEvent::MouseWheel { delta, .. } => self.mouse_wheel(delta),
+            WindowEvent::Focused(false) => self.focus_lost(),
+            _ => (),
+        }
+    }
+
+    fn handle_device_event(&mut self, event: &DeviceEvent) {
+        match *event {
+            DeviceEvent::MouseMotion { delta } => self.mouse_motion(delta),
+            DeviceEvent::Motion { axis, value } => self.device_motion(axis, value),
+            _ => (),
+        }
+    }
+
+    pub fn key(&mut self, key: VirtualKeyCode, state: ElementState) {
+        self.keys.set(key, state);
+    }
+
+    pub fn mouse_button(&mut self, button: MouseButton, state: ElementState) {
+        self.mouse_buttons.set(button, state);
+    }
+
+    // with several windows, the position is in whichever window the cursor last moved over
+    pub fn cursor_moved(&mut self, position: PhysicalPosition<f64>) {
+        if let Some(last) = self.cursor_position {
+            self.cursor_delta +=
+                Vector2::new((position.x - last.x) as f32, (position.y - last.y) as f32);
+        }
+        self.cursor_position = Some(position);
+    }
+
+    // so that re-entering the window elsewhere doesn't count as movement
+    pub fn cursor_left(&mut self) {
+        self.cursor_position = None;
+    }
+
+    pub fn mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        self.scroll_delta += match delta {
+            MouseScrollDelta::LineDelta(x, y) => Vector2::new(x, y),
+            MouseScrollDelta::PixelDelta(delta) => {
+                Vector2::new(delta.x as f32, delta.y as f32) / config::PIXELS_PER_SCROLL_LINE
+            }
+        };
+    }
+
+    pub fn mouse_motion(&mut self, delta: (f64, f64)) {
+        self.mouse_delta += Vector2::new(delta.0 as f32, delta.1 as f32);
+    }
+
+    pub fn device_motion(&mut self, axis: AxisId, value: f64) {
+        self.device_axes.insert(axis, value);
+    }
+
+    // releases everything, since releases are missed while another window has focus
+    pub fn focus_lost(&mut self) {
+        self.keys.release_all();
+        self.mouse_buttons.release_all();
+    }
+
+    // called by `App::run` after each fixed update
+    pub fn end_frame(&mut self) {
+        self.keys.end_frame();
+        self.mouse_buttons.end_frame();
+        self.cursor_delta = Vector2::zero();
+        self.mouse_delta = Vector2::zero();
+        self.scroll_delta = Vector2::zero();
+    }
+
+    pub fn is_button_held(&self, button: ButtonBinding) -> bool {
+        match button {
+            ButtonBinding::Key(key) => self.keys.held.contains(&key),
+            ButtonBinding::Mouse(button) => self.mouse_buttons.held.contains(&button),
+        }
+    }
+
+    pub fn was_button_pressed(&self, button: ButtonBinding) -> bool {
+        match button {
+            ButtonBinding::Key(key) => self.keys.pressed.contains(&key),
+            ButtonBinding::Mouse(button) => self.mouse_buttons.pressed.contains(&button),
+        }
+    }
+
+    pub fn was_button_released(&self, button: ButtonBinding) -> bool {
+        match button {
+            ButtonBinding::Key(key) => self.keys.released.contains(&key),
+            ButtonBinding::Mouse(button) => self.mouse_buttons.released.contains(&button),
+        }
+    }
+
+    // an action is held while any of its buttons is, and pressed or released when any of them
+    // was; unknown actions never are
+    pub fn is_held(&self, action: &str) -> bool {
+        self.bindings
+            .action(action)
+            .iter()
+            .any(|&button| self.is_button_held(button))
+    }
+
+    pub fn was_pressed(&self, action: &str) -> bool {
+        self.bindings
+            .action(action)
+            .iter()
+            .any(|&button| self.was_button_pressed(button))
+    }
+
+    pub fn was_released(&self, action: &str) -> bool {
+        self.bindings
+            .action(action)
+            .iter()
+            .any(|&button| self.was_button_released(button))
+    }
+
+    // 0 for unknown axes
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.bindings
+            .axis(axis)
+            .iter()
+            .map(|&binding| self.axis_binding_value(binding))
+            .sum()
+    }
+
+    fn axis_binding_value(&self, binding: AxisBinding) -> f32 {
+        match binding {
+            AxisBinding::Buttons { positive, negative } => {
+                let value = |button| {
+                    if self.is_button_held(button) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                };
+                value(positive) - value(negative)
+            }
+            AxisBinding::MouseX => self.mouse_delta.x,
+            AxisBinding::MouseY => self.mouse_delta.y,
+            AxisBinding::ScrollX => self.scroll_delta.x,
+            AxisBinding::ScrollY => self.scroll_delta.y,
+            AxisBinding::Device(axis) => self.device_axis(axis) as f32,
+        }
+    }
+
+    pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+        self.cursor_position
+    }
+
+    // in physical pixels since the last frame
+    pub fn cursor_delta(&self) -> Vector2<f32> {
+        self.cursor_delta
+    }
+
+    pub fn mouse_delta(&self) -> Vector2<f32> {
+        self.mouse_delta
+    }
+
+    // in lines since the last frame; positive `y` scrolls up
+    pub fn scroll_delta(&self) -> Vector2<f32> {
+        self.scroll_delta
+    }
+
+    // 0 until the axis has reported a value
+    pub fn device_axis(&self, axis: AxisId) -> f64 {
+        self.device_axes.get(&axis).copied().unwrap_or(0.0)
+    }
+}
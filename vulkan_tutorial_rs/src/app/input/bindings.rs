@@ -0,0 +1,96 @@
+use winit::event::{AxisId, MouseButton, VirtualKeyCode};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ButtonBinding {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+}
+
+// an axis's value is the sum of its bindings' values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AxisBinding {
+    // 1 while `positive` is held, -1 while `negative` is and 0 while both or neither are
+    Buttons {
+        positive: ButtonBinding,
+        negative: ButtonBinding,
+    },
+    // raw mouse movement since the last frame, unaffected by cursor speed and not stopped by the
+    // window's edges
+    MouseX,
+    MouseY,
+    // lines scrolled since the last frame
+    ScrollX,
+    ScrollY,
+    // the latest value of a device axis such as a joystick's, as reported by
+    // `DeviceEvent::Motion`
+    Device(AxisId),
+}
+
+// named actions and axes, each with any number of bindings
+#[derive(Clone, Debug)]
+pub struct InputBindings {
+    actions: HashMap<String, Vec<ButtonBinding>>,
+    axes: HashMap<String, Vec<AxisBinding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> InputBindings {
+        InputBindings::new()
+    }
+}
+
+impl InputBindings {
+    pub fn new() -> InputBindings {
+        InputBindings {
+            actions: HashMap::new(),
+            axes: HashMap::new(),
+        }
+    }
+
+    // adds to the action's existing bindings
+    pub fn bind_action(&mut self, action: &str, button: ButtonBinding) {
+        let buttons = self.actions.entry(action.to_string()).or_default();
+        if !buttons.contains(&button) {
+            buttons.push(button);
+        }
+    }
+
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        let bindings = self.axes.entry(axis.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    // removes all of the action's bindings, e.g. before rebinding it
+    pub fn unbind_action(&mut self, action: &str) {
+        self.actions.remove(action);
+    }
+
+    pub fn unbind_axis(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    // empty for unknown actions
+    pub fn action(&self, action: &str) -> &[ButtonBinding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn axis(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // the actions `button` is bound to, e.g. to warn about a conflicting binding
+    pub fn actions_bound_to(&self, button: ButtonBinding) -> Vec<&str> {
+        let mut actions: Vec<&str> = self
+            .actions
+            .iter()
+            .filter(|(_, buttons)| buttons.contains(&button))
+            .map(|(action, _)| action.as_str())
+            .collect();
+        actions.sort_unstable();
+        actions
+    }
+}
@@ -1,55 +1,115 @@
-use crate::app::config::required_device_extensions;
-use crate::app::PhysicalDeviceInfo;
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::paths;
+use crate::app::pipeline_cache::{self, PipelineCacheKey};
+use crate::app::{PhysicalDeviceInfo, Uploader};
 use crate::vulkano_ext::QueueFamilyExt;
 use std::collections::BTreeSet;
-use std::sync::Arc;
-use vulkano::device::{Device, Features, Queue};
+use std::sync::{Arc, Mutex, MutexGuard};
+use vulkano::device::{Device, DeviceCreationError, Features, Queue};
+use vulkano::instance::QueueFamily;
+use vulkano::pipeline::cache::PipelineCache;
+use vulkano::OomError;
 
 pub struct LogicalDevice {
     device: Arc<Device>,
     graphics_queue: Arc<Queue>,
     present_queue: Arc<Queue>,
+    transfer_queue: Option<Arc<Queue>>,
+    pipeline_cache: Arc<PipelineCache>,
+    pipeline_cache_key: PipelineCacheKey,
+    uploader: Mutex<Uploader>,
 }
 
 impl LogicalDevice {
-    pub fn new(physical_device_info: &PhysicalDeviceInfo) -> LogicalDevice {
+    pub fn new(
+        physical_device_info: &PhysicalDeviceInfo,
+        features: &Features,
+    ) -> Result<LogicalDevice, DeviceCreationError> {
         let physical_device = physical_device_info.physical_device();
 
-        let features = Features::none();
+        let extensions = physical_device_info.required_extensions();
 
-        let extensions = required_device_extensions();
+        // `features` are required; anisotropic filtering is also enabled whenever it's supported
+        let features = Features {
+            sampler_anisotropy: features.sampler_anisotropy
+                || (config::ENABLE_SAMPLER_ANISOTROPY
+                    && physical_device.supported_features().sampler_anisotropy),
+            ..features.clone()
+        };
 
         let families_and_priorities = {
             let families = physical_device_info.queue_families();
             let graphics_family =
                 QueueFamilyExt::new(families.graphics_family(&physical_device).unwrap());
-            let present_family =
-                QueueFamilyExt::new(families.present_family(&physical_device).unwrap());
+            let present_family = families
+                .present_family(&physical_device)
+                .map(QueueFamilyExt::new);
+            let transfer_family = families
+                .transfer_family(&physical_device)
+                .map(QueueFamilyExt::new);
             let queue_priority = 1.0f32;
             let mut families_set: BTreeSet<QueueFamilyExt> = BTreeSet::new();
             families_set.insert(graphics_family);
-            families_set.insert(present_family);
+            families_set.extend(present_family);
+            families_set.extend(transfer_family);
             families_set
                 .into_iter()
                 .map(move |f| (f.inner(), queue_priority))
         };
 
-        let (device, mut queues) = vulkano::device::Device::new(
+        let (device, queues) = vulkano::device::Device::new(
             physical_device,
             &features,
             &extensions,
             families_and_priorities,
-        )
-        .unwrap();
+        )?;
+
+        // one queue is created per distinct family, in family id order
+        let queues: Vec<Arc<Queue>> = queues.collect();
+        let families = physical_device_info.queue_families();
+        let queue_for = |family: Option<QueueFamily>| {
+            family.and_then(|f| queues.iter().find(|q| q.family().id() == f.id()).cloned())
+        };
+        let graphics_queue = queue_for(families.graphics_family(&physical_device)).unwrap();
+        let present_queue = queue_for(families.present_family(&physical_device))
+            .unwrap_or_else(|| graphics_queue.clone());
+        let transfer_queue = queue_for(families.transfer_family(&physical_device));
+
+        debug_utils::set_name(&*device, &*device, &physical_device.name());
+        // the graphics and present queues are often the same queue
+        debug_utils::set_name(&*device, &**present_queue, "present queue");
+        debug_utils::set_name(&*device, &**graphics_queue, "graphics queue");
+        if let Some(ref transfer_queue) = transfer_queue {
+            debug_utils::set_name(&*device, &**transfer_queue, "transfer queue");
+        }
+
+        let pipeline_cache_key = PipelineCacheKey::new(&physical_device);
+        let pipeline_cache = pipeline_cache::load(
+            &device,
+            &pipeline_cache_key,
+            config::PIPELINE_CACHE_DIRECTORY
+                .map(paths::data_path)
+                .as_deref(),
+        );
 
-        let graphics_queue = queues.next().unwrap();
-        let present_queue = queues.next().unwrap_or_else(|| graphics_queue.clone());
+        let uploader = Uploader::new(
+            device.clone(),
+            transfer_queue
+                .clone()
+                .unwrap_or_else(|| graphics_queue.clone()),
+            graphics_queue.clone(),
+        );
 
-        LogicalDevice {
+        Ok(LogicalDevice {
             device,
             graphics_queue,
             present_queue,
-        }
+            transfer_queue,
+            pipeline_cache,
+            pipeline_cache_key,
+            uploader: Mutex::new(uploader),
+        })
     }
 
     pub fn device(&self) -> &Arc<Device> {
@@ -63,4 +123,46 @@ impl LogicalDevice {
     pub fn present_queue(&self) -> &Arc<Queue> {
         &self.present_queue
     }
+
+    // the dedicated transfer queue if the device has one, otherwise the graphics queue
+    pub fn transfer_queue(&self) -> &Arc<Queue> {
+        self.transfer_queue.as_ref().unwrap_or(&self.graphics_queue)
+    }
+
+    pub fn has_dedicated_transfer_queue(&self) -> bool {
+        self.transfer_queue.is_some()
+    }
+
+    // shared by everything uploading to the device, so its staging buffers are only
+    // allocated once
+    pub fn uploader(&self) -> MutexGuard<Uploader> {
+        self.uploader.lock().unwrap()
+    }
+
+    // every pipeline should be built with this so later runs can skip compiling it again
+    pub fn pipeline_cache(&self) -> &Arc<PipelineCache> {
+        &self.pipeline_cache
+    }
+
+    pub fn save_pipeline_cache(&self) {
+        let directory = match config::PIPELINE_CACHE_DIRECTORY {
+            Some(directory) => paths::data_path(directory),
+            None => return,
+        };
+        if let Err(e) =
+            pipeline_cache::save(&self.pipeline_cache, &self.pipeline_cache_key, &directory)
+        {
+            println!("WARNING: Failed to save pipeline cache: {}", e);
+        }
+    }
+
+    // blocks until every queue of the device has finished its submitted work
+    pub fn wait_idle(&self) -> Result<(), OomError> {
+        self.graphics_queue.wait()?;
+        self.present_queue.wait()?;
+        match self.transfer_queue {
+            Some(ref transfer_queue) => transfer_queue.wait(),
+            None => Ok(()),
+        }
+    }
 }
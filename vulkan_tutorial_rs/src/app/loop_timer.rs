@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+// fixed-timestep accumulator: updates always advance by `timestep`, and rendering gets an
+// interpolation alpha for the time left over
+pub struct LoopTimer {
+    timestep: Duration,
+    max_updates_per_frame: u32,
+    accumulator: Duration,
+    min_frame_time: Option<Duration>,
+    last_frame: Option<Instant>,
+}
+
+impl LoopTimer {
+    pub fn new(
+        update_rate: u32,
+        max_updates_per_frame: u32,
+        frame_rate_cap: Option<u32>,
+    ) -> LoopTimer {
+        assert!(update_rate > 0, "update rate must be positive");
+        assert!(
+            max_updates_per_frame > 0,
+            "at least one update per frame must be allowed"
+        );
+        LoopTimer {
+            timestep: Duration::from_secs(1) / update_rate,
+            max_updates_per_frame,
+            accumulator: Duration::from_secs(0),
+            min_frame_time: frame_rate_cap.map(|fps| Duration::from_secs(1) / fps.max(1)),
+            last_frame: None,
+        }
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    // when a frame may start under the frame-rate cap; `None` if uncapped or no frame ran yet
+    pub fn next_frame_at(&self) -> Option<Instant> {
+        match (self.last_frame, self.min_frame_time) {
+            (Some(last), Some(min)) => Some(last + min),
+            _ => None,
+        }
+    }
+
+    pub fn is_frame_due(&self, now: Instant) -> bool {
+        self.next_frame_at().map_or(true, |at| now >= at)
+    }
+
+    // starts a frame at `now`, returning the time since the previous frame (`None` for the
+    // first frame)
+    pub fn begin_frame(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = self.last_frame.map(|last| now - last);
+        self.last_frame = Some(now);
+        elapsed
+    }
+
+    // adds `elapsed` to the accumulator and returns how many fixed updates to run; time beyond
+    // `max_updates_per_frame` steps is dropped so a slow frame can't snowball
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+        let mut steps = 0;
+        while self.accumulator >= self.timestep {
+            self.accumulator -= self.timestep;
+            steps += 1;
+            if steps == self.max_updates_per_frame {
+                self.accumulator = Duration::from_secs(0);
+                break;
+            }
+        }
+        steps
+    }
+
+    // how far between the last update and the next one the current time is, in [0, 1)
+    pub fn alpha(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.timestep.as_secs_f64()
+    }
+}
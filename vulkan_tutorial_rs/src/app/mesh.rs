@@ -0,0 +1,63 @@
+pub mod builtin;
+pub mod processing;
+
+use crate::app::debug_utils;
+use crate::app::{Pod, Uploader};
+
+use vulkano::buffer::{BufferUsage, ImmutableBuffer, TypedBufferAccess};
+
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+    pub uv: [f32; 2],
+    pub normal: [f32; 3],
+    // `w` is the bitangent's handedness, +1 or -1
+    pub tangent: [f32; 4],
+}
+
+vulkano::impl_vertex!(Vertex, position, color, uv, normal, tangent);
+
+// only `f32`s, so there's no padding between them
+unsafe impl Pod for Vertex {}
+
+// CPU-side mesh, e.g. from `builtin` or a model loader
+#[derive(Clone, Debug, Default)]
+pub struct MeshData<V> {
+    pub vertices: Vec<V>,
+    pub indices: Vec<u32>,
+}
+
+// device-local vertex and index buffers
+pub struct Mesh<V> {
+    vertex_buffer: Arc<ImmutableBuffer<[V]>>,
+    index_buffer: Arc<ImmutableBuffer<[u32]>>,
+}
+
+impl<V: Pod> Mesh<V> {
+    // the copies are recorded into `uploader`'s batch; join its `flush` future before the
+    // mesh is drawn
+    pub fn upload(uploader: &mut Uploader, data: &MeshData<V>) -> Mesh<V> {
+        let mesh = Mesh {
+            vertex_buffer: uploader.upload_buffer(&data.vertices, BufferUsage::vertex_buffer()),
+            index_buffer: uploader.upload_buffer(&data.indices, BufferUsage::index_buffer()),
+        };
+        debug_utils::set_buffer_name(uploader.device(), &*mesh.vertex_buffer, "mesh vertices");
+        debug_utils::set_buffer_name(uploader.device(), &*mesh.index_buffer, "mesh indices");
+        mesh
+    }
+
+    pub fn vertex_buffer(&self) -> &Arc<ImmutableBuffer<[V]>> {
+        &self.vertex_buffer
+    }
+
+    pub fn index_buffer(&self) -> &Arc<ImmutableBuffer<[u32]>> {
+        &self.index_buffer
+    }
+
+    pub fn index_count(&self) -> u32 {
+        self.index_buffer.len() as u32
+    }
+}
@@ -0,0 +1,131 @@
+use crate::app::mesh::processing;
+use crate::app::mesh::{MeshData, Vertex};
+
+const RED: [f32; 3] = [1.0, 0.0, 0.0];
+const GREEN: [f32; 3] = [0.0, 1.0, 0.0];
+const BLUE: [f32; 3] = [0.0, 0.0, 1.0];
+const YELLOW: [f32; 3] = [1.0, 1.0, 0.0];
+const CYAN: [f32; 3] = [0.0, 1.0, 1.0];
+const MAGENTA: [f32; 3] = [1.0, 0.0, 1.0];
+
+// texture coordinates of a face's corners, in the order the faces list them
+const FACE_UVS: [[f32; 2]; 4] = [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]];
+
+fn vertex(position: [f32; 3], color: [f32; 3], uv: [f32; 2]) -> Vertex {
+    Vertex {
+        position,
+        color,
+        uv,
+        ..Vertex::default()
+    }
+}
+
+fn with_normals_and_tangents(mut data: MeshData<Vertex>) -> MeshData<Vertex> {
+    processing::generate_normals(&mut data);
+    processing::generate_tangents(&mut data);
+    data
+}
+
+// all built-in meshes are centred on the origin with a size of 1 along each used axis
+
+pub fn triangle() -> MeshData<Vertex> {
+    with_normals_and_tangents(MeshData {
+        vertices: vec![
+            vertex([0.0, -0.5, 0.0], RED, [0.5, 0.0]),
+            vertex([0.5, 0.5, 0.0], GREEN, [1.0, 1.0]),
+            vertex([-0.5, 0.5, 0.0], BLUE, [0.0, 1.0]),
+        ],
+        indices: vec![0, 1, 2],
+    })
+}
+
+pub fn quad() -> MeshData<Vertex> {
+    with_normals_and_tangents(MeshData {
+        vertices: vec![
+            vertex([-0.5, -0.5, 0.0], RED, [0.0, 0.0]),
+            vertex([0.5, -0.5, 0.0], GREEN, [1.0, 0.0]),
+            vertex([0.5, 0.5, 0.0], BLUE, [1.0, 1.0]),
+            vertex([-0.5, 0.5, 0.0], YELLOW, [0.0, 1.0]),
+        ],
+        indices: vec![0, 1, 2, 2, 3, 0],
+    })
+}
+
+// each face has its own four vertices (and colour) so faces get flat normals
+pub fn cube() -> MeshData<Vertex> {
+    let faces: [([[f32; 3]; 4], [f32; 3]); 6] = [
+        // +x
+        (
+            [
+                [0.5, -0.5, -0.5],
+                [0.5, 0.5, -0.5],
+                [0.5, 0.5, 0.5],
+                [0.5, -0.5, 0.5],
+            ],
+            RED,
+        ),
+        // -x
+        (
+            [
+                [-0.5, -0.5, 0.5],
+                [-0.5, 0.5, 0.5],
+                [-0.5, 0.5, -0.5],
+                [-0.5, -0.5, -0.5],
+            ],
+            CYAN,
+        ),
+        // +y
+        (
+            [
+                [-0.5, 0.5, -0.5],
+                [-0.5, 0.5, 0.5],
+                [0.5, 0.5, 0.5],
+                [0.5, 0.5, -0.5],
+            ],
+            GREEN,
+        ),
+        // -y
+        (
+            [
+                [-0.5, -0.5, 0.5],
+                [-0.5, -0.5, -0.5],
+                [0.5, -0.5, -0.5],
+                [0.5, -0.5, 0.5],
+            ],
+            MAGENTA,
+        ),
+        // +z
+        (
+            [
+                [-0.5, -0.5, 0.5],
+                [0.5, -0.5, 0.5],
+                [0.5, 0.5, 0.5],
+                [-0.5, 0.5, 0.5],
+            ],
+            BLUE,
+        ),
+        // -z
+        (
+            [
+                [0.5, -0.5, -0.5],
+                [-0.5, -0.5, -0.5],
+                [-0.5, 0.5, -0.5],
+                [0.5, 0.5, -0.5],
+            ],
+            YELLOW,
+        ),
+    ];
+    let mut data = MeshData::default();
+    for (corners, color) in faces.iter() {
+        let base = data.vertices.len() as u32;
+        data.vertices.extend(
+            corners
+                .iter()
+                .zip(FACE_UVS.iter())
+                .map(|(&position, &uv)| vertex(position, *color, uv)),
+        );
+        data.indices
+            .extend(&[base, base + 1, base + 2, base + 2, base + 3, base]);
+    }
+    with_normals_and_tangents(data)
+}
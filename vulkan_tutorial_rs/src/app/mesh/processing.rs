@@ -0,0 +1,128 @@
+use crate::app::mesh::{MeshData, Vertex};
+
+use cgmath::{InnerSpace, Vector2, Vector3};
+
+use std::collections::HashMap;
+
+// merges bitwise identical vertices and rewrites the indices to match
+pub fn deduplicate(data: &mut MeshData<Vertex>) {
+    let mut unique: HashMap<[u32; 15], u32> = HashMap::with_capacity(data.vertices.len());
+    let mut vertices = Vec::with_capacity(data.vertices.len());
+    let remap: Vec<u32> = data
+        .vertices
+        .iter()
+        .map(|vertex| {
+            *unique.entry(vertex_key(vertex)).or_insert_with(|| {
+                vertices.push(*vertex);
+                vertices.len() as u32 - 1
+            })
+        })
+        .collect();
+    for index in data.indices.iter_mut() {
+        *index = remap[*index as usize];
+    }
+    data.vertices = vertices;
+}
+
+pub fn has_normals(data: &MeshData<Vertex>) -> bool {
+    data.vertices.iter().any(|v| v.normal != [0.0; 3])
+}
+
+pub fn has_tangents(data: &MeshData<Vertex>) -> bool {
+    data.vertices.iter().any(|v| v.tangent != [0.0; 4])
+}
+
+// smooth normals from the area-weighted normals of the triangles sharing each vertex; triangles
+// are assumed to wind counter-clockwise around their front face
+pub fn generate_normals(data: &mut MeshData<Vertex>) {
+    let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); data.vertices.len()];
+    for triangle in data.indices.chunks_exact(3) {
+        let [a, b, c] = positions(data, triangle);
+        // the cross product's length is twice the triangle's area
+        let normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += normal;
+        }
+    }
+    for (vertex, normal) in data.vertices.iter_mut().zip(normals) {
+        vertex.normal = normalize_or(normal, Vector3::unit_z()).into();
+    }
+}
+
+// per-vertex tangents from the texture coordinate gradients, with the bitangent's handedness in
+// `w`; needs normals
+pub fn generate_tangents(data: &mut MeshData<Vertex>) {
+    let zero = Vector3::new(0.0, 0.0, 0.0);
+    let mut tangents = vec![zero; data.vertices.len()];
+    let mut bitangents = vec![zero; data.vertices.len()];
+    for triangle in data.indices.chunks_exact(3) {
+        let [a, b, c] = positions(data, triangle);
+        let [uv_a, uv_b, uv_c] = uvs(data, triangle);
+        let (edge_1, edge_2) = (b - a, c - a);
+        let (delta_1, delta_2) = (uv_b - uv_a, uv_c - uv_a);
+        let determinant = delta_1.x * delta_2.y - delta_2.x * delta_1.y;
+        if determinant.abs() <= f32::EPSILON {
+            continue;
+        }
+        let tangent = (edge_1 * delta_2.y - edge_2 * delta_1.y) / determinant;
+        let bitangent = (edge_2 * delta_1.x - edge_1 * delta_2.x) / determinant;
+        for &index in triangle {
+            tangents[index as usize] += tangent;
+            bitangents[index as usize] += bitangent;
+        }
+    }
+    for (i, vertex) in data.vertices.iter_mut().enumerate() {
+        let normal = Vector3::from(vertex.normal);
+        // Gram-Schmidt; vertices without usable texture coordinates get any perpendicular axis
+        let tangent = tangents[i] - normal * normal.dot(tangents[i]);
+        let tangent = normalize_or(tangent, any_perpendicular(normal));
+        let handedness = if normal.cross(tangent).dot(bitangents[i]) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        vertex.tangent = [tangent.x, tangent.y, tangent.z, handedness];
+    }
+}
+
+fn vertex_key(vertex: &Vertex) -> [u32; 15] {
+    let mut key = [0; 15];
+    let components = vertex
+        .position
+        .iter()
+        .chain(&vertex.color)
+        .chain(&vertex.uv)
+        .chain(&vertex.normal)
+        .chain(&vertex.tangent);
+    for (k, component) in key.iter_mut().zip(components) {
+        *k = component.to_bits();
+    }
+    key
+}
+
+fn positions(data: &MeshData<Vertex>, triangle: &[u32]) -> [Vector3<f32>; 3] {
+    let position = |i: usize| Vector3::from(data.vertices[triangle[i] as usize].position);
+    [position(0), position(1), position(2)]
+}
+
+fn uvs(data: &MeshData<Vertex>, triangle: &[u32]) -> [Vector2<f32>; 3] {
+    let uv = |i: usize| Vector2::from(data.vertices[triangle[i] as usize].uv);
+    [uv(0), uv(1), uv(2)]
+}
+
+fn normalize_or(v: Vector3<f32>, fallback: Vector3<f32>) -> Vector3<f32> {
+    if v.magnitude2() > f32::EPSILON {
+        v.normalize()
+    } else {
+        fallback
+    }
+}
+
+fn any_perpendicular(normal: Vector3<f32>) -> Vector3<f32> {
+    let axis = if normal.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+    normalize_or(axis - normal * normal.dot(axis), Vector3::unit_x())
+}
@@ -0,0 +1,209 @@
+pub mod gltf_loader;
+pub mod obj_loader;
+
+use crate::app::mesh::{processing, Mesh, MeshData, Vertex};
+use crate::app::{Texture, TextureError, Uploader};
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ModelError {
+    Obj(tobj::LoadError),
+    Gltf(gltf::Error),
+    UnsupportedFormat(PathBuf),
+    // a mesh refers to a vertex it doesn't have
+    IndexOutOfRange { index: u32, vertex_count: usize },
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModelError::Obj(e) => write!(f, "failed to load OBJ model: {}", e),
+            ModelError::Gltf(e) => write!(f, "failed to load glTF model: {}", e),
+            ModelError::UnsupportedFormat(path) => {
+                write!(f, "unsupported model format: {}", path.display())
+            }
+            ModelError::IndexOutOfRange {
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "vertex index {} is out of range for a mesh with {} vertices",
+                index, vertex_count
+            ),
+        }
+    }
+}
+
+impl Error for ModelError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ModelError::Obj(e) => Some(e),
+            ModelError::Gltf(e) => Some(e),
+            ModelError::UnsupportedFormat(_) | ModelError::IndexOutOfRange { .. } => None,
+        }
+    }
+}
+
+impl From<tobj::LoadError> for ModelError {
+    fn from(e: tobj::LoadError) -> ModelError {
+        ModelError::Obj(e)
+    }
+}
+
+impl From<gltf::Error> for ModelError {
+    fn from(e: gltf::Error) -> ModelError {
+        ModelError::Gltf(e)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TextureSource {
+    Path(PathBuf),
+    // already decoded, e.g. embedded in a glTF file
+    Rgba8 {
+        pixels: Vec<u8>,
+        dimensions: [u32; 2],
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct MaterialData {
+    pub name: String,
+    // linear RGBA; also baked into the vertex colours of the meshes using the material
+    pub base_color: [f32; 4],
+    pub base_color_texture: Option<TextureSource>,
+}
+
+impl Default for MaterialData {
+    fn default() -> MaterialData {
+        MaterialData {
+            name: String::new(),
+            base_color: [1.0; 4],
+            base_color_texture: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ModelMeshData {
+    pub data: MeshData<Vertex>,
+    // index into `ModelData::materials`
+    pub material: Option<usize>,
+}
+
+// CPU-side model; every mesh has normals and tangents and no duplicate vertices
+#[derive(Clone, Debug, Default)]
+pub struct ModelData {
+    pub meshes: Vec<ModelMeshData>,
+    pub materials: Vec<MaterialData>,
+}
+
+impl ModelData {
+    // picks the loader from the extension: `.obj` (with its MTL files), `.gltf` or `.glb`
+    pub fn load(path: &Path) -> Result<ModelData, ModelError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("obj") => obj_loader::load(path),
+            Some("gltf") | Some("glb") => gltf_loader::load(path),
+            _ => Err(ModelError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+}
+
+// checks the indices, then fills in whatever the source file didn't provide; called by the
+// loaders
+pub fn finish_mesh(data: &mut MeshData<Vertex>) -> Result<(), ModelError> {
+    let vertex_count = data.vertices.len();
+    if let Some(&index) = data
+        .indices
+        .iter()
+        .find(|&&index| index as usize >= vertex_count)
+    {
+        return Err(ModelError::IndexOutOfRange {
+            index,
+            vertex_count,
+        });
+    }
+    if !processing::has_normals(data) {
+        processing::generate_normals(data);
+    }
+    if !processing::has_tangents(data) {
+        processing::generate_tangents(data);
+    }
+    processing::deduplicate(data);
+    Ok(())
+}
+
+pub struct Material {
+    name: String,
+    base_color: [f32; 4],
+    base_color_texture: Option<Texture>,
+}
+
+impl Material {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn base_color(&self) -> [f32; 4] {
+        self.base_color
+    }
+
+    pub fn base_color_texture(&self) -> Option<&Texture> {
+        self.base_color_texture.as_ref()
+    }
+}
+
+// a model in device-local memory; like meshes, it must not be drawn before the uploader's
+// `flush` future has been joined
+pub struct Model {
+    meshes: Vec<(Mesh<Vertex>, Option<usize>)>,
+    materials: Vec<Material>,
+}
+
+impl Model {
+    pub fn upload(uploader: &mut Uploader, data: &ModelData) -> Result<Model, TextureError> {
+        let materials = data
+            .materials
+            .iter()
+            .map(|material| {
+                let base_color_texture = match material.base_color_texture {
+                    Some(TextureSource::Path(ref path)) => Some(Texture::load(uploader, path)?),
+                    Some(TextureSource::Rgba8 {
+                        ref pixels,
+                        dimensions,
+                    }) => Some(Texture::from_rgba8(uploader, pixels, dimensions)),
+                    None => None,
+                };
+                Ok(Material {
+                    name: material.name.clone(),
+                    base_color: material.base_color,
+                    base_color_texture,
+                })
+            })
+            .collect::<Result<_, TextureError>>()?;
+        let meshes = data
+            .meshes
+            .iter()
+            .filter(|mesh| !mesh.data.indices.is_empty())
+            .map(|mesh| (Mesh::upload(uploader, &mesh.data), mesh.material))
+            .collect();
+        Ok(Model { meshes, materials })
+    }
+
+    pub fn meshes(&self) -> impl Iterator<Item = (&Mesh<Vertex>, Option<&Material>)> {
+        self.meshes.iter().map(move |(mesh, material)| {
+            (mesh, material.and_then(|index| self.materials.get(index)))
+        })
+    }
+
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+}
@@ -0,0 +1,195 @@
+use crate::app::mesh::{MeshData, Vertex};
+use crate::app::model::{
+    finish_mesh, MaterialData, ModelData, ModelError, ModelMeshData, TextureSource,
+};
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, SquareMatrix, Vector3, Vector4};
+
+use gltf::image::Format;
+use gltf::mesh::Mode;
+
+use std::path::Path;
+
+// handles `.gltf` with embedded or external buffers and images as well as `.glb`; meshes are
+// flattened into model space using the default scene's node transforms
+pub fn load(path: &Path) -> Result<ModelData, ModelError> {
+    let (document, buffers, images) = gltf::import(path)?;
+
+    let materials: Vec<MaterialData> = document
+        .materials()
+        .map(|material| {
+            let pbr = material.pbr_metallic_roughness();
+            MaterialData {
+                name: material.name().unwrap_or_default().to_string(),
+                base_color: pbr.base_color_factor(),
+                base_color_texture: pbr
+                    .base_color_texture()
+                    .and_then(|info| to_rgba8(&images[info.texture().source().index()])),
+            }
+        })
+        .collect();
+
+    let mut loader = Loader {
+        buffers: &buffers,
+        materials: &materials,
+        meshes: Vec::new(),
+    };
+    match document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        Some(scene) => {
+            for node in scene.nodes() {
+                loader.add_node(&node, Matrix4::identity())?;
+            }
+        }
+        // a file with no scenes is a library of meshes
+        None => {
+            for mesh in document.meshes() {
+                loader.add_mesh(&mesh, Matrix4::identity())?;
+            }
+        }
+    }
+
+    Ok(ModelData {
+        meshes: loader.meshes,
+        materials,
+    })
+}
+
+struct Loader<'a> {
+    buffers: &'a [gltf::buffer::Data],
+    materials: &'a [MaterialData],
+    meshes: Vec<ModelMeshData>,
+}
+
+impl<'a> Loader<'a> {
+    fn add_node(
+        &mut self,
+        node: &gltf::Node,
+        parent_transform: Matrix4<f32>,
+    ) -> Result<(), ModelError> {
+        let transform = parent_transform * Matrix4::from(node.transform().matrix());
+        if let Some(mesh) = node.mesh() {
+            self.add_mesh(&mesh, transform)?;
+        }
+        for child in node.children() {
+            self.add_node(&child, transform)?;
+        }
+        Ok(())
+    }
+
+    fn add_mesh(&mut self, mesh: &gltf::Mesh, transform: Matrix4<f32>) -> Result<(), ModelError> {
+        let linear = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        );
+        let normal_matrix = linear
+            .invert()
+            .map(|inverse| inverse.transpose())
+            .unwrap_or(linear);
+
+        for primitive in mesh.primitives() {
+            if primitive.mode() != Mode::Triangles {
+                println!(
+                    "WARNING: Skipping {:?} primitive in mesh {}; only triangles are supported.",
+                    primitive.mode(),
+                    mesh.index()
+                );
+                continue;
+            }
+            let buffers = self.buffers;
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let positions = match reader.read_positions() {
+                Some(positions) => positions,
+                None => continue,
+            };
+
+            let material = primitive.material().index();
+            let base_color = material.map_or([1.0; 4], |index| self.materials[index].base_color);
+            let mut vertices: Vec<Vertex> = positions
+                .map(|position| {
+                    let position =
+                        transform * Vector4::new(position[0], position[1], position[2], 1.0);
+                    Vertex {
+                        position: position.truncate().into(),
+                        color: [base_color[0], base_color[1], base_color[2]],
+                        ..Vertex::default()
+                    }
+                })
+                .collect();
+            if let Some(normals) = reader.read_normals() {
+                for (vertex, normal) in vertices.iter_mut().zip(normals) {
+                    vertex.normal = normalize(normal_matrix * Vector3::from(normal)).into();
+                }
+            }
+            if let Some(tangents) = reader.read_tangents() {
+                for (vertex, tangent) in vertices.iter_mut().zip(tangents) {
+                    let xyz = normalize(linear * Vector3::new(tangent[0], tangent[1], tangent[2]));
+                    vertex.tangent = [xyz.x, xyz.y, xyz.z, tangent[3]];
+                }
+            }
+            if let Some(uvs) = reader.read_tex_coords(0) {
+                for (vertex, uv) in vertices.iter_mut().zip(uvs.into_f32()) {
+                    vertex.uv = uv;
+                }
+            }
+            if let Some(colors) = reader.read_colors(0) {
+                for (vertex, color) in vertices.iter_mut().zip(colors.into_rgb_f32()) {
+                    for (c, factor) in vertex.color.iter_mut().zip(&color) {
+                        *c *= factor;
+                    }
+                }
+            }
+            let indices = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..vertices.len() as u32).collect(),
+            };
+
+            let mut data = MeshData { vertices, indices };
+            finish_mesh(&mut data)?;
+            self.meshes.push(ModelMeshData { data, material });
+        }
+        Ok(())
+    }
+}
+
+// scaling transforms stretch normals and tangents; zero vectors are left as they are
+fn normalize(v: Vector3<f32>) -> Vector3<f32> {
+    if v.magnitude2() > f32::EPSILON {
+        v.normalize()
+    } else {
+        v
+    }
+}
+
+fn to_rgba8(image: &gltf::image::Data) -> Option<TextureSource> {
+    let pixels = match image.format {
+        Format::R8G8B8A8 => image.pixels.clone(),
+        Format::R8G8B8 => expand(&image.pixels, 3, |p| [p[0], p[1], p[2], 255]),
+        Format::B8G8R8A8 => expand(&image.pixels, 4, |p| [p[2], p[1], p[0], p[3]]),
+        Format::B8G8R8 => expand(&image.pixels, 3, |p| [p[2], p[1], p[0], 255]),
+        Format::R8G8 => expand(&image.pixels, 2, |p| [p[0], p[1], 0, 255]),
+        Format::R8 => expand(&image.pixels, 1, |p| [p[0], p[0], p[0], 255]),
+        format => {
+            println!("WARNING: Unsupported glTF image format {:?}.", format);
+            return None;
+        }
+    };
+    Some(TextureSource::Rgba8 {
+        pixels,
+        dimensions: [image.width, image.height],
+    })
+}
+
+fn expand<F>(pixels: &[u8], channels: usize, to_rgba: F) -> Vec<u8>
+where
+    F: Fn(&[u8]) -> [u8; 4],
+{
+    let mut rgba = Vec::with_capacity(pixels.len() / channels * 4);
+    for p in pixels.chunks_exact(channels) {
+        rgba.extend_from_slice(&to_rgba(p));
+    }
+    rgba
+}
@@ -0,0 +1,87 @@
+use crate::app::mesh::{MeshData, Vertex};
+use crate::app::model::{
+    finish_mesh, MaterialData, ModelData, ModelError, ModelMeshData, TextureSource,
+};
+
+use std::path::Path;
+
+// MTL files and textures are looked up relative to the OBJ file
+pub fn load(path: &Path) -> Result<ModelData, ModelError> {
+    let (models, materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)?;
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let materials: Vec<MaterialData> = match materials {
+        Ok(materials) => materials
+            .into_iter()
+            .map(|material| MaterialData {
+                base_color: [
+                    material.diffuse[0],
+                    material.diffuse[1],
+                    material.diffuse[2],
+                    material.dissolve,
+                ],
+                base_color_texture: if material.diffuse_texture.is_empty() {
+                    None
+                } else {
+                    Some(TextureSource::Path(
+                        directory.join(&material.diffuse_texture),
+                    ))
+                },
+                name: material.name,
+            })
+            .collect(),
+        Err(e) => {
+            println!(
+                "WARNING: Failed to load materials for {}: {}",
+                path.display(),
+                e
+            );
+            Vec::new()
+        }
+    };
+
+    let meshes = models
+        .into_iter()
+        .map(|model| {
+            let mesh = model.mesh;
+            let material = mesh.material_id.filter(|&id| id < materials.len());
+            let color = material.map_or([1.0; 3], |id| {
+                let [r, g, b, _] = materials[id].base_color;
+                [r, g, b]
+            });
+            let vertices = (0..mesh.positions.len() / 3)
+                .map(|i| Vertex {
+                    position: [
+                        mesh.positions[3 * i],
+                        mesh.positions[3 * i + 1],
+                        mesh.positions[3 * i + 2],
+                    ],
+                    color,
+                    // OBJ puts v = 0 at the bottom of the texture, Vulkan at the top
+                    uv: if mesh.texcoords.is_empty() {
+                        [0.0; 2]
+                    } else {
+                        [mesh.texcoords[2 * i], 1.0 - mesh.texcoords[2 * i + 1]]
+                    },
+                    normal: if mesh.normals.is_empty() {
+                        [0.0; 3]
+                    } else {
+                        [
+                            mesh.normals[3 * i],
+                            mesh.normals[3 * i + 1],
+                            mesh.normals[3 * i + 2],
+                        ]
+                    },
+                    tangent: [0.0; 4],
+                })
+                .collect();
+            let mut data = MeshData {
+                vertices,
+                indices: mesh.indices,
+            };
+            finish_mesh(&mut data)?;
+            Ok(ModelMeshData { data, material })
+        })
+        .collect::<Result<_, ModelError>>()?;
+
+    Ok(ModelData { meshes, materials })
+}
@@ -0,0 +1,51 @@
+use crate::app::config;
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+// a file the app reads but never writes, such as an asset or a shader source. It is looked up
+// next to the executable, then in each directory above it, so that running from `target/` finds
+// the copies in the source tree; `relative` under the executable's directory if none has it
+pub fn resource_path<P: AsRef<Path>>(relative: P) -> PathBuf {
+    let relative = relative.as_ref();
+    let executable_directory = executable_directory();
+    executable_directory
+        .ancestors()
+        .map(|directory| directory.join(relative))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| executable_directory.join(relative))
+}
+
+// a file the app writes and keeps between runs, such as a pipeline cache or a screenshot, under
+// `config::DATA_DIRECTORY_NAME` in the user's data directory. Falls back to the current
+// directory if the platform's data directory is unknown
+pub fn data_path<P: AsRef<Path>>(relative: P) -> PathBuf {
+    user_data_directory()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(config::DATA_DIRECTORY_NAME)
+        .join(relative)
+}
+
+fn executable_directory() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+// %APPDATA% on Windows, ~/Library/Application Support on macOS and $XDG_DATA_HOME or
+// ~/.local/share elsewhere
+fn user_data_directory() -> Option<PathBuf> {
+    let absolute = |variable: &str| {
+        env::var_os(variable)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+    if cfg!(target_os = "windows") {
+        absolute("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        absolute("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        absolute("XDG_DATA_HOME").or_else(|| absolute("HOME").map(|home| home.join(".local/share")))
+    }
+}
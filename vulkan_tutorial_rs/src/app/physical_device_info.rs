@@ -1,30 +1,31 @@
 use crate::app::config::required_device_extensions;
 use crate::app::init::IndentedPrinter;
+use crate::app::swapchain_info::supported_depth_format;
 use crate::app::QueueFamilies;
 use std::cmp::Ordering;
 use std::sync::Arc;
-use vulkano::device::DeviceExtensions;
+use vulkano::device::{DeviceExtensions, Features};
 use vulkano::instance::{Instance, PhysicalDevice, PhysicalDeviceType, QueueFamily};
-use vulkano::swapchain::{Capabilities, Surface};
-use winit::window::Window;
+use vulkano::swapchain::{Capabilities, CapabilitiesError, Surface};
+use winit::window::{Window, WindowId};
 
 pub struct PhysicalDeviceInfo {
     instance: Arc<Instance>,
     physical_device_index: usize,
-    surface: Arc<Surface<Window>>,
+    surfaces: Vec<Arc<Surface<Window>>>,
     supported_extensions: DeviceExtensions,
 }
 
 impl PhysicalDeviceInfo {
     pub fn new(
         physical_device: &PhysicalDevice,
-        surface: &Arc<Surface<Window>>,
+        surfaces: &[Arc<Surface<Window>>],
     ) -> PhysicalDeviceInfo {
         let supported_extensions = DeviceExtensions::supported_by_device(*physical_device);
         PhysicalDeviceInfo {
             instance: physical_device.instance().clone(),
             physical_device_index: physical_device.index(),
-            surface: surface.clone(),
+            surfaces: surfaces.to_vec(),
             supported_extensions,
         }
     }
@@ -33,12 +34,21 @@ impl PhysicalDeviceInfo {
         PhysicalDevice::from_index(&self.instance, self.physical_device_index).unwrap()
     }
 
-    pub fn surface(&self) -> &Arc<Surface<Window>> {
-        &self.surface
+    pub fn surfaces(&self) -> &[Arc<Surface<Window>>] {
+        &self.surfaces
+    }
+
+    pub fn add_surface(&mut self, surface: Arc<Surface<Window>>) {
+        self.surfaces.push(surface);
+    }
+
+    // surfaces keep their windows open, so a closed window's surface must be forgotten here too
+    pub fn remove_surface(&mut self, window_id: WindowId) {
+        self.surfaces.retain(|s| s.window().id() != window_id);
     }
 
     pub fn queue_families(&self) -> QueueFamilies {
-        QueueFamilies::new(self.physical_device(), &self.surface())
+        QueueFamilies::new(self.physical_device(), self.surfaces())
     }
 
     pub fn graphics_family(&self) -> Option<QueueFamily> {
@@ -51,25 +61,58 @@ impl PhysicalDeviceInfo {
             .present_family(&self.physical_device())
     }
 
+    pub fn transfer_family(&self) -> Option<QueueFamily> {
+        self.queue_families()
+            .transfer_family(&self.physical_device())
+    }
+
     pub fn supported_extensions(&self) -> &DeviceExtensions {
         &self.supported_extensions
     }
 
+    pub fn is_headless(&self) -> bool {
+        self.surfaces.is_empty()
+    }
+
+    // without surfaces there is nothing to present to, so the swapchain extension isn't needed
+    pub fn required_extensions(&self) -> DeviceExtensions {
+        if self.is_headless() {
+            DeviceExtensions::none()
+        } else {
+            required_device_extensions()
+        }
+    }
+
     pub fn supports_required_extensions(&self) -> bool {
-        required_device_extensions().intersection(&self.supported_extensions())
-            == required_device_extensions()
+        self.required_extensions()
+            .intersection(&self.supported_extensions())
+            == self.required_extensions()
+    }
+
+    pub fn supports_features(&self, features: &Features) -> bool {
+        self.physical_device()
+            .supported_features()
+            .superset_of(features)
     }
 
-    pub fn surface_capabilities(&self) -> Capabilities {
-        self.surface()
-            .capabilities(self.physical_device())
-            .expect("failed to get surface capabilities")
+    pub fn surface_capabilities(
+        &self,
+        surface: &Surface<Window>,
+    ) -> Result<Capabilities, CapabilitiesError> {
+        surface.capabilities(self.physical_device())
     }
 
-    pub fn is_surface_valid(&self) -> bool {
-        let capabilities = self.surface_capabilities();
-        !capabilities.supported_formats.is_empty()
-            && capabilities.present_modes.iter().next().is_some()
+    pub fn is_surface_valid(&self, surface: &Surface<Window>) -> bool {
+        // a surface that can't be queried can't be presented to either
+        self.surface_capabilities(surface)
+            .map_or(false, |capabilities| {
+                !capabilities.supported_formats.is_empty()
+                    && capabilities.present_modes.iter().next().is_some()
+            })
+    }
+
+    pub fn are_surfaces_valid(&self) -> bool {
+        self.surfaces().iter().all(|s| self.is_surface_valid(s))
     }
 
     pub fn is_discrete_gpu(&self) -> bool {
@@ -80,10 +123,16 @@ impl PhysicalDeviceInfo {
         self.physical_device().limits().max_image_dimension_2d()
     }
 
+    // every swapchain and offscreen target needs a depth attachment
+    pub fn supports_depth_attachment(&self) -> bool {
+        supported_depth_format(self.physical_device()).is_some()
+    }
+
     pub fn is_valid(&self) -> bool {
         self.queue_families()._is_complete()
             && self.supports_required_extensions()
-            && self.is_surface_valid()
+            && self.supports_depth_attachment()
+            && self.are_surfaces_valid()
     }
 
     fn cmp_valid(&self, other: &Self) -> Ordering {
@@ -117,6 +166,8 @@ impl PhysicalDeviceInfo {
         printer.print_key_value_debug("Type", &self.physical_device().ty());
         printer.print_key_value_debug("Graphics Queue Family", &self.graphics_family());
         printer.print_key_value_debug("Present Queue Family", &self.present_family());
+        printer.print_key_value_debug("Transfer Queue Family", &self.transfer_family());
+        printer.print_key_value_debug("Surface Count", &self.surfaces().len());
         printer.print_key_value_debug("Max Image Dimensions 2D", &self.max_image_dimension_2d());
     }
 }
@@ -0,0 +1,229 @@
+use vulkano::device::Device;
+use vulkano::instance::PhysicalDevice;
+use vulkano::pipeline::cache::PipelineCache;
+use vulkano::OomError;
+
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const IDENTIFIER: [u8; 8] = *b"VTPCACHE";
+const FORMAT_VERSION: u32 = 1;
+// identifier, format version, vendor id, device id, driver version, UUID, data length, checksum
+const HEADER_LENGTH: usize = 8 + 4 * 4 + 16 + 8 + 8;
+// the header Vulkan puts in front of its own cache data
+const VK_HEADER_LENGTH: usize = 32;
+const VK_PIPELINE_CACHE_HEADER_VERSION_ONE: u32 = 1;
+
+// pipeline cache data is only valid for the exact device and driver that produced it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PipelineCacheKey {
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub driver_version: u32,
+    pub uuid: [u8; 16],
+}
+
+impl PipelineCacheKey {
+    pub fn new(physical_device: &PhysicalDevice) -> PipelineCacheKey {
+        PipelineCacheKey {
+            vendor_id: physical_device.pci_vendor_id(),
+            device_id: physical_device.pci_device_id(),
+            driver_version: physical_device.driver_version(),
+            uuid: *physical_device.uuid(),
+        }
+    }
+
+    // one file per device and driver, so switching GPUs doesn't throw away the other's cache
+    pub fn file_name(&self) -> String {
+        let uuid: String = self.uuid.iter().map(|b| format!("{:02x}", b)).collect();
+        format!(
+            "pipeline_cache_{:04x}_{:04x}_{:08x}_{}.bin",
+            self.vendor_id, self.device_id, self.driver_version, uuid
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum PipelineCacheError {
+    Io(io::Error),
+    Oom(OomError),
+    InvalidIdentifier,
+    UnsupportedVersion(u32),
+    Truncated,
+    KeyMismatch,
+    ChecksumMismatch,
+}
+
+impl fmt::Display for PipelineCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PipelineCacheError::Io(e) => write!(f, "{}", e),
+            PipelineCacheError::Oom(e) => write!(f, "failed to read pipeline cache data: {}", e),
+            PipelineCacheError::InvalidIdentifier => write!(f, "not a pipeline cache file"),
+            PipelineCacheError::UnsupportedVersion(version) => {
+                write!(f, "unsupported pipeline cache file version {}", version)
+            }
+            PipelineCacheError::Truncated => write!(f, "pipeline cache file is truncated"),
+            PipelineCacheError::KeyMismatch => {
+                write!(f, "pipeline cache was created by another device or driver")
+            }
+            PipelineCacheError::ChecksumMismatch => write!(f, "pipeline cache file is corrupt"),
+        }
+    }
+}
+
+impl Error for PipelineCacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PipelineCacheError::Io(e) => Some(e),
+            PipelineCacheError::Oom(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PipelineCacheError {
+    fn from(e: io::Error) -> PipelineCacheError {
+        PipelineCacheError::Io(e)
+    }
+}
+
+impl From<OomError> for PipelineCacheError {
+    fn from(e: OomError) -> PipelineCacheError {
+        PipelineCacheError::Oom(e)
+    }
+}
+
+// prefixes the driver's cache data with the key and a checksum
+pub fn encode(key: &PipelineCacheKey, data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH + data.len());
+    bytes.extend(&IDENTIFIER);
+    bytes.extend(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend(&key.vendor_id.to_le_bytes());
+    bytes.extend(&key.device_id.to_le_bytes());
+    bytes.extend(&key.driver_version.to_le_bytes());
+    bytes.extend(&key.uuid);
+    bytes.extend(&(data.len() as u64).to_le_bytes());
+    bytes.extend(&checksum(data).to_le_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+// returns the driver's cache data if the file was written by `encode` for this key and is
+// intact; drivers don't all validate the data they are given, so nothing else may reach them
+pub fn decode<'a>(key: &PipelineCacheKey, bytes: &'a [u8]) -> Result<&'a [u8], PipelineCacheError> {
+    if bytes.len() < IDENTIFIER.len() || bytes[..IDENTIFIER.len()] != IDENTIFIER {
+        return Err(PipelineCacheError::InvalidIdentifier);
+    }
+    if bytes.len() < HEADER_LENGTH {
+        return Err(PipelineCacheError::Truncated);
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+    let version = u32_at(8);
+    if version != FORMAT_VERSION {
+        return Err(PipelineCacheError::UnsupportedVersion(version));
+    }
+    let file_key = PipelineCacheKey {
+        vendor_id: u32_at(12),
+        device_id: u32_at(16),
+        driver_version: u32_at(20),
+        uuid: bytes[24..40].try_into().unwrap(),
+    };
+    if file_key != *key {
+        return Err(PipelineCacheError::KeyMismatch);
+    }
+    let data = &bytes[HEADER_LENGTH..];
+    if data.len() as u64 != u64_at(40) {
+        return Err(PipelineCacheError::Truncated);
+    }
+    if checksum(data) != u64_at(48) {
+        return Err(PipelineCacheError::ChecksumMismatch);
+    }
+    check_vk_header(key, data)?;
+    Ok(data)
+}
+
+// the driver's own header repeats the vendor, device and UUID
+fn check_vk_header(key: &PipelineCacheKey, data: &[u8]) -> Result<(), PipelineCacheError> {
+    if data.len() < VK_HEADER_LENGTH {
+        return Err(PipelineCacheError::Truncated);
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let header_length = u32_at(0) as usize;
+    if header_length < VK_HEADER_LENGTH || header_length > data.len() {
+        return Err(PipelineCacheError::Truncated);
+    }
+    let version = u32_at(4);
+    if version != VK_PIPELINE_CACHE_HEADER_VERSION_ONE {
+        return Err(PipelineCacheError::UnsupportedVersion(version));
+    }
+    if u32_at(8) != key.vendor_id || u32_at(12) != key.device_id || data[16..32] != key.uuid {
+        return Err(PipelineCacheError::KeyMismatch);
+    }
+    Ok(())
+}
+
+// 64-bit FNV-1a; only guards against truncated or damaged files
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+// starts from the cache file in `directory` if there is a usable one, otherwise from an empty
+// cache; unusable files are left alone and replaced on the next save
+pub fn load(
+    device: &Arc<Device>,
+    key: &PipelineCacheKey,
+    directory: Option<&Path>,
+) -> Arc<PipelineCache> {
+    if let Some(path) = directory.map(|d| d.join(key.file_name())) {
+        match read(&path, key) {
+            Ok(data) => {
+                // `decode` checked that the data came from this device and driver
+                match unsafe { PipelineCache::with_data(device.clone(), &data) } {
+                    Ok(cache) => return cache,
+                    Err(e) => println!(
+                        "WARNING: Failed to create pipeline cache from {}: {}",
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+            Err(PipelineCacheError::Io(ref e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => println!(
+                "WARNING: Discarding pipeline cache {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    PipelineCache::empty(device.clone()).expect("failed to create pipeline cache")
+}
+
+fn read(path: &Path, key: &PipelineCacheKey) -> Result<Vec<u8>, PipelineCacheError> {
+    let bytes = fs::read(path)?;
+    decode(key, &bytes).map(<[u8]>::to_vec)
+}
+
+// written to a temporary file first so a crash mid-write can't leave a half-written cache
+pub fn save(
+    cache: &PipelineCache,
+    key: &PipelineCacheKey,
+    directory: &Path,
+) -> Result<PathBuf, PipelineCacheError> {
+    let data = cache.get_data()?;
+    fs::create_dir_all(directory)?;
+    let path = directory.join(key.file_name());
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, encode(key, &data))?;
+    fs::rename(&temporary_path, &path)?;
+    Ok(path)
+}
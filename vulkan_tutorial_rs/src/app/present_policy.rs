@@ -0,0 +1,44 @@
+use vulkano::swapchain::{PresentMode, SupportedPresentModes};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresentPolicy {
+    // low latency without tearing; falls back to Fifo
+    Mailbox,
+    // vsync; always supported
+    Fifo,
+    // vsync, but late frames are shown immediately; falls back to Fifo
+    FifoRelaxed,
+    // no vsync; falls back to Mailbox, then Fifo
+    Immediate,
+}
+
+impl Default for PresentPolicy {
+    fn default() -> PresentPolicy {
+        PresentPolicy::Mailbox
+    }
+}
+
+impl PresentPolicy {
+    pub fn preferred_modes(self) -> &'static [PresentMode] {
+        match self {
+            PresentPolicy::Mailbox => &[PresentMode::Mailbox],
+            PresentPolicy::Fifo => &[],
+            PresentPolicy::FifoRelaxed => &[PresentMode::Relaxed],
+            PresentPolicy::Immediate => &[PresentMode::Immediate, PresentMode::Mailbox],
+        }
+    }
+
+    pub fn choose(self, modes: &SupportedPresentModes) -> PresentMode {
+        self.preferred_modes()
+            .iter()
+            .cloned()
+            .find(|&m| modes.supports(m))
+            .unwrap_or_else(|| {
+                assert!(
+                    modes.fifo,
+                    "Fifo support is required by the Vulkan standard"
+                );
+                PresentMode::Fifo
+            })
+    }
+}
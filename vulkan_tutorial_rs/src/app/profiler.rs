@@ -0,0 +1,376 @@
+pub mod overlay;
+pub mod timestamps;
+
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::profiler::timestamps::TimestampWrite;
+use crate::app::{Frame, LogicalDevice};
+
+use vulkano::device::Device;
+use vulkano::query::UnsafeQueryPool;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// the queries at the start of each frame's range time the whole frame
+const FRAME_QUERIES: u32 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionProfile {
+    pub name: String,
+    // how many regions this one is nested in
+    pub depth: usize,
+    // time spent between `begin_region` and `end_region`, i.e. recording the region
+    pub cpu: Duration,
+    // `None` without timestamp support, or for regions beyond `config::PROFILER_MAX_REGIONS`
+    pub gpu: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameProfile {
+    pub cpu: Duration,
+    pub gpu: Option<Duration>,
+    // in the order they were begun
+    pub regions: Vec<RegionProfile>,
+}
+
+// the last `capacity` frame profiles, oldest first
+pub struct ProfileHistory {
+    frames: VecDeque<FrameProfile>,
+    capacity: usize,
+}
+
+impl ProfileHistory {
+    pub fn new(capacity: usize) -> ProfileHistory {
+        assert!(capacity > 0, "profile history must hold at least one frame");
+        ProfileHistory {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, frame: FrameProfile) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = &FrameProfile> {
+        self.frames.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&FrameProfile> {
+        self.frames.back()
+    }
+
+    // regions are matched by name and depth, in the order they first appear; GPU times are
+    // averaged over the frames that have them. `None` until a frame has been recorded
+    pub fn average(&self) -> Option<FrameProfile> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let mut cpu = Vec::new();
+        let mut gpu = Vec::new();
+        let mut regions: Vec<(String, usize, Vec<Duration>, Vec<Duration>)> = Vec::new();
+        for frame in &self.frames {
+            cpu.push(frame.cpu);
+            gpu.extend(frame.gpu);
+            for region in &frame.regions {
+                let index = match regions
+                    .iter()
+                    .position(|(name, depth, _, _)| *name == region.name && *depth == region.depth)
+                {
+                    Some(index) => index,
+                    None => {
+                        regions.push((region.name.clone(), region.depth, Vec::new(), Vec::new()));
+                        regions.len() - 1
+                    }
+                };
+                regions[index].2.push(region.cpu);
+                regions[index].3.extend(region.gpu);
+            }
+        }
+        Some(FrameProfile {
+            cpu: mean(&cpu).unwrap(),
+            gpu: mean(&gpu),
+            regions: regions
+                .into_iter()
+                .map(|(name, depth, cpu, gpu)| RegionProfile {
+                    name,
+                    depth,
+                    cpu: mean(&cpu).unwrap(),
+                    gpu: mean(&gpu),
+                })
+                .collect(),
+        })
+    }
+}
+
+fn mean(durations: &[Duration]) -> Option<Duration> {
+    if durations.is_empty() {
+        None
+    } else {
+        Some(durations.iter().sum::<Duration>() / durations.len() as u32)
+    }
+}
+
+// timestamps count ticks of `period` nanoseconds and only the low `valid_bits` bits are
+// meaningful, so the counter may wrap between two of them
+pub fn ticks_to_duration(start: u64, end: u64, valid_bits: u32, period: f32) -> Duration {
+    let mask = if valid_bits >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << valid_bits) - 1
+    };
+    let ticks = end.wrapping_sub(start) & mask;
+    Duration::from_nanos((ticks as f64 * f64::from(period)) as u64)
+}
+
+struct PendingRegion {
+    name: String,
+    depth: usize,
+    begun: Instant,
+    cpu: Option<Duration>,
+    // the region's begin query; its end query follows it
+    query: Option<u32>,
+}
+
+// the queries of one frame, which are only read once the frame has had
+// `config::PROFILER_LATENCY` frames to finish
+struct FrameSlot {
+    first_query: u32,
+    regions: Vec<PendingRegion>,
+    cpu: Duration,
+    // the frame's `Frame::submitted`, once it has ended; frames that weren't submitted never
+    // reset or wrote their queries, which may still hold an older frame's times
+    submitted: Option<Arc<AtomicBool>>,
+}
+
+// times a window's frames on the CPU and, where the graphics queue supports timestamps, on the
+// GPU. Each frame is bracketed by `begin_frame` and `end_frame`, with `begin_region` and
+// `end_region` around the command buffers to time; results arrive a few frames later
+pub struct Profiler {
+    device: Arc<Device>,
+    query_pool: Option<Arc<UnsafeQueryPool>>,
+    timestamp_period: f32,
+    timestamp_valid_bits: u32,
+    slots: Vec<FrameSlot>,
+    current: usize,
+    frame_begun: Option<Instant>,
+    open_regions: Vec<usize>,
+    history: ProfileHistory,
+}
+
+impl Profiler {
+    pub fn new(logical_device: &LogicalDevice) -> Profiler {
+        let device = logical_device.device().clone();
+        let physical_device = device.physical_device();
+        let timestamp_valid_bits = logical_device
+            .graphics_queue()
+            .family()
+            .timestamp_valid_bits();
+        let queries_per_frame = FRAME_QUERIES + 2 * config::PROFILER_MAX_REGIONS;
+        let query_pool = timestamp_valid_bits.map(|_| {
+            timestamps::create_query_pool(
+                &device,
+                queries_per_frame * config::PROFILER_LATENCY as u32,
+            )
+        });
+        if query_pool.is_none() {
+            println!(
+                "WARNING: The graphics queue doesn't support timestamps; GPU timings are disabled"
+            );
+        }
+        Profiler {
+            timestamp_period: physical_device.limits().timestamp_period(),
+            timestamp_valid_bits: timestamp_valid_bits.unwrap_or(0),
+            slots: (0..config::PROFILER_LATENCY as u32)
+                .map(|i| FrameSlot {
+                    first_query: i * queries_per_frame,
+                    regions: Vec::new(),
+                    cpu: Duration::from_secs(0),
+                    submitted: None,
+                })
+                .collect(),
+            current: 0,
+            frame_begun: None,
+            open_regions: Vec::new(),
+            history: ProfileHistory::new(config::PROFILER_HISTORY_LENGTH),
+            device,
+            query_pool,
+        }
+    }
+
+    pub fn is_gpu_timing_supported(&self) -> bool {
+        self.query_pool.is_some()
+    }
+
+    pub fn history(&self) -> &ProfileHistory {
+        &self.history
+    }
+
+    // the most recent frame whose results are in
+    pub fn last_frame(&self) -> Option<&FrameProfile> {
+        self.history.last()
+    }
+
+    pub fn begin_frame(&mut self, frame: &mut Frame) {
+        if self.frame_begun.is_some() {
+            self.end_frame(frame);
+        }
+        self.current = (self.current + 1) % self.slots.len();
+        let submitted = self.slots[self.current]
+            .submitted
+            .take()
+            .map_or(false, |submitted| submitted.load(Ordering::Relaxed));
+        if submitted {
+            let profile = self.resolve(self.current);
+            self.history.record(profile);
+        }
+        let slot = &mut self.slots[self.current];
+        slot.regions.clear();
+        let first_query = slot.first_query;
+        self.frame_begun = Some(Instant::now());
+        self.write_timestamp(
+            frame,
+            TimestampWrite {
+                index: first_query,
+                stage: timestamps::top_of_pipe(),
+                reset: Some(FRAME_QUERIES + 2 * config::PROFILER_MAX_REGIONS),
+            },
+        );
+    }
+
+    // regions may nest; they time the command buffers submitted to `frame` until `end_region`,
+    // which also show up under the region's name in capture tools
+    pub fn begin_region(&mut self, frame: &mut Frame, name: &str) {
+        debug_utils::begin_label(frame, name, [0.0; 4]);
+        let slot = &mut self.slots[self.current];
+        let index = slot.regions.len() as u32;
+        let query = if index < config::PROFILER_MAX_REGIONS {
+            Some(slot.first_query + FRAME_QUERIES + 2 * index)
+        } else {
+            None
+        };
+        slot.regions.push(PendingRegion {
+            name: name.to_string(),
+            depth: self.open_regions.len(),
+            begun: Instant::now(),
+            cpu: None,
+            query,
+        });
+        self.open_regions.push(slot.regions.len() - 1);
+        if let Some(query) = query {
+            self.write_timestamp(
+                frame,
+                TimestampWrite {
+                    index: query,
+                    stage: timestamps::top_of_pipe(),
+                    reset: None,
+                },
+            );
+        }
+    }
+
+    pub fn end_region(&mut self, frame: &mut Frame) {
+        let index = match self.open_regions.pop() {
+            Some(index) => index,
+            None => {
+                println!("WARNING: Profiler region ended without being begun");
+                return;
+            }
+        };
+        let region = &mut self.slots[self.current].regions[index];
+        region.cpu = Some(region.begun.elapsed());
+        if let Some(query) = region.query {
+            self.write_timestamp(
+                frame,
+                TimestampWrite {
+                    index: query + 1,
+                    stage: timestamps::bottom_of_pipe(),
+                    reset: None,
+                },
+            );
+        }
+        debug_utils::end_label(frame);
+    }
+
+    pub fn end_frame(&mut self, frame: &mut Frame) {
+        let begun = match self.frame_begun.take() {
+            Some(begun) => begun,
+            None => return,
+        };
+        while !self.open_regions.is_empty() {
+            self.end_region(frame);
+        }
+        let first_query = self.slots[self.current].first_query;
+        self.write_timestamp(
+            frame,
+            TimestampWrite {
+                index: first_query + 1,
+                stage: timestamps::bottom_of_pipe(),
+                reset: None,
+            },
+        );
+        let slot = &mut self.slots[self.current];
+        slot.cpu = begun.elapsed();
+        slot.submitted = Some(frame.submitted().clone());
+    }
+
+    fn write_timestamp(&self, frame: &mut Frame, write: TimestampWrite) {
+        if let Some(ref query_pool) = self.query_pool {
+            timestamps::record(frame, query_pool, write);
+        }
+    }
+
+    // GPU times are left out if the frame's queries aren't all available, e.g. because the GPU
+    // hasn't finished the frame yet
+    fn resolve(&self, slot_index: usize) -> FrameProfile {
+        let slot = &self.slots[slot_index];
+        let timed_regions = slot.regions.iter().filter(|r| r.query.is_some()).count() as u32;
+        let results = self.query_pool.as_ref().and_then(|query_pool| {
+            timestamps::read_results(
+                &self.device,
+                query_pool,
+                slot.first_query,
+                FRAME_QUERIES + 2 * timed_regions,
+            )
+        });
+        let gpu_time = |query: u32| {
+            results.as_ref().map(|results| {
+                let offset = (query - slot.first_query) as usize;
+                ticks_to_duration(
+                    results[offset],
+                    results[offset + 1],
+                    self.timestamp_valid_bits,
+                    self.timestamp_period,
+                )
+            })
+        };
+        FrameProfile {
+            cpu: slot.cpu,
+            gpu: gpu_time(slot.first_query),
+            regions: slot
+                .regions
+                .iter()
+                .map(|region| RegionProfile {
+                    name: region.name.clone(),
+                    depth: region.depth,
+                    cpu: region.cpu.unwrap_or_default(),
+                    gpu: region.query.and_then(gpu_time),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::profiler::FrameProfile;
+use crate::app::render_target::RenderTarget;
+use crate::app::{Frame, LogicalDevice};
+
+use vulkano::buffer::CpuBufferPool;
+use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder, DynamicState};
+use vulkano::device::Device;
+use vulkano::format::ClearValue;
+use vulkano::framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract, Subpass};
+use vulkano::pipeline::viewport::Viewport;
+use vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+mod vertex_shader {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        path: "shaders/overlay.vert"
+    }
+}
+
+mod fragment_shader {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        path: "shaders/overlay.frag"
+    }
+}
+
+// in pixels
+const MARGIN: f32 = 8.0;
+const ROW_HEIGHT: f32 = 8.0;
+const ROW_GAP: f32 = 3.0;
+const INDENT: f32 = 8.0;
+const BUDGET_WIDTH: f32 = 300.0;
+
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const OVER_BUDGET: [f32; 4] = [1.0, 0.1, 0.1, 1.0];
+// regions are coloured by their position in the frame
+const PALETTE: &[[f32; 3]] = &[
+    [0.3, 0.7, 1.0],
+    [0.4, 0.9, 0.4],
+    [1.0, 0.8, 0.2],
+    [0.9, 0.5, 1.0],
+    [0.3, 0.9, 0.9],
+    [1.0, 0.6, 0.4],
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct OverlayVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+vulkano::impl_vertex!(OverlayVertex, position, color);
+
+// draws a frame profile over what has already been rendered: one row for the whole frame, then
+// one per region, indented by nesting depth. The top half of a row is GPU time and the bottom
+// half CPU time, as a fraction of `config::PROFILER_OVERLAY_BUDGET`; bars past it turn red
+pub struct ProfilerOverlay {
+    render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
+    pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+    framebuffers: Vec<Arc<dyn FramebufferAbstract + Send + Sync>>,
+    vertices: CpuBufferPool<OverlayVertex>,
+}
+
+impl ProfilerOverlay {
+    pub fn new<T: RenderTarget>(logical_device: &LogicalDevice, target: &T) -> ProfilerOverlay {
+        let device = logical_device.device();
+        let render_pass = create_render_pass(device, target);
+        let vertex_shader =
+            vertex_shader::Shader::load(device.clone()).expect("failed to load vertex shader");
+        let fragment_shader =
+            fragment_shader::Shader::load(device.clone()).expect("failed to load fragment shader");
+        let pipeline = Arc::new(
+            GraphicsPipeline::start()
+                .vertex_input_single_buffer::<OverlayVertex>()
+                .vertex_shader(vertex_shader.main_entry_point(), ())
+                .triangle_list()
+                .viewports_dynamic_scissors_irrelevant(1)
+                .fragment_shader(fragment_shader.main_entry_point(), ())
+                .blend_alpha_blending()
+                .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+                .build_with_cache(logical_device.pipeline_cache().clone())
+                .build(device.clone())
+                .expect("failed to create overlay pipeline"),
+        );
+        debug_utils::set_pipeline_name(device, &*pipeline, "profiler overlay pipeline");
+        let mut overlay = ProfilerOverlay {
+            render_pass,
+            pipeline,
+            framebuffers: Vec::new(),
+            vertices: CpuBufferPool::vertex_buffer(device.clone()),
+        };
+        overlay.recreate_framebuffers(target);
+        overlay
+    }
+
+    // must be called whenever the window's swapchain is recreated
+    pub fn recreate_framebuffers<T: RenderTarget>(&mut self, target: &T) {
+        self.framebuffers = target
+            .images()
+            .into_iter()
+            .map(|image| {
+                Arc::new(
+                    Framebuffer::start(self.render_pass.clone())
+                        .add(image)
+                        .unwrap()
+                        .build()
+                        .expect("failed to create framebuffer"),
+                ) as Arc<dyn FramebufferAbstract + Send + Sync>
+            })
+            .collect();
+    }
+
+    // submit after the command buffers that render the frame
+    pub fn draw(&mut self, frame: &Frame, profile: &FrameProfile) -> AutoCommandBuffer {
+        let logical_device = frame.logical_device();
+        let dimensions = frame.dimensions();
+        let vertices = self
+            .vertices
+            .chunk(layout(profile, dimensions))
+            .expect("failed to allocate overlay vertices");
+
+        let dynamic_state = DynamicState {
+            viewports: Some(vec![Viewport {
+                origin: [0.0, 0.0],
+                dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+                depth_range: 0.0..1.0,
+            }]),
+            ..DynamicState::none()
+        };
+        let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(
+            logical_device.device().clone(),
+            logical_device.graphics_queue().family(),
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                self.framebuffers[frame.image_num()].clone(),
+                false,
+                vec![ClearValue::None],
+            )
+            .unwrap()
+            .draw(
+                self.pipeline.clone(),
+                &dynamic_state,
+                vec![Arc::new(vertices)],
+                (),
+                (),
+            )
+            .unwrap()
+            .end_render_pass()
+            .unwrap();
+        builder.build().unwrap()
+    }
+}
+
+// keeps what was rendered before, so it has to draw into the resolved image
+fn create_render_pass<T: RenderTarget>(
+    device: &Arc<Device>,
+    target: &T,
+) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    Arc::new(
+        vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                color: {
+                    load: Load,
+                    store: Store,
+                    format: target.format(),
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .expect("failed to create render pass"),
+    )
+}
+
+fn layout(profile: &FrameProfile, dimensions: [u32; 2]) -> Vec<OverlayVertex> {
+    let mut vertices = Vec::new();
+    let rows = std::iter::once((0, profile.gpu, profile.cpu))
+        .chain(
+            profile
+                .regions
+                .iter()
+                .map(|region| (region.depth + 1, region.gpu, region.cpu)),
+        )
+        .enumerate();
+    for (row, (depth, gpu, cpu)) in rows {
+        let x = MARGIN + depth as f32 * INDENT;
+        let y = MARGIN + row as f32 * (ROW_HEIGHT + ROW_GAP);
+        let color = PALETTE[row % PALETTE.len()];
+        rectangle(
+            &mut vertices,
+            dimensions,
+            [x, y],
+            [BUDGET_WIDTH, ROW_HEIGHT],
+            BACKGROUND,
+        );
+        let half = ROW_HEIGHT / 2.0;
+        if let Some(gpu) = gpu {
+            bar(&mut vertices, dimensions, [x, y], half, gpu, color, 1.0);
+        }
+        bar(
+            &mut vertices,
+            dimensions,
+            [x, y + half],
+            half,
+            cpu,
+            color,
+            0.6,
+        );
+    }
+    vertices
+}
+
+fn bar(
+    vertices: &mut Vec<OverlayVertex>,
+    dimensions: [u32; 2],
+    position: [f32; 2],
+    height: f32,
+    time: Duration,
+    color: [f32; 3],
+    brightness: f32,
+) {
+    let fraction = time.as_secs_f32() / config::PROFILER_OVERLAY_BUDGET.as_secs_f32();
+    let color = if fraction > 1.0 {
+        OVER_BUDGET
+    } else {
+        [
+            color[0] * brightness,
+            color[1] * brightness,
+            color[2] * brightness,
+            1.0,
+        ]
+    };
+    rectangle(
+        vertices,
+        dimensions,
+        position,
+        [BUDGET_WIDTH * fraction.min(1.0), height],
+        color,
+    );
+}
+
+// `position` and `size` in pixels from the top-left corner
+fn rectangle(
+    vertices: &mut Vec<OverlayVertex>,
+    dimensions: [u32; 2],
+    position: [f32; 2],
+    size: [f32; 2],
+    color: [f32; 4],
+) {
+    let to_ndc = |x: f32, y: f32| {
+        [
+            x / dimensions[0] as f32 * 2.0 - 1.0,
+            y / dimensions[1] as f32 * 2.0 - 1.0,
+        ]
+    };
+    let (left, top) = (position[0], position[1]);
+    let (right, bottom) = (left + size[0], top + size[1]);
+    for &(x, y) in &[
+        (left, top),
+        (right, top),
+        (left, bottom),
+        (left, bottom),
+        (right, top),
+        (right, bottom),
+    ] {
+        vertices.push(OverlayVertex {
+            position: to_ndc(x, y),
+            color,
+        });
+    }
+}
@@ -0,0 +1,85 @@
+use crate::app::Frame;
+
+use vulkano::device::Device;
+use vulkano::query::{QueryType, UnsafeQueryPool};
+use vulkano::sync::PipelineStages;
+use vulkano::VulkanObject;
+
+use std::any::Any;
+use std::sync::Arc;
+
+// a write of query `index`, optionally preceded by a reset of `reset` queries starting at it
+#[derive(Clone, Copy, Debug)]
+pub struct TimestampWrite {
+    pub index: u32,
+    pub stage: PipelineStages,
+    pub reset: Option<u32>,
+}
+
+// recorded into `frame` between the command buffers submitted before and after; the query pool
+// must have been created by `create_query_pool`
+pub fn record(frame: &mut Frame, query_pool: &Arc<UnsafeQueryPool>, write: TimestampWrite) {
+    let resources: Vec<Arc<dyn Any + Send + Sync>> = vec![query_pool.clone()];
+    let query_pool = query_pool.clone();
+    // timestamps touch no buffers or images, and the profiler never uses a query from two
+    // frames at once
+    unsafe {
+        frame.record_raw(resources, move |builder| {
+            if let Some(count) = write.reset {
+                builder.reset_query_pool(query_pool.queries_range(write.index, count).unwrap());
+            }
+            builder.write_timestamp(query_pool.query(write.index).unwrap(), write.stage);
+        });
+    }
+}
+
+pub fn create_query_pool(device: &Arc<Device>, count: u32) -> Arc<UnsafeQueryPool> {
+    Arc::new(
+        UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, count)
+            .expect("failed to create timestamp query pool"),
+    )
+}
+
+// `None` while any of the queries hasn't been written yet; never waits for the GPU
+pub fn read_results(
+    device: &Device,
+    query_pool: &UnsafeQueryPool,
+    first: u32,
+    count: u32,
+) -> Option<Vec<u64>> {
+    if count == 0 {
+        return Some(Vec::new());
+    }
+    let mut results = vec![0u64; count as usize];
+    let result = unsafe {
+        device.pointers().GetQueryPoolResults(
+            device.internal_object(),
+            query_pool.internal_object(),
+            first,
+            count,
+            results.len() * 8,
+            results.as_mut_ptr() as *mut _,
+            8,
+            vk_sys::QUERY_RESULT_64_BIT,
+        )
+    };
+    if result == vk_sys::SUCCESS {
+        Some(results)
+    } else {
+        None
+    }
+}
+
+pub fn top_of_pipe() -> PipelineStages {
+    PipelineStages {
+        top_of_pipe: true,
+        ..PipelineStages::none()
+    }
+}
+
+pub fn bottom_of_pipe() -> PipelineStages {
+    PipelineStages {
+        bottom_of_pipe: true,
+        ..PipelineStages::none()
+    }
+}
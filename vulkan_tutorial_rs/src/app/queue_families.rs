@@ -3,22 +3,33 @@ use vulkano::swapchain::Surface;
 
 use winit::window::Window;
 
+use std::sync::Arc;
+
 pub struct QueueFamilies {
     graphics_family_id: Option<u32>,
     present_family_id: Option<u32>,
+    transfer_family_id: Option<u32>,
+    presentation_required: bool,
 }
 
 impl QueueFamilies {
-    pub fn new(device: PhysicalDevice, surface: &Surface<Window>) -> QueueFamilies {
+    // the present family must be able to present to every surface, so that a single present
+    // queue serves all windows; with no surfaces (headless) no present family is looked for
+    pub fn new(device: PhysicalDevice, surfaces: &[Arc<Surface<Window>>]) -> QueueFamilies {
         let mut families = QueueFamilies {
             graphics_family_id: None,
             present_family_id: None,
+            transfer_family_id: choose_transfer_family(device),
+            presentation_required: !surfaces.is_empty(),
         };
         for family in device.queue_families() {
             if families.graphics_family_id.is_none() && family.supports_graphics() {
                 families.graphics_family_id = Some(family.id())
             }
-            if families.present_family_id.is_none() && surface.is_supported(family).unwrap() {
+            if families.presentation_required
+                && families.present_family_id.is_none()
+                && surfaces.iter().all(|s| s.is_supported(family).unwrap())
+            {
                 families.present_family_id = Some(family.id())
             }
             if families._is_complete() {
@@ -38,7 +49,29 @@ impl QueueFamilies {
             .and_then(|i| device.queue_family_by_id(i))
     }
 
+    // a dedicated transfer family, if the device has one
+    pub fn transfer_family<'a>(&self, device: &PhysicalDevice<'a>) -> Option<QueueFamily<'a>> {
+        self.transfer_family_id
+            .and_then(|i| device.queue_family_by_id(i))
+    }
+
     pub fn _is_complete(&self) -> bool {
-        return self.graphics_family_id.is_some() && self.present_family_id.is_some();
+        return self.graphics_family_id.is_some()
+            && (self.present_family_id.is_some() || !self.presentation_required);
     }
 }
+
+// prefer a transfer-only family (usually backed by a DMA engine), then any non-graphics family
+// that can transfer
+fn choose_transfer_family(device: PhysicalDevice) -> Option<u32> {
+    let transfer_only = device.queue_families().find(|f| {
+        f.explicitly_supports_transfers() && !f.supports_graphics() && !f.supports_compute()
+    });
+    transfer_only
+        .or_else(|| {
+            device
+                .queue_families()
+                .find(|f| f.explicitly_supports_transfers() && !f.supports_graphics())
+        })
+        .map(|f| f.id())
+}
@@ -0,0 +1,103 @@
+use vulkano::buffer::BufferAccess;
+use vulkano::command_buffer::pool::standard::{
+    StandardCommandPoolAlloc, StandardCommandPoolBuilder,
+};
+use vulkano::command_buffer::pool::{CommandPool, CommandPoolBuilderAlloc};
+use vulkano::command_buffer::sys::{Flags, Kind, UnsafeCommandBuffer, UnsafeCommandBufferBuilder};
+use vulkano::command_buffer::{CommandBuffer, CommandBufferExecError};
+use vulkano::device::{Device, DeviceOwned, Queue};
+use vulkano::image::{ImageAccess, ImageLayout};
+use vulkano::instance::QueueFamily;
+use vulkano::sync::{AccessCheckError, AccessFlagBits, GpuFuture, PipelineStages};
+
+use std::any::Any;
+use std::sync::Arc;
+
+// a command buffer for the commands vulkano's builder doesn't offer, such as timestamp queries
+// and debug labels. It is submitted between a frame's other command buffers, and queue
+// submission order places its commands between theirs
+pub struct RawCommandBuffer {
+    inner: UnsafeCommandBuffer<StandardCommandPoolAlloc>,
+    _pool_alloc: StandardCommandPoolAlloc,
+    // whatever the recorded commands use that must outlive them, e.g. a query pool
+    _resources: Vec<Arc<dyn Any + Send + Sync>>,
+}
+
+impl RawCommandBuffer {
+    /// Records a command buffer with `record`.
+    ///
+    /// # Safety
+    ///
+    /// Nothing checks what `record` records. The command buffer reports no buffer or image
+    /// accesses for vulkano to synchronise, so it must either touch none or the caller must
+    /// order them itself, and `resources` must hold everything the commands refer to.
+    pub unsafe fn new<F>(
+        device: &Arc<Device>,
+        queue_family: QueueFamily,
+        resources: Vec<Arc<dyn Any + Send + Sync>>,
+        record: F,
+    ) -> RawCommandBuffer
+    where
+        F: FnOnce(&mut UnsafeCommandBufferBuilder<StandardCommandPoolBuilder>),
+    {
+        let pool_builder: StandardCommandPoolBuilder =
+            Device::standard_command_pool(device, queue_family)
+                .alloc(false, 1)
+                .expect("failed to allocate command buffer")
+                .next()
+                .unwrap();
+        let mut builder =
+            UnsafeCommandBufferBuilder::new(&pool_builder, Kind::primary(), Flags::OneTimeSubmit)
+                .expect("failed to begin command buffer");
+        record(&mut builder);
+        RawCommandBuffer {
+            inner: builder.build().expect("failed to build command buffer"),
+            _pool_alloc: pool_builder.into_alloc(),
+            _resources: resources,
+        }
+    }
+}
+
+unsafe impl DeviceOwned for RawCommandBuffer {
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+// see `new`: there is nothing to lock or check
+unsafe impl CommandBuffer for RawCommandBuffer {
+    type PoolAlloc = StandardCommandPoolAlloc;
+
+    fn inner(&self) -> &UnsafeCommandBuffer<StandardCommandPoolAlloc> {
+        &self.inner
+    }
+
+    fn lock_submit(
+        &self,
+        _future: &dyn GpuFuture,
+        _queue: &Queue,
+    ) -> Result<(), CommandBufferExecError> {
+        Ok(())
+    }
+
+    unsafe fn unlock(&self) {}
+
+    fn check_buffer_access(
+        &self,
+        _buffer: &dyn BufferAccess,
+        _exclusive: bool,
+        _queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    fn check_image_access(
+        &self,
+        _image: &dyn ImageAccess,
+        _layout: ImageLayout,
+        _exclusive: bool,
+        _queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+}
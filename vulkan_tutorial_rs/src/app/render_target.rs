@@ -0,0 +1,158 @@
+use crate::app::debug_utils;
+use crate::app::swapchain_info::{choose_sample_count, create_attachments, supported_depth_format};
+use crate::app::{LogicalDevice, SwapchainInfo};
+
+use vulkano::format::Format;
+use vulkano::image::{AttachmentImage, ImageUsage, ImageViewAccess};
+use vulkano::swapchain::CompositeAlpha;
+
+use std::sync::Arc;
+
+// what a renderer needs to know about the images it draws into; each of `images` gets its own
+// framebuffer, sharing the colour and depth attachments
+pub trait RenderTarget {
+    fn format(&self) -> Format;
+    fn depth_format(&self) -> Format;
+    fn samples(&self) -> u32;
+    fn dimensions(&self) -> [u32; 2];
+    // whether the images' alpha is ignored; transparent targets are cleared to alpha 0 so that
+    // whatever is behind them shows where nothing is drawn
+    fn is_opaque(&self) -> bool;
+    fn images(&self) -> Vec<Arc<dyn ImageViewAccess + Send + Sync>>;
+    // only with MSAA, resolving into the image being drawn
+    fn color_image(&self) -> Option<&Arc<AttachmentImage>>;
+    fn depth_image(&self) -> &Arc<AttachmentImage>;
+}
+
+impl RenderTarget for SwapchainInfo {
+    fn format(&self) -> Format {
+        *self._format()
+    }
+
+    fn depth_format(&self) -> Format {
+        self.depth_format()
+    }
+
+    fn samples(&self) -> u32 {
+        self.samples()
+    }
+
+    fn dimensions(&self) -> [u32; 2] {
+        self._dimensions()
+    }
+
+    fn is_opaque(&self) -> bool {
+        self._composite_alpha() == CompositeAlpha::Opaque
+    }
+
+    fn images(&self) -> Vec<Arc<dyn ImageViewAccess + Send + Sync>> {
+        self._images()
+            .iter()
+            .map(|image| image.clone() as Arc<dyn ImageViewAccess + Send + Sync>)
+            .collect()
+    }
+
+    fn color_image(&self) -> Option<&Arc<AttachmentImage>> {
+        self.color_image()
+    }
+
+    fn depth_image(&self) -> &Arc<AttachmentImage> {
+        self.depth_image()
+    }
+}
+
+// a single image to render into without a window, e.g. for tests; it can be copied out with
+// `ScreenshotCapture`
+pub struct OffscreenTarget {
+    image: Arc<AttachmentImage>,
+    format: Format,
+    depth_format: Format,
+    samples: u32,
+    transparent: bool,
+    color_image: Option<Arc<AttachmentImage>>,
+    depth_image: Arc<AttachmentImage>,
+}
+
+impl OffscreenTarget {
+    // `samples` is lowered to what the device supports, as for swapchains; `transparent`
+    // targets keep the alpha they're drawn with, as a swapchain with a non-opaque composite
+    // alpha would
+    pub fn new(
+        logical_device: &LogicalDevice,
+        dimensions: [u32; 2],
+        format: Format,
+        samples: u32,
+        transparent: bool,
+    ) -> OffscreenTarget {
+        let device = logical_device.device();
+        let physical_device = device.physical_device();
+        let image = AttachmentImage::with_usage(
+            device.clone(),
+            dimensions,
+            format,
+            ImageUsage {
+                transfer_source: true,
+                ..ImageUsage::color_attachment()
+            },
+        )
+        .expect("failed to create offscreen image");
+        debug_utils::set_image_name(device, &*image, "offscreen image");
+        // devices without one aren't suitable, see `PhysicalDeviceInfo::is_valid`
+        let depth_format =
+            supported_depth_format(physical_device).expect("no supported depth format");
+        let limits = physical_device.limits();
+        let samples = choose_sample_count(
+            samples,
+            limits.framebuffer_color_sample_counts() & limits.framebuffer_depth_sample_counts(),
+        );
+        let (color_image, depth_image) =
+            create_attachments(device, dimensions, format, depth_format, samples);
+        OffscreenTarget {
+            image,
+            format,
+            depth_format,
+            samples,
+            transparent,
+            color_image,
+            depth_image,
+        }
+    }
+
+    pub fn image(&self) -> &Arc<AttachmentImage> {
+        &self.image
+    }
+}
+
+impl RenderTarget for OffscreenTarget {
+    fn format(&self) -> Format {
+        self.format
+    }
+
+    fn depth_format(&self) -> Format {
+        self.depth_format
+    }
+
+    fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    fn dimensions(&self) -> [u32; 2] {
+        self.image.dimensions()
+    }
+
+    fn is_opaque(&self) -> bool {
+        !self.transparent
+    }
+
+    fn images(&self) -> Vec<Arc<dyn ImageViewAccess + Send + Sync>> {
+        vec![self.image.clone()]
+    }
+
+    fn color_image(&self) -> Option<&Arc<AttachmentImage>> {
+        self.color_image.as_ref()
+    }
+
+    fn depth_image(&self) -> &Arc<AttachmentImage> {
+        &self.depth_image
+    }
+}
@@ -0,0 +1,410 @@
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::mesh::{Mesh, Vertex};
+use crate::app::paths;
+use crate::app::render_target::{OffscreenTarget, RenderTarget};
+use crate::app::shader::interface::ShaderInterface;
+use crate::app::shader::{self, ShaderCompiler, ShaderError};
+use crate::app::{DescriptorSetCache, Frame, FrameUniforms, LogicalDevice, Texture, UniformBuffer};
+
+use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::descriptor::ShaderStages;
+use vulkano::device::Device;
+use vulkano::format::ClearValue;
+use vulkano::framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract, Subpass};
+use vulkano::pipeline::cache::PipelineCache;
+use vulkano::pipeline::shader::{GraphicsShaderType, ShaderModule};
+use vulkano::pipeline::viewport::Viewport;
+use vulkano::pipeline::{
+    GraphicsPipeline, GraphicsPipelineAbstract, GraphicsPipelineCreationError,
+};
+
+use std::ffi::CStr;
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+mod vertex_shader {
+    vulkano_shaders::shader! {
+        ty: "vertex",
+        path: "shaders/mesh.vert"
+    }
+}
+
+mod fragment_shader {
+    vulkano_shaders::shader! {
+        ty: "fragment",
+        path: "shaders/mesh.frag"
+    }
+}
+
+// relative to `config::SHADER_DIRECTORY`; the same files are compiled into the binary above
+const VERTEX_SHADER: &str = "mesh.vert";
+const FRAGMENT_SHADER: &str = "mesh.frag";
+
+// alpha is 1 for opaque targets and 0 for transparent ones, see `RenderTarget::is_opaque`
+const CLEAR_COLOR: [f32; 3] = [0.0, 0.0, 0.0];
+
+// draws meshes into one window's swapchain images, or into an offscreen target
+pub struct Renderer {
+    device: Arc<Device>,
+    pipeline_cache: Arc<PipelineCache>,
+    render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
+    pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+    framebuffers: Vec<Arc<dyn FramebufferAbstract + Send + Sync>>,
+    uniforms: UniformBuffer<FrameUniforms>,
+    descriptor_sets: DescriptorSetCache,
+    // the files the current pipeline's shaders were built from, including their includes
+    shader_sources: Vec<PathBuf>,
+}
+
+impl Renderer {
+    pub fn new<T: RenderTarget>(logical_device: &LogicalDevice, target: &T) -> Renderer {
+        let device = logical_device.device();
+        let render_pass = create_render_pass(device, target);
+
+        let vertex_shader =
+            vertex_shader::Shader::load(device.clone()).expect("failed to load vertex shader");
+        let fragment_shader =
+            fragment_shader::Shader::load(device.clone()).expect("failed to load fragment shader");
+        // these are the modules the interfaces were generated from
+        let pipeline = unsafe {
+            create_pipeline(
+                device,
+                logical_device.pipeline_cache(),
+                &render_pass,
+                vertex_shader.module(),
+                fragment_shader.module(),
+            )
+        }
+        .expect("failed to create graphics pipeline");
+
+        let shader_directory = paths::resource_path(config::SHADER_DIRECTORY);
+        let mut renderer = Renderer {
+            device: device.clone(),
+            pipeline_cache: logical_device.pipeline_cache().clone(),
+            render_pass,
+            pipeline,
+            framebuffers: Vec::new(),
+            uniforms: UniformBuffer::new(device.clone()),
+            descriptor_sets: DescriptorSetCache::new(),
+            shader_sources: vec![
+                shader::canonical(&shader_directory.join(VERTEX_SHADER)),
+                shader::canonical(&shader_directory.join(FRAGMENT_SHADER)),
+            ],
+        };
+        renderer.recreate_framebuffers(target);
+        renderer
+    }
+
+    pub fn uses_any_shader_source(&self, paths: &[PathBuf]) -> bool {
+        self.shader_sources
+            .iter()
+            .any(|source| paths.contains(source))
+    }
+
+    // rebuilds the pipeline from the shader sources on disk; on failure the current pipeline is
+    // kept. The pipeline is still described with the interfaces vulkano-shaders generated from
+    // the built-in shaders, so new shaders whose inputs, outputs, descriptors or push constants
+    // don't fit those are rejected with `ShaderError::InterfaceMismatch`
+    pub fn reload_shaders(&mut self, compiler: &mut ShaderCompiler) -> Result<(), ShaderError> {
+        let shader_directory = paths::resource_path(config::SHADER_DIRECTORY);
+        let vertex_shader = compiler.load(&shader_directory.join(VERTEX_SHADER))?;
+        let fragment_shader = compiler.load(&shader_directory.join(FRAGMENT_SHADER))?;
+        vertex_shader.check_interface(&vertex_interface())?;
+        fragment_shader.check_interface(&fragment_interface())?;
+        self.pipeline = unsafe {
+            create_pipeline(
+                &self.device,
+                &self.pipeline_cache,
+                &self.render_pass,
+                &shader::create_module(&self.device, &vertex_shader)?,
+                &shader::create_module(&self.device, &fragment_shader)?,
+            )
+        }?;
+        // the new pipeline has new descriptor set layouts
+        self.descriptor_sets.clear();
+        self.shader_sources = vertex_shader.sources;
+        self.shader_sources.extend(fragment_shader.sources);
+        Ok(())
+    }
+
+    // must be called whenever the window's swapchain (and with it the attachments) is recreated
+    pub fn recreate_framebuffers<T: RenderTarget>(&mut self, target: &T) {
+        self.framebuffers = target
+            .images()
+            .into_iter()
+            .map(|image| {
+                let depth_image = target.depth_image().clone();
+                let framebuffer = Framebuffer::start(self.render_pass.clone());
+                // attachments are added in the order `create_render_pass` declares them
+                match target.color_image() {
+                    Some(color_image) => Arc::new(
+                        framebuffer
+                            .add(color_image.clone())
+                            .unwrap()
+                            .add(depth_image)
+                            .unwrap()
+                            .add(image)
+                            .unwrap()
+                            .build()
+                            .expect("failed to create framebuffer"),
+                    )
+                        as Arc<dyn FramebufferAbstract + Send + Sync>,
+                    None => Arc::new(
+                        framebuffer
+                            .add(image)
+                            .unwrap()
+                            .add(depth_image)
+                            .unwrap()
+                            .build()
+                            .expect("failed to create framebuffer"),
+                    ) as Arc<dyn FramebufferAbstract + Send + Sync>,
+                }
+            })
+            .collect();
+    }
+
+    pub fn draw(
+        &mut self,
+        frame: &Frame,
+        meshes: &[&Mesh<Vertex>],
+        uniforms: FrameUniforms,
+        texture: &Texture,
+    ) -> AutoCommandBuffer {
+        self.record(
+            frame.logical_device(),
+            frame.image_num(),
+            frame.swapchain_info(),
+            meshes,
+            uniforms,
+            texture,
+        )
+    }
+
+    // the renderer must have been created for `target`
+    pub fn draw_offscreen(
+        &mut self,
+        logical_device: &LogicalDevice,
+        target: &OffscreenTarget,
+        meshes: &[&Mesh<Vertex>],
+        uniforms: FrameUniforms,
+        texture: &Texture,
+    ) -> AutoCommandBuffer {
+        self.record(logical_device, 0, target, meshes, uniforms, texture)
+    }
+
+    fn record<T: RenderTarget>(
+        &mut self,
+        logical_device: &LogicalDevice,
+        framebuffer_index: usize,
+        target: &T,
+        meshes: &[&Mesh<Vertex>],
+        uniforms: FrameUniforms,
+        texture: &Texture,
+    ) -> AutoCommandBuffer {
+        let dimensions = target.dimensions();
+        let dynamic_state = DynamicState {
+            viewports: Some(vec![Viewport {
+                origin: [0.0, 0.0],
+                dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+                depth_range: 0.0..1.0,
+            }]),
+            ..DynamicState::none()
+        };
+
+        let uniform_buffer = self.uniforms.next(uniforms);
+        let descriptor_set = Arc::new(
+            self.descriptor_sets
+                .pool(&*self.pipeline, 0)
+                .next()
+                .add_buffer(uniform_buffer)
+                .unwrap()
+                .add_sampled_image(texture.image().clone(), texture.sampler().clone())
+                .unwrap()
+                .build()
+                .expect("failed to create descriptor set"),
+        );
+
+        let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(
+            logical_device.device().clone(),
+            logical_device.graphics_queue().family(),
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                self.framebuffers[framebuffer_index].clone(),
+                false,
+                clear_values(target),
+            )
+            .unwrap();
+        for mesh in meshes {
+            builder
+                .draw_indexed(
+                    self.pipeline.clone(),
+                    &dynamic_state,
+                    mesh.vertex_buffer().clone(),
+                    mesh.index_buffer().clone(),
+                    descriptor_set.clone(),
+                    (),
+                )
+                .unwrap();
+        }
+        builder.end_render_pass().unwrap();
+        builder.build().unwrap()
+    }
+}
+
+// the interfaces vulkano-shaders generated for the built-in shaders, with the uniform buffer as
+// large as `Renderer::record` binds it
+fn vertex_interface() -> ShaderInterface {
+    ShaderInterface::from_definitions(
+        &vertex_shader::MainInput,
+        &vertex_shader::MainOutput,
+        &vertex_layout(),
+    )
+    .with_buffer_size(0, 0, mem::size_of::<FrameUniforms>() as u32)
+}
+
+fn fragment_interface() -> ShaderInterface {
+    ShaderInterface::from_definitions(
+        &fragment_shader::MainInput,
+        &fragment_shader::MainOutput,
+        &fragment_layout(),
+    )
+}
+
+fn vertex_layout() -> vertex_shader::Layout {
+    vertex_shader::Layout(ShaderStages {
+        vertex: true,
+        ..ShaderStages::none()
+    })
+}
+
+fn fragment_layout() -> fragment_shader::Layout {
+    fragment_shader::Layout(ShaderStages {
+        fragment: true,
+        ..ShaderStages::none()
+    })
+}
+
+// unsafe because the entry points use the interfaces vulkano-shaders generated for the built-in
+// shaders, which nothing checks the modules against here; `Renderer::reload_shaders` checks
+// reloaded ones with `CompiledShader::check_interface` first
+unsafe fn create_pipeline(
+    device: &Arc<Device>,
+    pipeline_cache: &Arc<PipelineCache>,
+    render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>,
+    vertex_module: &ShaderModule,
+    fragment_module: &ShaderModule,
+) -> Result<Arc<dyn GraphicsPipelineAbstract + Send + Sync>, GraphicsPipelineCreationError> {
+    let main = CStr::from_bytes_with_nul(b"main\0").unwrap();
+    let (vertex_entry_point, fragment_entry_point) = (
+        vertex_module.graphics_entry_point(
+            main,
+            vertex_shader::MainInput,
+            vertex_shader::MainOutput,
+            vertex_layout(),
+            GraphicsShaderType::Vertex,
+        ),
+        fragment_module.graphics_entry_point(
+            main,
+            fragment_shader::MainInput,
+            fragment_shader::MainOutput,
+            fragment_layout(),
+            GraphicsShaderType::Fragment,
+        ),
+    );
+    // the viewport is dynamic so the pipeline survives swapchain recreation
+    let pipeline = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<Vertex>()
+        .vertex_shader(vertex_entry_point, ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .depth_stencil_simple_depth()
+        .fragment_shader(fragment_entry_point, ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build_with_cache(pipeline_cache.clone())
+        .build(device.clone())?;
+    debug_utils::set_pipeline_name(device, &pipeline, "mesh pipeline");
+    Ok(Arc::new(pipeline))
+}
+
+// with MSAA, rendering goes to transient multisampled attachments and the colour is resolved
+// into the target's image at the end of the pass
+fn create_render_pass<T: RenderTarget>(
+    device: &Arc<Device>,
+    target: &T,
+) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    let format = target.format();
+    let depth_format = target.depth_format();
+    let samples = target.samples();
+    if samples == 1 {
+        return Arc::new(
+            vulkano::single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        load: Clear,
+                        store: Store,
+                        format: format,
+                        samples: 1,
+                    },
+                    depth: {
+                        load: Clear,
+                        store: DontCare,
+                        format: depth_format,
+                        samples: 1,
+                    }
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {depth}
+                }
+            )
+            .expect("failed to create render pass"),
+        );
+    }
+    Arc::new(
+        vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                multisampled_color: {
+                    load: Clear,
+                    store: DontCare,
+                    format: format,
+                    samples: samples,
+                },
+                depth: {
+                    load: Clear,
+                    store: DontCare,
+                    format: depth_format,
+                    samples: samples,
+                },
+                color: {
+                    load: DontCare,
+                    store: Store,
+                    format: format,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [multisampled_color],
+                depth_stencil: {depth},
+                resolve: [color],
+            }
+        )
+        .expect("failed to create render pass"),
+    )
+}
+
+// one per attachment of `create_render_pass`'s render pass
+fn clear_values<T: RenderTarget>(target: &T) -> Vec<ClearValue> {
+    let alpha = if target.is_opaque() { 1.0 } else { 0.0 };
+    let color = [CLEAR_COLOR[0], CLEAR_COLOR[1], CLEAR_COLOR[2], alpha];
+    if target.samples() == 1 {
+        vec![color.into(), 1f32.into()]
+    } else {
+        vec![color.into(), 1f32.into(), ClearValue::None]
+    }
+}
@@ -0,0 +1,247 @@
+use vulkano::buffer::cpu_access::ReadLockError;
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder, CopyBufferImageError};
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::image::ImageAccess;
+use vulkano::instance::QueueFamily;
+
+use image::RgbaImage;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+pub enum ScreenshotError {
+    UnsupportedFormat(Format),
+    Copy(CopyBufferImageError),
+    Read(ReadLockError),
+    Io(io::Error),
+    Encode(image::ImageError),
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScreenshotError::UnsupportedFormat(format) => {
+                write!(f, "can't convert {:?} images to RGBA8", format)
+            }
+            ScreenshotError::Copy(e) => write!(f, "failed to copy image: {}", e),
+            ScreenshotError::Read(e) => write!(f, "failed to read screenshot buffer: {}", e),
+            ScreenshotError::Io(e) => write!(f, "{}", e),
+            ScreenshotError::Encode(e) => write!(f, "failed to write PNG: {}", e),
+        }
+    }
+}
+
+impl Error for ScreenshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScreenshotError::UnsupportedFormat(_) => None,
+            ScreenshotError::Copy(e) => Some(e),
+            ScreenshotError::Read(e) => Some(e),
+            ScreenshotError::Io(e) => Some(e),
+            ScreenshotError::Encode(e) => Some(e),
+        }
+    }
+}
+
+impl From<CopyBufferImageError> for ScreenshotError {
+    fn from(e: CopyBufferImageError) -> ScreenshotError {
+        ScreenshotError::Copy(e)
+    }
+}
+
+impl From<ReadLockError> for ScreenshotError {
+    fn from(e: ReadLockError) -> ScreenshotError {
+        ScreenshotError::Read(e)
+    }
+}
+
+impl From<io::Error> for ScreenshotError {
+    fn from(e: io::Error) -> ScreenshotError {
+        ScreenshotError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for ScreenshotError {
+    fn from(e: image::ImageError) -> ScreenshotError {
+        ScreenshotError::Encode(e)
+    }
+}
+
+// a copy of an image in a host-visible buffer; it can only be read once the command buffer
+// returned by `record` has finished executing
+pub struct ScreenshotCapture {
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    dimensions: [u32; 2],
+    format: Format,
+    opaque: bool,
+}
+
+impl ScreenshotCapture {
+    // works for swapchain images created with `transfer_source` usage as well as offscreen
+    // targets; `opaque` replaces the image's alpha, which an opaque swapchain may leave undefined
+    pub fn record<I>(
+        device: &Arc<Device>,
+        queue_family: QueueFamily,
+        image: Arc<I>,
+        format: Format,
+        dimensions: [u32; 2],
+        opaque: bool,
+    ) -> Result<(ScreenshotCapture, AutoCommandBuffer), ScreenshotError>
+    where
+        I: ImageAccess + Send + Sync + 'static,
+    {
+        if !is_supported_format(format) {
+            return Err(ScreenshotError::UnsupportedFormat(format));
+        }
+        let length = dimensions[0] as usize * dimensions[1] as usize * 4;
+        // host cached, since the buffer is only ever read by the CPU
+        let buffer = unsafe {
+            CpuAccessibleBuffer::uninitialized_array(
+                device.clone(),
+                length,
+                BufferUsage::transfer_destination(),
+                true,
+            )
+            .expect("failed to create screenshot buffer")
+        };
+        let mut builder =
+            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue_family)
+                .unwrap();
+        builder.copy_image_to_buffer(image, buffer.clone())?;
+        let capture = ScreenshotCapture {
+            buffer,
+            dimensions,
+            format,
+            opaque,
+        };
+        Ok((capture, builder.build().unwrap()))
+    }
+
+    pub fn to_rgba8(&self) -> Result<RgbaImage, ScreenshotError> {
+        let data = self.buffer.read()?;
+        let pixels = convert_to_rgba8(self.format, &data, self.opaque)?;
+        Ok(
+            RgbaImage::from_raw(self.dimensions[0], self.dimensions[1], pixels)
+                .expect("screenshot buffer doesn't match its dimensions"),
+        )
+    }
+
+    // writes a PNG named after the current time into `directory`, creating it if needed
+    pub fn save(&self, directory: &Path) -> Result<PathBuf, ScreenshotError> {
+        let image = self.to_rgba8()?;
+        fs::create_dir_all(directory)?;
+        let path = directory.join(file_name(SystemTime::now()));
+        image.save_with_format(&path, image::ImageFormat::Png)?;
+        Ok(path)
+    }
+}
+
+pub fn is_supported_format(format: Format) -> bool {
+    matches!(
+        format,
+        Format::B8G8R8A8Unorm
+            | Format::B8G8R8A8Srgb
+            | Format::R8G8B8A8Unorm
+            | Format::R8G8B8A8Srgb
+            | Format::A8B8G8R8UnormPack32
+            | Format::A8B8G8R8SrgbPack32
+            | Format::A2B10G10R10UnormPack32
+            | Format::A2R10G10B10UnormPack32
+    )
+}
+
+// the stored values are copied as they are: sRGB formats already hold sRGB encoded values, and
+// UNORM swapchains are presented as sRGB without conversion, so either way the bytes are what
+// ended up on screen. 10-bit channels are rounded to 8 bits
+pub fn convert_to_rgba8(
+    format: Format,
+    data: &[u8],
+    opaque: bool,
+) -> Result<Vec<u8>, ScreenshotError> {
+    let mut pixels: Vec<u8> = match format {
+        Format::R8G8B8A8Unorm
+        | Format::R8G8B8A8Srgb
+        | Format::A8B8G8R8UnormPack32
+        | Format::A8B8G8R8SrgbPack32 => data.to_vec(),
+        Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => {
+            let mut pixels = Vec::with_capacity(data.len());
+            for p in data.chunks_exact(4) {
+                pixels.extend_from_slice(&[p[2], p[1], p[0], p[3]]);
+            }
+            pixels
+        }
+        Format::A2B10G10R10UnormPack32 | Format::A2R10G10B10UnormPack32 => {
+            let mut pixels = Vec::with_capacity(data.len());
+            for p in data.chunks_exact(4) {
+                let packed = u32::from_ne_bytes([p[0], p[1], p[2], p[3]]);
+                let low = unorm10_to_8(packed);
+                let middle = unorm10_to_8(packed >> 10);
+                let high = unorm10_to_8(packed >> 20);
+                let alpha = (packed >> 30) as u8 * 85;
+                if format == Format::A2B10G10R10UnormPack32 {
+                    pixels.extend_from_slice(&[low, middle, high, alpha]);
+                } else {
+                    pixels.extend_from_slice(&[high, middle, low, alpha]);
+                }
+            }
+            pixels
+        }
+        format => return Err(ScreenshotError::UnsupportedFormat(format)),
+    };
+    if opaque {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+    }
+    Ok(pixels)
+}
+
+fn unorm10_to_8(value: u32) -> u8 {
+    (((value & 0x3ff) * 255 + 511) / 1023) as u8
+}
+
+// e.g. "screenshot_2020-06-01_12-30-45.123.png", in UTC so names sort chronologically
+pub fn file_name(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+    format!(
+        "screenshot_{:04}-{:02}-{:02}_{:02}-{:02}-{:02}.{:03}.png",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+// days since 1970-01-01 to a proleptic Gregorian (year, month, day), after Howard Hinnant's
+// `civil_from_days`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
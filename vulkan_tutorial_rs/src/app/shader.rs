@@ -0,0 +1,309 @@
+pub mod interface;
+
+use crate::app::config;
+use crate::app::paths;
+
+use self::interface::{InterfaceMismatch, ReflectionError, ShaderInterface};
+
+use shaderc::{CompileOptions, Compiler, IncludeType, ResolvedInclude, ShaderKind, SourceLanguage};
+
+use vulkano::device::Device;
+use vulkano::pipeline::shader::ShaderModule;
+use vulkano::pipeline::GraphicsPipelineCreationError;
+use vulkano::OomError;
+
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// one message from the shader compiler
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file, line, severity, self.message),
+            None => write!(f, "{}: {}: {}", self.file, severity, self.message),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ShaderError {
+    Io(PathBuf, io::Error),
+    UnknownStage(PathBuf),
+    Compilation(Vec<Diagnostic>),
+    InvalidSpirv(PathBuf),
+    Reflection(PathBuf, ReflectionError),
+    InterfaceMismatch(PathBuf, InterfaceMismatch),
+    ModuleCreation(OomError),
+    PipelineCreation(GraphicsPipelineCreationError),
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderError::Io(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            ShaderError::UnknownStage(path) => {
+                write!(f, "can't tell the shader stage of {}", path.display())
+            }
+            ShaderError::Compilation(diagnostics) => {
+                write!(f, "failed to compile shader")?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            ShaderError::InvalidSpirv(path) => write!(f, "invalid SPIR-V in {}", path.display()),
+            ShaderError::Reflection(path, e) => {
+                write!(f, "failed to reflect {}: {}", path.display(), e)
+            }
+            ShaderError::InterfaceMismatch(path, e) => write!(
+                f,
+                "{} doesn't fit the pipeline it's meant for: {}",
+                path.display(),
+                e
+            ),
+            ShaderError::ModuleCreation(e) => write!(f, "failed to create shader module: {}", e),
+            ShaderError::PipelineCreation(e) => write!(f, "failed to create pipeline: {}", e),
+        }
+    }
+}
+
+impl Error for ShaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShaderError::Io(_, e) => Some(e),
+            ShaderError::UnknownStage(_) => None,
+            ShaderError::Compilation(_) => None,
+            ShaderError::InvalidSpirv(_) => None,
+            ShaderError::Reflection(_, e) => Some(e),
+            ShaderError::InterfaceMismatch(_, e) => Some(e),
+            ShaderError::ModuleCreation(e) => Some(e),
+            ShaderError::PipelineCreation(e) => Some(e),
+        }
+    }
+}
+
+impl From<OomError> for ShaderError {
+    fn from(e: OomError) -> ShaderError {
+        ShaderError::ModuleCreation(e)
+    }
+}
+
+impl From<GraphicsPipelineCreationError> for ShaderError {
+    fn from(e: GraphicsPipelineCreationError) -> ShaderError {
+        ShaderError::PipelineCreation(e)
+    }
+}
+
+pub struct CompiledShader {
+    pub spirv: Vec<u32>,
+    // the shader's own file followed by everything it includes
+    pub sources: Vec<PathBuf>,
+}
+
+impl CompiledShader {
+    // whether the `main` entry point can replace a shader with the `expected` interface in an
+    // existing pipeline layout
+    pub fn check_interface(&self, expected: &ShaderInterface) -> Result<(), ShaderError> {
+        let path = &self.sources[0];
+        interface::reflect(&self.spirv, "main")
+            .map_err(|e| ShaderError::Reflection(path.clone(), e))?
+            .check_compatible(expected)
+            .map_err(|e| ShaderError::InterfaceMismatch(path.clone(), e))
+    }
+}
+
+// loads shaders at runtime: `.spv` files are used as they are, GLSL (`.vert`, `.frag`, ...) and
+// HLSL (`.vert.hlsl`, `.frag.hlsl`, ...) sources are compiled with shaderc. `#include "..."` is
+// resolved relative to the including file, `#include <...>` relative to the shader directory
+pub struct ShaderCompiler {
+    compiler: Compiler,
+}
+
+impl Default for ShaderCompiler {
+    fn default() -> ShaderCompiler {
+        ShaderCompiler::new()
+    }
+}
+
+impl ShaderCompiler {
+    pub fn new() -> ShaderCompiler {
+        ShaderCompiler {
+            compiler: Compiler::new().expect("failed to create shader compiler"),
+        }
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<CompiledShader, ShaderError> {
+        let path = canonical(path);
+        if has_extension(&path, "spv") {
+            let bytes = fs::read(&path).map_err(|e| ShaderError::Io(path.clone(), e))?;
+            let spirv = words(&bytes).ok_or_else(|| ShaderError::InvalidSpirv(path.clone()))?;
+            return Ok(CompiledShader {
+                spirv,
+                sources: vec![path],
+            });
+        }
+
+        let (language, kind) = source_language_and_kind(&path)
+            .ok_or_else(|| ShaderError::UnknownStage(path.clone()))?;
+        let source = fs::read_to_string(&path).map_err(|e| ShaderError::Io(path.clone(), e))?;
+        let includes = RefCell::new(Vec::new());
+        let mut options = CompileOptions::new().expect("failed to create shader compile options");
+        options.set_source_language(language);
+        options.set_generate_debug_info();
+        options.set_include_callback(|name, include_type, including_file, _depth| {
+            let directory = match include_type {
+                IncludeType::Relative => Path::new(including_file)
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+                IncludeType::Standard => paths::resource_path(config::SHADER_DIRECTORY),
+            };
+            let include = canonical(&directory.join(name));
+            let content = fs::read_to_string(&include)
+                .map_err(|e| format!("failed to read {}: {}", include.display(), e))?;
+            includes.borrow_mut().push(include.clone());
+            Ok(ResolvedInclude {
+                resolved_name: include.to_string_lossy().into_owned(),
+                content,
+            })
+        });
+
+        let file_name = path.to_string_lossy();
+        let artifact = self
+            .compiler
+            .compile_into_spirv(&source, kind, &file_name, "main", Some(&options))
+            .map_err(|e| match e {
+                shaderc::Error::CompilationError(_, messages) => {
+                    ShaderError::Compilation(parse_diagnostics(&messages))
+                }
+                e => ShaderError::Compilation(vec![Diagnostic {
+                    file: file_name.to_string(),
+                    line: None,
+                    severity: Severity::Error,
+                    message: e.to_string(),
+                }]),
+            })?;
+        for warning in parse_diagnostics(&artifact.get_warning_messages()) {
+            println!("WARNING: {}", warning);
+        }
+        drop(options);
+
+        let mut sources = vec![path.clone()];
+        sources.extend(includes.into_inner());
+        Ok(CompiledShader {
+            spirv: artifact.as_binary().to_vec(),
+            sources,
+        })
+    }
+}
+
+pub fn create_module(
+    device: &Arc<Device>,
+    shader: &CompiledShader,
+) -> Result<Arc<ShaderModule>, ShaderError> {
+    // the SPIR-V is only checked by the validation layers, if at all
+    Ok(unsafe { ShaderModule::from_words(device.clone(), &shader.spirv)? })
+}
+
+// parses shaderc's `file:line: error: message` output; lines that don't look like that (e.g.
+// the trailing "1 error generated.") are skipped
+pub fn parse_diagnostics(messages: &str) -> Vec<Diagnostic> {
+    messages
+        .lines()
+        .filter_map(|line| {
+            let (location, severity, message) = [
+                (": error: ", Severity::Error),
+                (": warning: ", Severity::Warning),
+            ]
+            .iter()
+            .filter_map(|&(separator, severity)| {
+                line.find(separator)
+                    .map(|at| (&line[..at], severity, &line[at + separator.len()..]))
+            })
+            .min_by_key(|(location, _, _)| location.len())?;
+            let (file, line_number) = match location.rfind(':') {
+                Some(at) => match location[at + 1..].parse() {
+                    Ok(line_number) => (&location[..at], Some(line_number)),
+                    Err(_) => (location, None),
+                },
+                None => (location, None),
+            };
+            Some(Diagnostic {
+                file: file.to_string(),
+                line: line_number,
+                severity,
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+// GLSL stages come from the extension, HLSL ones from the extension before `.hlsl`
+fn source_language_and_kind(path: &Path) -> Option<(SourceLanguage, ShaderKind)> {
+    let (language, stage_path) = if has_extension(path, "hlsl") {
+        (SourceLanguage::HLSL, path.file_stem().map(Path::new)?)
+    } else {
+        (SourceLanguage::GLSL, path)
+    };
+    let kind = match stage_path.extension()?.to_str()? {
+        "vert" => ShaderKind::Vertex,
+        "frag" => ShaderKind::Fragment,
+        "comp" => ShaderKind::Compute,
+        "geom" => ShaderKind::Geometry,
+        "tesc" => ShaderKind::TessControl,
+        "tese" => ShaderKind::TessEvaluation,
+        _ => return None,
+    };
+    Some((language, kind))
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .map_or(false, |e| e.eq_ignore_ascii_case(extension))
+}
+
+// file watcher events use canonical paths, so sources are recorded that way too
+pub fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn words(bytes: &[u8]) -> Option<Vec<u32>> {
+    const MAGIC: u32 = 0x0723_0203;
+    if bytes.len() % 4 != 0 || bytes.len() < 4 {
+        return None;
+    }
+    let words: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+        .collect();
+    if words[0] == MAGIC {
+        Some(words)
+    } else if words[0].swap_bytes() == MAGIC {
+        Some(words.into_iter().map(u32::swap_bytes).collect())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,869 @@
+use vulkano::descriptor::descriptor::{
+    DescriptorDescTy, DescriptorImageDesc, DescriptorImageDescArray, DescriptorImageDescDimensions,
+};
+use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
+use vulkano::format::Format;
+use vulkano::pipeline::shader::ShaderInterfaceDef;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+const MAGIC: u32 = 0x0723_0203;
+const HEADER_LENGTH: usize = 5;
+
+const OP_ENTRY_POINT: u32 = 15;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_OUTPUT: u32 = 3;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const DIM_1D: u32 = 0;
+const DIM_2D: u32 = 1;
+const DIM_3D: u32 = 2;
+const DIM_CUBE: u32 = 3;
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+// what a graphics pipeline is built against: the entry point's user-defined inputs and outputs,
+// one per location, the descriptors the module declares and the size of its push constants.
+// Reloaded shaders are checked against the interface of the shaders a pipeline was built from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderInterface {
+    // sorted by location
+    pub inputs: Vec<InterfaceVariable>,
+    pub outputs: Vec<InterfaceVariable>,
+    // sorted by set and binding
+    pub descriptors: Vec<DescriptorBinding>,
+    pub push_constant_size: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InterfaceVariable {
+    pub location: u32,
+    pub format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DescriptorBinding {
+    pub set: u32,
+    pub binding: u32,
+    pub kind: DescriptorKind,
+    // 0 for runtime sized arrays
+    pub count: u32,
+    // the size of a buffer's block in bytes, if known
+    pub size: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DescriptorKind {
+    Sampler,
+    CombinedImageSampler(ImageKind),
+    SampledImage(ImageKind),
+    StorageImage(ImageKind),
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    InputAttachment,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageKind {
+    pub dimensions: ImageDimensions,
+    pub arrayed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageDimensions {
+    OneDimensional,
+    TwoDimensional,
+    ThreeDimensional,
+    Cube,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReflectionError {
+    Malformed,
+    MissingEntryPoint(String),
+    // an input or output without a location, such as a block, or of a type with no single
+    // attribute format per location, such as a struct
+    UnsupportedInterfaceVariable { id: u32 },
+    // a descriptor of a type Vulkan doesn't allow, or one without a set or binding
+    UnsupportedDescriptor { id: u32 },
+}
+
+impl fmt::Display for ReflectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReflectionError::Malformed => write!(f, "malformed SPIR-V"),
+            ReflectionError::MissingEntryPoint(name) => write!(f, "no entry point named {}", name),
+            ReflectionError::UnsupportedInterfaceVariable { id } => {
+                write!(f, "unsupported input or output variable %{}", id)
+            }
+            ReflectionError::UnsupportedDescriptor { id } => {
+                write!(f, "unsupported descriptor variable %{}", id)
+            }
+        }
+    }
+}
+
+impl Error for ReflectionError {}
+
+#[derive(Debug, PartialEq)]
+pub enum InterfaceMismatch {
+    Inputs {
+        expected: Vec<InterfaceVariable>,
+        found: Vec<InterfaceVariable>,
+    },
+    Outputs {
+        expected: Vec<InterfaceVariable>,
+        found: Vec<InterfaceVariable>,
+    },
+    // the descriptor isn't in the pipeline layout, or is there with another type or count
+    Descriptor {
+        set: u32,
+        binding: u32,
+    },
+    // the shader reads more of a buffer than is bound to it
+    BufferSize {
+        set: u32,
+        binding: u32,
+        size: u32,
+        available: u32,
+    },
+    PushConstantSize {
+        size: u32,
+        available: u32,
+    },
+}
+
+impl fmt::Display for InterfaceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterfaceMismatch::Inputs { expected, found } => write!(
+                f,
+                "inputs {} don't match {}",
+                variables(found),
+                variables(expected)
+            ),
+            InterfaceMismatch::Outputs { expected, found } => write!(
+                f,
+                "outputs {} don't match {}",
+                variables(found),
+                variables(expected)
+            ),
+            InterfaceMismatch::Descriptor { set, binding } => write!(
+                f,
+                "descriptor set {} binding {} doesn't match the pipeline layout",
+                set, binding
+            ),
+            InterfaceMismatch::BufferSize {
+                set,
+                binding,
+                size,
+                available,
+            } => write!(
+                f,
+                "set {} binding {} is {} bytes, only {} are bound",
+                set, binding, size, available
+            ),
+            InterfaceMismatch::PushConstantSize { size, available } => write!(
+                f,
+                "push constants are {} bytes, the pipeline layout has {}",
+                size, available
+            ),
+        }
+    }
+}
+
+impl Error for InterfaceMismatch {}
+
+fn variables(variables: &[InterfaceVariable]) -> String {
+    let variables: Vec<_> = variables
+        .iter()
+        .map(|v| format!("{}: {:?}", v.location, v.format))
+        .collect();
+    format!("[{}]", variables.join(", "))
+}
+
+impl ShaderInterface {
+    // the interface vulkano-shaders generated for a built-in shader; descriptor sizes aren't
+    // known to vulkano, see `with_buffer_size`
+    pub fn from_definitions<I, O, L>(input: &I, output: &O, layout: &L) -> ShaderInterface
+    where
+        I: ShaderInterfaceDef,
+        O: ShaderInterfaceDef,
+        L: PipelineLayoutDesc,
+    {
+        let mut descriptors = Vec::new();
+        for set in 0..layout.num_sets() {
+            for binding in 0..layout.num_bindings_in_set(set).unwrap_or(0) {
+                if let Some(descriptor) = layout.descriptor(set, binding) {
+                    descriptors.push(DescriptorBinding {
+                        set: set as u32,
+                        binding: binding as u32,
+                        kind: descriptor_kind(&descriptor.ty),
+                        count: descriptor.array_count,
+                        size: None,
+                    });
+                }
+            }
+        }
+        let push_constant_size = (0..layout.num_push_constants_ranges())
+            .filter_map(|i| layout.push_constants_range(i))
+            .map(|range| (range.offset + range.size) as u32)
+            .max()
+            .unwrap_or(0);
+        ShaderInterface {
+            inputs: interface_variables(input),
+            outputs: interface_variables(output),
+            descriptors,
+            push_constant_size,
+        }
+    }
+
+    // the number of bytes bound to a buffer descriptor, which reloaded shaders may not read past
+    pub fn with_buffer_size(mut self, set: u32, binding: u32, size: u32) -> ShaderInterface {
+        for descriptor in &mut self.descriptors {
+            if descriptor.set == set && descriptor.binding == binding {
+                descriptor.size = Some(size);
+            }
+        }
+        self
+    }
+
+    // a pipeline built for `expected` can use a module with this interface if the inputs and
+    // outputs are the same and everything else fits in what the pipeline layout provides
+    pub fn check_compatible(&self, expected: &ShaderInterface) -> Result<(), InterfaceMismatch> {
+        if self.inputs != expected.inputs {
+            return Err(InterfaceMismatch::Inputs {
+                expected: expected.inputs.clone(),
+                found: self.inputs.clone(),
+            });
+        }
+        if self.outputs != expected.outputs {
+            return Err(InterfaceMismatch::Outputs {
+                expected: expected.outputs.clone(),
+                found: self.outputs.clone(),
+            });
+        }
+        for descriptor in &self.descriptors {
+            let available = expected
+                .descriptors
+                .iter()
+                .find(|d| d.set == descriptor.set && d.binding == descriptor.binding)
+                .filter(|d| d.kind == descriptor.kind && d.count == descriptor.count)
+                .ok_or(InterfaceMismatch::Descriptor {
+                    set: descriptor.set,
+                    binding: descriptor.binding,
+                })?;
+            if let (Some(size), Some(available)) = (descriptor.size, available.size) {
+                if size > available {
+                    return Err(InterfaceMismatch::BufferSize {
+                        set: descriptor.set,
+                        binding: descriptor.binding,
+                        size,
+                        available,
+                    });
+                }
+            }
+        }
+        if self.push_constant_size > expected.push_constant_size {
+            return Err(InterfaceMismatch::PushConstantSize {
+                size: self.push_constant_size,
+                available: expected.push_constant_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+// one entry per location, as in reflected interfaces
+fn interface_variables<I: ShaderInterfaceDef>(interface: &I) -> Vec<InterfaceVariable> {
+    let mut variables: Vec<_> = interface
+        .elements()
+        .flat_map(|element| {
+            let format = element.format;
+            element
+                .location
+                .map(move |location| InterfaceVariable { location, format })
+        })
+        .collect();
+    variables.sort_by_key(|v| v.location);
+    variables
+}
+
+fn descriptor_kind(ty: &DescriptorDescTy) -> DescriptorKind {
+    match ty {
+        DescriptorDescTy::Sampler => DescriptorKind::Sampler,
+        DescriptorDescTy::CombinedImageSampler(image) => {
+            DescriptorKind::CombinedImageSampler(image_kind(image))
+        }
+        DescriptorDescTy::Image(image) if image.sampled => {
+            DescriptorKind::SampledImage(image_kind(image))
+        }
+        DescriptorDescTy::Image(image) => DescriptorKind::StorageImage(image_kind(image)),
+        DescriptorDescTy::TexelBuffer { storage: false, .. } => DescriptorKind::UniformTexelBuffer,
+        DescriptorDescTy::TexelBuffer { storage: true, .. } => DescriptorKind::StorageTexelBuffer,
+        DescriptorDescTy::InputAttachment { .. } => DescriptorKind::InputAttachment,
+        DescriptorDescTy::Buffer(buffer) if buffer.storage => DescriptorKind::StorageBuffer,
+        DescriptorDescTy::Buffer(_) => DescriptorKind::UniformBuffer,
+    }
+}
+
+fn image_kind(image: &DescriptorImageDesc) -> ImageKind {
+    ImageKind {
+        dimensions: match image.dimensions {
+            DescriptorImageDescDimensions::OneDimensional => ImageDimensions::OneDimensional,
+            DescriptorImageDescDimensions::TwoDimensional => ImageDimensions::TwoDimensional,
+            DescriptorImageDescDimensions::ThreeDimensional => ImageDimensions::ThreeDimensional,
+            DescriptorImageDescDimensions::Cube => ImageDimensions::Cube,
+        },
+        arrayed: !matches!(image.array_layers, DescriptorImageDescArray::NonArrayed),
+    }
+}
+
+enum Type {
+    Scalar {
+        float: bool,
+        signed: bool,
+        width: u32,
+    },
+    Vector {
+        component: u32,
+        count: u32,
+    },
+    Matrix {
+        column: u32,
+        columns: u32,
+    },
+    Image {
+        dim: u32,
+        arrayed: bool,
+        sampled: u32,
+    },
+    Sampler,
+    SampledImage {
+        image: u32,
+    },
+    Array {
+        element: u32,
+        length: u32,
+    },
+    RuntimeArray {
+        element: u32,
+    },
+    Struct {
+        members: Vec<u32>,
+    },
+    Pointer {
+        pointee: u32,
+    },
+}
+
+#[derive(Default)]
+struct Module {
+    types: HashMap<u32, Type>,
+    constants: HashMap<u32, u32>,
+    // (result type, storage class) by id
+    variables: HashMap<u32, (u32, u32)>,
+    decorations: HashMap<(u32, u32), u32>,
+    flags: Vec<(u32, u32)>,
+    member_decorations: HashMap<(u32, u32, u32), u32>,
+    // (name, interface ids)
+    entry_points: Vec<(String, Vec<u32>)>,
+}
+
+impl Module {
+    fn parse(spirv: &[u32]) -> Result<Module, ReflectionError> {
+        if spirv.len() < HEADER_LENGTH || spirv[0] != MAGIC {
+            return Err(ReflectionError::Malformed);
+        }
+        let mut module = Module::default();
+        let mut rest = &spirv[HEADER_LENGTH..];
+        while !rest.is_empty() {
+            let length = (rest[0] >> 16) as usize;
+            if length == 0 || length > rest.len() {
+                return Err(ReflectionError::Malformed);
+            }
+            let (instruction, next) = rest.split_at(length);
+            module.add(instruction[0] & 0xffff, &instruction[1..])?;
+            rest = next;
+        }
+        Ok(module)
+    }
+
+    fn add(&mut self, opcode: u32, operands: &[u32]) -> Result<(), ReflectionError> {
+        let operand = |i: usize| operands.get(i).cloned().ok_or(ReflectionError::Malformed);
+        match opcode {
+            OP_ENTRY_POINT => {
+                let (name, length) = string(operands.get(2..).unwrap_or(&[]))?;
+                let interface = operands.get(2 + length..).unwrap_or(&[]).to_vec();
+                self.entry_points.push((name, interface));
+            }
+            OP_TYPE_BOOL => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Scalar {
+                        float: false,
+                        signed: false,
+                        width: 32,
+                    },
+                );
+            }
+            OP_TYPE_INT => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Scalar {
+                        float: false,
+                        signed: operand(2)? != 0,
+                        width: operand(1)?,
+                    },
+                );
+            }
+            OP_TYPE_FLOAT => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Scalar {
+                        float: true,
+                        signed: true,
+                        width: operand(1)?,
+                    },
+                );
+            }
+            OP_TYPE_VECTOR => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Vector {
+                        component: operand(1)?,
+                        count: operand(2)?,
+                    },
+                );
+            }
+            OP_TYPE_MATRIX => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Matrix {
+                        column: operand(1)?,
+                        columns: operand(2)?,
+                    },
+                );
+            }
+            OP_TYPE_IMAGE => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Image {
+                        dim: operand(2)?,
+                        arrayed: operand(4)? != 0,
+                        sampled: operand(6)?,
+                    },
+                );
+            }
+            OP_TYPE_SAMPLER => {
+                self.types.insert(operand(0)?, Type::Sampler);
+            }
+            OP_TYPE_SAMPLED_IMAGE => {
+                self.types
+                    .insert(operand(0)?, Type::SampledImage { image: operand(1)? });
+            }
+            OP_TYPE_ARRAY => {
+                let length = *self
+                    .constants
+                    .get(&operand(2)?)
+                    .ok_or(ReflectionError::Malformed)?;
+                self.types.insert(
+                    operand(0)?,
+                    Type::Array {
+                        element: operand(1)?,
+                        length,
+                    },
+                );
+            }
+            OP_TYPE_RUNTIME_ARRAY => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::RuntimeArray {
+                        element: operand(1)?,
+                    },
+                );
+            }
+            OP_TYPE_STRUCT => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Struct {
+                        members: operands[1..].to_vec(),
+                    },
+                );
+            }
+            OP_TYPE_POINTER => {
+                self.types.insert(
+                    operand(0)?,
+                    Type::Pointer {
+                        pointee: operand(2)?,
+                    },
+                );
+            }
+            // only the low word matters, for array lengths
+            OP_CONSTANT => {
+                self.constants.insert(operand(1)?, operand(2)?);
+            }
+            OP_VARIABLE => {
+                self.variables
+                    .insert(operand(1)?, (operand(0)?, operand(2)?));
+            }
+            OP_DECORATE => {
+                let (target, decoration) = (operand(0)?, operand(1)?);
+                match operands.get(2) {
+                    Some(&value) => {
+                        self.decorations.insert((target, decoration), value);
+                    }
+                    None => self.flags.push((target, decoration)),
+                }
+            }
+            OP_MEMBER_DECORATE => {
+                if let Some(&value) = operands.get(3) {
+                    self.member_decorations
+                        .insert((operand(0)?, operand(1)?, operand(2)?), value);
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn ty(&self, id: u32) -> Result<&Type, ReflectionError> {
+        self.types.get(&id).ok_or(ReflectionError::Malformed)
+    }
+
+    fn has_decoration(&self, id: u32, decoration: u32) -> bool {
+        self.decorations.contains_key(&(id, decoration)) || self.flags.contains(&(id, decoration))
+    }
+
+    // gl_Position and friends, either decorated themselves or as members of gl_PerVertex
+    fn is_built_in(&self, variable: u32, ty: u32) -> bool {
+        if self.has_decoration(variable, DECORATION_BUILT_IN) {
+            return true;
+        }
+        let mut ty = ty;
+        while let Ok(Type::Array { element, .. }) = self.ty(ty) {
+            ty = *element;
+        }
+        match self.ty(ty) {
+            Ok(Type::Struct { .. }) => self
+                .member_decorations
+                .keys()
+                .any(|&(s, _, decoration)| s == ty && decoration == DECORATION_BUILT_IN),
+            _ => false,
+        }
+    }
+
+    fn pointee(&self, pointer: u32) -> Result<u32, ReflectionError> {
+        match self.ty(pointer)? {
+            Type::Pointer { pointee } => Ok(*pointee),
+            _ => Err(ReflectionError::Malformed),
+        }
+    }
+
+    // the format of each location a variable of type `ty` takes up, in order
+    fn formats(&self, ty: u32) -> Option<Vec<Format>> {
+        match self.ty(ty).ok()? {
+            Type::Scalar { .. } => Some(vec![self.vector_format(ty, 1)?]),
+            Type::Vector { component, count } => {
+                let format = self.vector_format(*component, *count)?;
+                // 64-bit vectors of three or four components take two locations
+                let locations = if self.width(*component)? == 64 && *count > 2 {
+                    2
+                } else {
+                    1
+                };
+                Some(vec![format; locations])
+            }
+            Type::Matrix { column, columns } => {
+                let column = self.formats(*column)?;
+                Some(column.repeat(*columns as usize))
+            }
+            Type::Array { element, length } => {
+                Some(self.formats(*element)?.repeat(*length as usize))
+            }
+            _ => None,
+        }
+    }
+
+    fn width(&self, scalar: u32) -> Option<u32> {
+        match self.ty(scalar).ok()? {
+            Type::Scalar { width, .. } => Some(*width),
+            _ => None,
+        }
+    }
+
+    // the formats vulkano-shaders uses for interface variables
+    fn vector_format(&self, scalar: u32, count: u32) -> Option<Format> {
+        let (float, signed, width) = match self.ty(scalar).ok()? {
+            Type::Scalar {
+                float,
+                signed,
+                width,
+            } => (*float, *signed, *width),
+            _ => return None,
+        };
+        let formats = match (float, signed, width) {
+            (true, _, 32) => [
+                Format::R32Sfloat,
+                Format::R32G32Sfloat,
+                Format::R32G32B32Sfloat,
+                Format::R32G32B32A32Sfloat,
+            ],
+            (true, _, 64) => [
+                Format::R64Sfloat,
+                Format::R64G64Sfloat,
+                Format::R64G64B64Sfloat,
+                Format::R64G64B64A64Sfloat,
+            ],
+            (false, true, 32) => [
+                Format::R32Sint,
+                Format::R32G32Sint,
+                Format::R32G32B32Sint,
+                Format::R32G32B32A32Sint,
+            ],
+            (false, false, 32) => [
+                Format::R32Uint,
+                Format::R32G32Uint,
+                Format::R32G32B32Uint,
+                Format::R32G32B32A32Uint,
+            ],
+            _ => return None,
+        };
+        formats.get(count.checked_sub(1)? as usize).cloned()
+    }
+
+    // the bytes a block member of type `ty` takes up, given its own decorations
+    fn size(&self, ty: u32, matrix_stride: Option<u32>) -> Option<u32> {
+        match self.ty(ty).ok()? {
+            Type::Scalar { width, .. } => Some(width / 8),
+            Type::Vector { component, count } => Some(self.size(*component, None)? * count),
+            Type::Matrix { column, columns } => match matrix_stride {
+                Some(stride) => Some(stride * columns),
+                None => Some(self.size(*column, None)? * columns),
+            },
+            Type::Array { length, .. } => {
+                let stride = *self.decorations.get(&(ty, DECORATION_ARRAY_STRIDE))?;
+                Some(stride * length)
+            }
+            Type::RuntimeArray { .. } => Some(0),
+            Type::Struct { members } => members
+                .iter()
+                .enumerate()
+                .map(|(i, &member)| {
+                    let i = i as u32;
+                    let offset = *self.member_decorations.get(&(ty, i, DECORATION_OFFSET))?;
+                    let stride = self
+                        .member_decorations
+                        .get(&(ty, i, DECORATION_MATRIX_STRIDE))
+                        .cloned();
+                    Some(offset + self.size(member, stride)?)
+                })
+                .try_fold(0, |size, end: Option<u32>| end.map(|end| size.max(end))),
+            _ => None,
+        }
+    }
+
+    fn descriptor(&self, variable: u32, ty: u32, storage_class: u32) -> Option<DescriptorBinding> {
+        let set = *self
+            .decorations
+            .get(&(variable, DECORATION_DESCRIPTOR_SET))?;
+        let binding = *self.decorations.get(&(variable, DECORATION_BINDING))?;
+        let (ty, count) = match self.ty(ty).ok()? {
+            Type::Array { element, length } => (*element, *length),
+            Type::RuntimeArray { element } => (*element, 0),
+            _ => (ty, 1),
+        };
+        let image_kind = |dim: u32, arrayed: bool| {
+            let dimensions = match dim {
+                DIM_1D => ImageDimensions::OneDimensional,
+                DIM_2D => ImageDimensions::TwoDimensional,
+                DIM_3D => ImageDimensions::ThreeDimensional,
+                DIM_CUBE => ImageDimensions::Cube,
+                _ => return None,
+            };
+            Some(ImageKind {
+                dimensions,
+                arrayed,
+            })
+        };
+        let (kind, size) = match (self.ty(ty).ok()?, storage_class) {
+            (Type::Sampler, _) => (DescriptorKind::Sampler, None),
+            (Type::SampledImage { image }, _) => match self.ty(*image).ok()? {
+                Type::Image { dim, arrayed, .. } => (
+                    DescriptorKind::CombinedImageSampler(image_kind(*dim, *arrayed)?),
+                    None,
+                ),
+                _ => return None,
+            },
+            (
+                Type::Image {
+                    dim: DIM_BUFFER,
+                    sampled,
+                    ..
+                },
+                _,
+            ) => match sampled {
+                2 => (DescriptorKind::StorageTexelBuffer, None),
+                _ => (DescriptorKind::UniformTexelBuffer, None),
+            },
+            (
+                Type::Image {
+                    dim: DIM_SUBPASS_DATA,
+                    ..
+                },
+                _,
+            ) => (DescriptorKind::InputAttachment, None),
+            (
+                Type::Image {
+                    dim,
+                    arrayed,
+                    sampled,
+                },
+                _,
+            ) => {
+                let image = image_kind(*dim, *arrayed)?;
+                match sampled {
+                    2 => (DescriptorKind::StorageImage(image), None),
+                    _ => (DescriptorKind::SampledImage(image), None),
+                }
+            }
+            (Type::Struct { .. }, STORAGE_CLASS_STORAGE_BUFFER) => {
+                (DescriptorKind::StorageBuffer, Some(self.size(ty, None)?))
+            }
+            (Type::Struct { .. }, STORAGE_CLASS_UNIFORM)
+                if self.has_decoration(ty, DECORATION_BUFFER_BLOCK) =>
+            {
+                (DescriptorKind::StorageBuffer, Some(self.size(ty, None)?))
+            }
+            (Type::Struct { .. }, STORAGE_CLASS_UNIFORM)
+                if self.has_decoration(ty, DECORATION_BLOCK) =>
+            {
+                (DescriptorKind::UniformBuffer, Some(self.size(ty, None)?))
+            }
+            _ => return None,
+        };
+        Some(DescriptorBinding {
+            set,
+            binding,
+            kind,
+            count,
+            size,
+        })
+    }
+}
+
+// a nul terminated UTF-8 literal and the number of words it takes up
+fn string(words: &[u32]) -> Result<(String, usize), ReflectionError> {
+    let mut bytes = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        for &byte in &word.to_le_bytes() {
+            if byte == 0 {
+                let string = String::from_utf8(bytes).map_err(|_| ReflectionError::Malformed)?;
+                return Ok((string, i + 1));
+            }
+            bytes.push(byte);
+        }
+    }
+    Err(ReflectionError::Malformed)
+}
+
+// reads the interface of the entry point called `entry_point`; `spirv` must be in the host's
+// byte order, as `ShaderCompiler` returns it
+pub fn reflect(spirv: &[u32], entry_point: &str) -> Result<ShaderInterface, ReflectionError> {
+    let module = Module::parse(spirv)?;
+    let interface_ids = module
+        .entry_points
+        .iter()
+        .find(|(name, _)| name == entry_point)
+        .map(|(_, ids)| ids)
+        .ok_or_else(|| ReflectionError::MissingEntryPoint(entry_point.to_string()))?;
+
+    let mut interface = ShaderInterface::default();
+    for &id in interface_ids {
+        let (pointer, storage_class) = match module.variables.get(&id) {
+            Some(&variable) => variable,
+            None => continue,
+        };
+        let ty = module.pointee(pointer)?;
+        let variables = match storage_class {
+            STORAGE_CLASS_INPUT => &mut interface.inputs,
+            STORAGE_CLASS_OUTPUT => &mut interface.outputs,
+            _ => continue,
+        };
+        if module.is_built_in(id, ty) {
+            continue;
+        }
+        let location = module.decorations.get(&(id, DECORATION_LOCATION));
+        let (location, formats) = location
+            .and_then(|&location| Some((location, module.formats(ty)?)))
+            .ok_or(ReflectionError::UnsupportedInterfaceVariable { id })?;
+        variables.extend(
+            formats
+                .into_iter()
+                .zip(location..)
+                .map(|(format, location)| InterfaceVariable { location, format }),
+        );
+    }
+    interface.inputs.sort_by_key(|v| v.location);
+    interface.outputs.sort_by_key(|v| v.location);
+
+    let mut ids: Vec<_> = module.variables.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+        let (pointer, storage_class) = module.variables[&id];
+        let ty = module.pointee(pointer)?;
+        match storage_class {
+            STORAGE_CLASS_UNIFORM_CONSTANT
+            | STORAGE_CLASS_UNIFORM
+            | STORAGE_CLASS_STORAGE_BUFFER => {
+                let descriptor = module
+                    .descriptor(id, ty, storage_class)
+                    .ok_or(ReflectionError::UnsupportedDescriptor { id })?;
+                interface.descriptors.push(descriptor);
+            }
+            STORAGE_CLASS_PUSH_CONSTANT => {
+                let size = module
+                    .size(ty, None)
+                    .ok_or(ReflectionError::UnsupportedDescriptor { id })?;
+                interface.push_constant_size = interface.push_constant_size.max(size);
+            }
+            _ => (),
+        }
+    }
+    interface.descriptors.sort_by_key(|d| (d.set, d.binding));
+    Ok(interface)
+}
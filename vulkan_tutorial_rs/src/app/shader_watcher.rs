@@ -0,0 +1,48 @@
+use crate::app::shader::canonical;
+
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+// reports shader files that changed on disk; events are debounced by `delay` so that editors
+// writing a file in several steps trigger one reload
+pub struct ShaderWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<DebouncedEvent>,
+}
+
+impl ShaderWatcher {
+    pub fn new(directory: &Path, delay: Duration) -> notify::Result<ShaderWatcher> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::watcher(sender, delay)?;
+        watcher.watch(directory, RecursiveMode::Recursive)?;
+        Ok(ShaderWatcher {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    // never blocks; each path is reported once however many events it had
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.events.try_iter() {
+            let path = match event {
+                DebouncedEvent::Create(path) | DebouncedEvent::Write(path) => path,
+                // editors often save by writing a temporary file and renaming it over the original
+                DebouncedEvent::Rename(_, path) => path,
+                DebouncedEvent::Error(e, path) => {
+                    println!("WARNING: Shader watcher error: {} ({:?})", e, path);
+                    continue;
+                }
+                _ => continue,
+            };
+            let path = canonical(&path);
+            if !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+        changed
+    }
+}
@@ -1,11 +1,14 @@
-use crate::app::LogicalDevice;
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::{LogicalDevice, PresentPolicy};
 use std::sync::Arc;
+use vulkano::device::Device;
 use vulkano::format::Format;
-use vulkano::image::{ImageUsage, SwapchainImage};
+use vulkano::image::{AttachmentImage, ImageUsage, SwapchainImage};
 use vulkano::instance::PhysicalDevice;
 use vulkano::swapchain::{
-    Capabilities, ColorSpace, CompositeAlpha, FullscreenExclusive, PresentMode,
-    SupportedPresentModes, Surface, Swapchain,
+    Capabilities, CapabilitiesError, ColorSpace, CompositeAlpha, FullscreenExclusive, PresentMode,
+    SupportedCompositeAlpha, SupportedPresentModes, Surface, Swapchain, SwapchainCreationError,
 };
 use vulkano::sync::SharingMode;
 use winit::window::Window;
@@ -14,6 +17,14 @@ pub struct SwapchainInfo {
     _swapchain: Arc<Swapchain<Window>>,
     _images: Vec<Arc<SwapchainImage<Window>>>,
     _format: Format,
+    _composite_alpha: CompositeAlpha,
+    supports_capture: bool,
+    depth_format: Format,
+    samples: u32,
+    // the attachments are recreated with the swapchain so they always match its dimensions;
+    // the multisampled colour image only exists with MSAA and resolves into the swapchain image
+    color_image: Option<Arc<AttachmentImage>>,
+    depth_image: Arc<AttachmentImage>,
 }
 
 impl SwapchainInfo {
@@ -21,19 +32,26 @@ impl SwapchainInfo {
         surface: &Arc<Surface<Window>>,
         physical_device: &PhysicalDevice,
         logical_device: &LogicalDevice,
-    ) -> SwapchainInfo {
-        let surface_capabilities = surface
-            .capabilities(*physical_device)
-            .expect("failed to get surface capabilities");
+        window_dimensions: [u32; 2],
+        transparent: bool,
+        present_policy: PresentPolicy,
+    ) -> Result<SwapchainInfo, SwapchainCreationError> {
+        let surface_capabilities = capabilities(surface, *physical_device)?;
         let (format, color_space) = *choose_format(&surface_capabilities.supported_formats);
-        let dimensions = choose_dimensions(&surface_capabilities);
+        let dimensions = choose_dimensions(&surface_capabilities, window_dimensions);
         let num_images = choose_image_count(&surface_capabilities);
         let layers = 1u32;
-        let usage = ImageUsage::color_attachment();
+        // screenshots copy out of the swapchain images, which not every surface allows
+        let supports_capture = surface_capabilities.supported_usage_flags.transfer_source;
+        let usage = ImageUsage {
+            transfer_source: supports_capture,
+            ..ImageUsage::color_attachment()
+        };
         let sharing = choose_sharing_mode(logical_device);
         let transform = surface_capabilities.current_transform;
-        let alpha = CompositeAlpha::Opaque;
-        let mode = choose_present_mode(&surface_capabilities.present_modes);
+        let alpha =
+            choose_composite_alpha(&surface_capabilities.supported_composite_alpha, transparent);
+        let mode = choose_present_mode(&surface_capabilities.present_modes, present_policy);
         let fullscreen_exclusive = FullscreenExclusive::Default;
         let clipped = true;
         let (swapchain, images) = vulkano::swapchain::Swapchain::new(
@@ -51,13 +69,35 @@ impl SwapchainInfo {
             fullscreen_exclusive,
             clipped,
             color_space,
-        )
-        .expect("failed to create swapchain");
-        SwapchainInfo {
+        )?;
+        // devices without one aren't suitable, see `PhysicalDeviceInfo::is_valid`
+        let depth_format =
+            supported_depth_format(*physical_device).expect("no supported depth format");
+        let limits = physical_device.limits();
+        let samples = choose_sample_count(
+            config::MSAA_SAMPLES,
+            limits.framebuffer_color_sample_counts() & limits.framebuffer_depth_sample_counts(),
+        );
+        let (color_image, depth_image) = create_attachments(
+            logical_device.device(),
+            swapchain.dimensions(),
+            format,
+            depth_format,
+            samples,
+        );
+        let swapchain_info = SwapchainInfo {
             _swapchain: swapchain,
             _images: images,
             _format: format,
-        }
+            _composite_alpha: alpha,
+            supports_capture,
+            depth_format,
+            samples,
+            color_image,
+            depth_image,
+        };
+        swapchain_info.set_debug_names();
+        Ok(swapchain_info)
     }
 
     pub fn _swapchain(&self) -> &Arc<Swapchain<Window>> {
@@ -72,12 +112,83 @@ impl SwapchainInfo {
         &self._format
     }
 
+    pub fn _composite_alpha(&self) -> CompositeAlpha {
+        self._composite_alpha
+    }
+
     pub fn _dimensions(&self) -> [u32; 2] {
         self._swapchain().dimensions()
     }
+
+    pub fn supports_capture(&self) -> bool {
+        self.supports_capture
+    }
+
+    pub fn depth_format(&self) -> Format {
+        self.depth_format
+    }
+
+    // 1 without MSAA
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub fn color_image(&self) -> Option<&Arc<AttachmentImage>> {
+        self.color_image.as_ref()
+    }
+
+    pub fn depth_image(&self) -> &Arc<AttachmentImage> {
+        &self.depth_image
+    }
+
+    // window_dimensions must be in physical pixels, e.g. from `Window::inner_size`
+    pub fn recreate(&mut self, window_dimensions: [u32; 2]) -> Result<(), SwapchainCreationError> {
+        if window_dimensions[0] == 0 || window_dimensions[1] == 0 {
+            // minimised; keep the old swapchain until the window has an area again
+            return Err(SwapchainCreationError::UnsupportedDimensions);
+        }
+        let surface_capabilities = capabilities(
+            self._swapchain.surface(),
+            self._swapchain.device().physical_device(),
+        )?;
+        let dimensions = choose_dimensions(&surface_capabilities, window_dimensions);
+        let (swapchain, images) = self._swapchain.recreate_with_dimensions(dimensions)?;
+        let (color_image, depth_image) = create_attachments(
+            swapchain.device(),
+            swapchain.dimensions(),
+            self._format,
+            self.depth_format,
+            self.samples,
+        );
+        self.color_image = color_image;
+        self.depth_image = depth_image;
+        self._swapchain = swapchain;
+        self._images = images;
+        self.set_debug_names();
+        Ok(())
+    }
+
+    fn set_debug_names(&self) {
+        let device = self._swapchain.device();
+        debug_utils::set_name(device, &*self._swapchain, "swapchain");
+        for (i, image) in self._images.iter().enumerate() {
+            debug_utils::set_image_name(device, &**image, &format!("swapchain image {}", i));
+        }
+    }
+}
+
+// a lost surface is reported the way swapchain creation reports it, so callers can recover
+fn capabilities(
+    surface: &Surface<Window>,
+    physical_device: PhysicalDevice,
+) -> Result<Capabilities, SwapchainCreationError> {
+    surface.capabilities(physical_device).map_err(|e| match e {
+        CapabilitiesError::SurfaceLost => SwapchainCreationError::SurfaceLost,
+        CapabilitiesError::OomError(e) => SwapchainCreationError::OomError(e),
+    })
 }
 
-fn choose_format(formats: &Vec<(Format, ColorSpace)>) -> &(Format, ColorSpace) {
+pub fn choose_format(formats: &Vec<(Format, ColorSpace)>) -> &(Format, ColorSpace) {
     let ideal_format = formats
         .iter()
         .filter(|&(f, c)| *f == Format::B8G8R8A8Srgb && *c == ColorSpace::SrgbNonLinear)
@@ -88,36 +199,54 @@ fn choose_format(formats: &Vec<(Format, ColorSpace)>) -> &(Format, ColorSpace) {
     &formats[0]
 }
 
-fn choose_present_mode(modes: &SupportedPresentModes) -> PresentMode {
-    if modes.mailbox {
-        PresentMode::Mailbox
+pub fn choose_present_mode(modes: &SupportedPresentModes, policy: PresentPolicy) -> PresentMode {
+    policy.choose(modes)
+}
+
+pub fn choose_composite_alpha(
+    supported: &SupportedCompositeAlpha,
+    transparent: bool,
+) -> CompositeAlpha {
+    if transparent {
+        if supported.pre_multiplied {
+            return CompositeAlpha::PreMultiplied;
+        }
+        if supported.post_multiplied {
+            return CompositeAlpha::PostMultiplied;
+        }
+        if supported.inherit {
+            return CompositeAlpha::Inherit;
+        }
+        println!("WARNING: Transparent window requested, but not supported by the surface!");
+    }
+    if supported.opaque {
+        CompositeAlpha::Opaque
     } else {
-        assert!(
-            modes.fifo,
-            "Fifo support is required by the Vulkan standard"
-        );
-        PresentMode::Fifo
+        supported
+            .iter()
+            .next()
+            .expect("surface supports no composite alpha modes")
     }
 }
 
-fn choose_dimensions(c: &Capabilities) -> [u32; 2] {
+pub fn choose_dimensions(c: &Capabilities, window_dimensions: [u32; 2]) -> [u32; 2] {
     if c.current_extent.is_some() {
         return c.current_extent.unwrap();
     }
     let width = num::clamp(
-        crate::app::config::DEFAULT_WIDTH,
+        window_dimensions[0],
         c.min_image_extent[0],
         c.max_image_extent[0],
     );
     let height = num::clamp(
-        crate::app::config::DEFAULT_HEIGHT,
+        window_dimensions[1],
         c.min_image_extent[1],
         c.max_image_extent[1],
     );
     [width, height]
 }
 
-fn choose_image_count(c: &Capabilities) -> u32 {
+pub fn choose_image_count(c: &Capabilities) -> u32 {
     // ideally we get at least one more image than the minimum so we aren't waiting on the driver
     let ideal = c.min_image_count + 1;
     match c.max_image_count {
@@ -126,7 +255,41 @@ fn choose_image_count(c: &Capabilities) -> u32 {
     }
 }
 
-fn choose_sharing_mode(ld: &LogicalDevice) -> SharingMode {
+// the first format in `preferred` that `is_supported` accepts as a depth attachment
+pub fn choose_depth_format<F>(preferred: &[Format], is_supported: F) -> Option<Format>
+where
+    F: Fn(Format) -> bool,
+{
+    preferred
+        .iter()
+        .cloned()
+        .find(|&format| is_supported(format))
+}
+
+// the first of `config::DEPTH_FORMATS` the device can use as a depth attachment
+pub fn supported_depth_format(physical_device: PhysicalDevice) -> Option<Format> {
+    choose_depth_format(config::DEPTH_FORMATS, |format| {
+        format
+            .properties(physical_device)
+            .optimal_tiling_features
+            .depth_stencil_attachment
+    })
+}
+
+// the largest power of two up to `requested` that is set in `supported`, a mask of
+// VkSampleCountFlagBits such as the intersection of the device's colour and depth sample counts
+pub fn choose_sample_count(requested: u32, supported: u32) -> u32 {
+    let mut samples = requested.max(1).next_power_of_two();
+    if samples > requested.max(1) {
+        samples /= 2;
+    }
+    while samples > 1 && supported & samples == 0 {
+        samples /= 2;
+    }
+    samples
+}
+
+pub fn choose_sharing_mode(ld: &LogicalDevice) -> SharingMode {
     if ld.graphics_queue().family() == ld.present_queue().family() {
         ld.graphics_queue().into()
     } else {
@@ -135,3 +298,27 @@ fn choose_sharing_mode(ld: &LogicalDevice) -> SharingMode {
             .into()
     }
 }
+
+pub fn create_attachments(
+    device: &Arc<Device>,
+    dimensions: [u32; 2],
+    format: Format,
+    depth_format: Format,
+    samples: u32,
+) -> (Option<Arc<AttachmentImage>>, Arc<AttachmentImage>) {
+    if samples == 1 {
+        let depth_image = AttachmentImage::transient(device.clone(), dimensions, depth_format)
+            .expect("failed to create depth image");
+        debug_utils::set_image_name(device, &*depth_image, "depth attachment");
+        return (None, depth_image);
+    }
+    let color_image =
+        AttachmentImage::transient_multisampled(device.clone(), dimensions, samples, format)
+            .expect("failed to create multisampled colour image");
+    let depth_image =
+        AttachmentImage::transient_multisampled(device.clone(), dimensions, samples, depth_format)
+            .expect("failed to create depth image");
+    debug_utils::set_image_name(device, &*color_image, "multisampled colour attachment");
+    debug_utils::set_image_name(device, &*depth_image, "multisampled depth attachment");
+    (Some(color_image), depth_image)
+}
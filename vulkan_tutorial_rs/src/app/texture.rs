@@ -0,0 +1,196 @@
+pub mod ktx2;
+
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::Uploader;
+
+use self::ktx2::Ktx2Error;
+
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImageUsage, ImmutableImage};
+use vulkano::sampler::{Filter, MipmapMode, Sampler, SamplerAddressMode};
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum TextureError {
+    Io(io::Error),
+    Decode(image::ImageError),
+    Ktx2(Ktx2Error),
+    // a VkFormat that isn't known, or that the device can't copy into and sample from
+    UnsupportedFormat(u32),
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureError::Io(e) => write!(f, "failed to read texture: {}", e),
+            TextureError::Decode(e) => write!(f, "failed to decode texture: {}", e),
+            TextureError::Ktx2(e) => write!(f, "invalid KTX2 texture: {}", e),
+            TextureError::UnsupportedFormat(format) => {
+                write!(f, "unsupported texture format {}", format)
+            }
+        }
+    }
+}
+
+impl Error for TextureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextureError::Io(e) => Some(e),
+            TextureError::Decode(e) => Some(e),
+            TextureError::Ktx2(e) => Some(e),
+            TextureError::UnsupportedFormat(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for TextureError {
+    fn from(e: io::Error) -> TextureError {
+        TextureError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for TextureError {
+    fn from(e: image::ImageError) -> TextureError {
+        TextureError::Decode(e)
+    }
+}
+
+impl From<Ktx2Error> for TextureError {
+    fn from(e: Ktx2Error) -> TextureError {
+        TextureError::Ktx2(e)
+    }
+}
+
+// a sampled image; like meshes, textures are recorded into the uploader's batch and must not
+// be used before its `flush` future has been joined
+pub struct Texture {
+    image: Arc<ImmutableImage<Format>>,
+    sampler: Arc<Sampler>,
+}
+
+impl Texture {
+    // `.ktx2` files are uploaded as they are; anything else is decoded with `image` into sRGB
+    // RGBA8
+    pub fn load(uploader: &mut Uploader, path: &Path) -> Result<Texture, TextureError> {
+        let is_ktx2 = path
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("ktx2"));
+        let texture = if is_ktx2 {
+            Texture::from_ktx2(uploader, &fs::read(path)?)?
+        } else {
+            let image = image::open(path)?.into_rgba8();
+            let (width, height) = image.dimensions();
+            Texture::from_rgba8(uploader, &image, [width, height])
+        };
+        debug_utils::set_image_name(
+            uploader.device(),
+            &*texture.image,
+            &path.display().to_string(),
+        );
+        Ok(texture)
+    }
+
+    pub fn from_rgba8(uploader: &mut Uploader, pixels: &[u8], dimensions: [u32; 2]) -> Texture {
+        let dimensions = Dimensions::Dim2d {
+            width: dimensions[0],
+            height: dimensions[1],
+        };
+        let format = Format::R8G8B8A8Srgb;
+        let image = if supports_mipmap_generation(uploader.device(), format) {
+            uploader.upload_image_mipmapped(pixels, dimensions, format, ImageUsage::sampled())
+        } else {
+            uploader.upload_image(pixels, dimensions, format, ImageUsage::sampled())
+        };
+        Texture::new(uploader.device(), image)
+    }
+
+    pub fn from_ktx2(uploader: &mut Uploader, bytes: &[u8]) -> Result<Texture, TextureError> {
+        let ktx2 = ktx2::parse(bytes)?;
+        let format = Format::from_vulkan_num(ktx2.vk_format)
+            .filter(|&format| supports_upload(uploader.device(), format))
+            .ok_or(TextureError::UnsupportedFormat(ktx2.vk_format))?;
+        let block_size = format
+            .size()
+            .ok_or(TextureError::UnsupportedFormat(ktx2.vk_format))?;
+        ktx2.check_level_lengths(block_size, format.block_dimensions())?;
+        let dimensions = Dimensions::Dim2d {
+            width: ktx2.width,
+            height: ktx2.height,
+        };
+        let image = if ktx2.generate_mipmaps
+            && supports_mipmap_generation(uploader.device(), format)
+        {
+            uploader.upload_image_mipmapped(
+                ktx2.levels[0],
+                dimensions,
+                format,
+                ImageUsage::sampled(),
+            )
+        } else {
+            uploader.upload_image_levels(&ktx2.levels, dimensions, format, ImageUsage::sampled())
+        };
+        Ok(Texture::new(uploader.device(), image))
+    }
+
+    fn new(device: &Arc<Device>, image: Arc<ImmutableImage<Format>>) -> Texture {
+        let sampler = create_sampler(device, image.mipmap_levels());
+        debug_utils::set_image_name(device, &*image, "texture");
+        Texture { image, sampler }
+    }
+
+    pub fn image(&self) -> &Arc<ImmutableImage<Format>> {
+        &self.image
+    }
+
+    pub fn sampler(&self) -> &Arc<Sampler> {
+        &self.sampler
+    }
+}
+
+// textures are copied into from a buffer and then sampled
+fn supports_upload(device: &Device, format: Format) -> bool {
+    let features = format
+        .properties(device.physical_device())
+        .optimal_tiling_features;
+    features.sampled_image && features.transfer_dst
+}
+
+// mipmaps are generated with linear blits, which the format has to support
+fn supports_mipmap_generation(device: &Device, format: Format) -> bool {
+    let features = format
+        .properties(device.physical_device())
+        .optimal_tiling_features;
+    features.blit_src && features.blit_dst && features.sampled_image_filter_linear
+}
+
+// anisotropic filtering is only used if the `sampler_anisotropy` feature was enabled when the
+// logical device was created, which `LogicalDevice` does whenever the device supports it
+fn create_sampler(device: &Arc<Device>, mip_levels: u32) -> Arc<Sampler> {
+    let max_anisotropy = if device.enabled_features().sampler_anisotropy {
+        config::MAX_ANISOTROPY.min(device.physical_device().limits().max_sampler_anisotropy())
+    } else {
+        1.0
+    };
+    Sampler::new(
+        device.clone(),
+        Filter::Linear,
+        Filter::Linear,
+        MipmapMode::Linear,
+        SamplerAddressMode::Repeat,
+        SamplerAddressMode::Repeat,
+        SamplerAddressMode::Repeat,
+        0.0,
+        max_anisotropy,
+        0.0,
+        mip_levels as f32,
+    )
+    .expect("failed to create sampler")
+}
@@ -0,0 +1,173 @@
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n',
+];
+const HEADER_LENGTH: usize = 80;
+const LEVEL_INDEX_ENTRY_LENGTH: usize = 24;
+
+// a 2D, single layer, non-supercompressed KTX2 texture; other layouts aren't supported
+#[derive(Debug)]
+pub struct Ktx2<'a> {
+    // a VkFormat value
+    pub vk_format: u32,
+    pub width: u32,
+    pub height: u32,
+    // mip levels, largest first
+    pub levels: Vec<&'a [u8]>,
+    // the file has no mip levels beyond the first and asks for them to be generated
+    pub generate_mipmaps: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Ktx2Error {
+    InvalidIdentifier,
+    Truncated,
+    // VK_FORMAT_UNDEFINED, i.e. a Basis Universal texture that must be transcoded first
+    UndefinedFormat,
+    UnsupportedSupercompression(u32),
+    UnsupportedLayout,
+    // more mip levels than halving the largest dimension down to 1 gives
+    TooManyLevels {
+        levels: u32,
+        max: u32,
+    },
+    InvalidLevelLength {
+        level: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for Ktx2Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ktx2Error::InvalidIdentifier => write!(f, "not a KTX2 file"),
+            Ktx2Error::Truncated => write!(f, "KTX2 file is truncated"),
+            Ktx2Error::UndefinedFormat => write!(f, "KTX2 file needs transcoding"),
+            Ktx2Error::UnsupportedSupercompression(scheme) => {
+                write!(f, "unsupported KTX2 supercompression scheme {}", scheme)
+            }
+            Ktx2Error::UnsupportedLayout => {
+                write!(
+                    f,
+                    "only 2D KTX2 textures with one layer and face are supported"
+                )
+            }
+            Ktx2Error::TooManyLevels { levels, max } => {
+                write!(
+                    f,
+                    "KTX2 file has {} mip levels, at most {} fit",
+                    levels, max
+                )
+            }
+            Ktx2Error::InvalidLevelLength {
+                level,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "KTX2 mip level {} is {} bytes, expected {}",
+                level, actual, expected
+            ),
+        }
+    }
+}
+
+impl Error for Ktx2Error {}
+
+impl Ktx2<'_> {
+    // the header doesn't say how large a texel is, so the level lengths can only be checked once
+    // the format is known; `block_size` is in bytes and `block_dimensions` in texels, which are
+    // (1, 1) for uncompressed formats
+    pub fn check_level_lengths(
+        &self,
+        block_size: usize,
+        block_dimensions: (u32, u32),
+    ) -> Result<(), Ktx2Error> {
+        let (block_width, block_height) = block_dimensions;
+        for (level, bytes) in self.levels.iter().enumerate() {
+            let width = (self.width >> level).max(1);
+            let height = (self.height >> level).max(1);
+            let blocks_wide = width.div_ceil(block_width) as usize;
+            let blocks_high = height.div_ceil(block_height) as usize;
+            let expected = blocks_wide * blocks_high * block_size;
+            if bytes.len() != expected {
+                return Err(Ktx2Error::InvalidLevelLength {
+                    level,
+                    expected,
+                    actual: bytes.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Ktx2<'_>, Ktx2Error> {
+    if bytes.len() < IDENTIFIER.len() || bytes[..IDENTIFIER.len()] != IDENTIFIER {
+        return Err(Ktx2Error::InvalidIdentifier);
+    }
+    if bytes.len() < HEADER_LENGTH {
+        return Err(Ktx2Error::Truncated);
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+    let vk_format = u32_at(12);
+    let width = u32_at(20);
+    let height = u32_at(24);
+    let depth = u32_at(28);
+    let layer_count = u32_at(32);
+    let face_count = u32_at(36);
+    let level_count = u32_at(40);
+    let supercompression = u32_at(44);
+
+    if vk_format == 0 {
+        return Err(Ktx2Error::UndefinedFormat);
+    }
+    if supercompression != 0 {
+        return Err(Ktx2Error::UnsupportedSupercompression(supercompression));
+    }
+    if width == 0 || height == 0 || depth > 1 || layer_count > 1 || face_count != 1 {
+        return Err(Ktx2Error::UnsupportedLayout);
+    }
+    let max_levels = max_mip_levels(width, height);
+    if level_count > max_levels {
+        return Err(Ktx2Error::TooManyLevels {
+            levels: level_count,
+            max: max_levels,
+        });
+    }
+
+    let stored_levels = level_count.max(1) as usize;
+    if bytes.len() < HEADER_LENGTH + stored_levels * LEVEL_INDEX_ENTRY_LENGTH {
+        return Err(Ktx2Error::Truncated);
+    }
+    let levels = (0..stored_levels)
+        .map(|level| {
+            let entry = HEADER_LENGTH + level * LEVEL_INDEX_ENTRY_LENGTH;
+            let offset = u64_at(entry) as usize;
+            let length = u64_at(entry + 8) as usize;
+            offset
+                .checked_add(length)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or(Ktx2Error::Truncated)
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Ktx2 {
+        vk_format,
+        width,
+        height,
+        levels,
+        generate_mipmaps: level_count == 0,
+    })
+}
+
+// a full mip chain, down to 1x1
+pub fn max_mip_levels(width: u32, height: u32) -> u32 {
+    32 - width.max(height).leading_zeros()
+}
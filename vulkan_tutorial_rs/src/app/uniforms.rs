@@ -0,0 +1,72 @@
+use cgmath::{Matrix4, SquareMatrix};
+
+use vulkano::buffer::cpu_pool::CpuBufferPoolSubbuffer;
+use vulkano::buffer::CpuBufferPool;
+use vulkano::device::Device;
+use vulkano::memory::pool::StdMemoryPool;
+
+use std::sync::Arc;
+
+// matches the std140 `FrameData` uniform block in shaders/mesh.vert
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct FrameUniforms {
+    pub model: [[f32; 4]; 4],
+    pub view: [[f32; 4]; 4],
+    pub projection: [[f32; 4]; 4],
+    // seconds since the application started
+    pub time: f32,
+    _padding: [f32; 3],
+}
+
+impl FrameUniforms {
+    pub fn new(
+        model: Matrix4<f32>,
+        view: Matrix4<f32>,
+        projection: Matrix4<f32>,
+        time: f32,
+    ) -> FrameUniforms {
+        FrameUniforms {
+            model: model.into(),
+            view: view.into(),
+            projection: projection.into(),
+            time,
+            _padding: [0.0; 3],
+        }
+    }
+}
+
+impl Default for FrameUniforms {
+    fn default() -> FrameUniforms {
+        let identity = Matrix4::identity();
+        FrameUniforms::new(identity, identity, identity, 0.0)
+    }
+}
+
+pub type UniformSubbuffer<T> = Arc<CpuBufferPoolSubbuffer<T, Arc<StdMemoryPool>>>;
+
+// hands out a fresh uniform buffer per frame; a buffer returns to the pool once the GPU is
+// done with the frame that used it, so there is one copy per frame in flight without having
+// to track frames explicitly
+pub struct UniformBuffer<T> {
+    pool: CpuBufferPool<T>,
+}
+
+impl<T> UniformBuffer<T>
+where
+    T: Send + Sync + 'static,
+{
+    pub fn new(device: Arc<Device>) -> UniformBuffer<T> {
+        UniformBuffer {
+            pool: CpuBufferPool::uniform_buffer(device),
+        }
+    }
+
+    pub fn next(&self, data: T) -> UniformSubbuffer<T> {
+        Arc::new(
+            self.pool
+                .next(data)
+                .expect("failed to allocate uniform buffer"),
+        )
+    }
+}
@@ -0,0 +1,642 @@
+use crate::app::config;
+use crate::app::debug_utils;
+use crate::app::raw_command_buffer::RawCommandBuffer;
+
+use vulkano::buffer::{
+    BufferAccess, BufferSlice, BufferUsage, CpuAccessibleBuffer, ImmutableBuffer,
+};
+use vulkano::command_buffer::pool::standard::StandardCommandPoolBuilder;
+use vulkano::command_buffer::sys::{
+    UnsafeCommandBufferBuilder, UnsafeCommandBufferBuilderImageAspect,
+    UnsafeCommandBufferBuilderImageBlit, UnsafeCommandBufferBuilderPipelineBarrier,
+};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBuffer};
+use vulkano::device::{Device, Queue};
+use vulkano::format::Format;
+use vulkano::image::immutable::ImmutableImageInitialization;
+use vulkano::image::{Dimensions, ImageLayout, ImageUsage, ImmutableImage, MipmapsCount};
+use vulkano::instance::QueueFamily;
+use vulkano::sampler::Filter;
+use vulkano::sync::{self, AccessFlagBits, GpuFuture, PipelineStages};
+
+use std::any::Any;
+use std::iter;
+use std::mem;
+use std::sync::Arc;
+
+type StagingBuffer = Arc<CpuAccessibleBuffer<[u8]>>;
+
+/// Types that are nothing but their bytes, which `Uploader::upload_buffer` copies into a buffer
+/// byte by byte.
+///
+/// # Safety
+///
+/// Implementors must have no padding, references or pointers, and every bit pattern must be a
+/// valid value.
+pub unsafe trait Pod: Copy + Send + Sync + 'static {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for f32 {}
+
+// records CPU→GPU copies into device-local buffers and images; copies are batched into one
+// command buffer and only submitted by `flush`, whose future must be joined before the
+// uploaded resources are first used. Each `LogicalDevice` has one, so the staging buffers are
+// only allocated once
+//
+// resources uploaded on a dedicated transfer queue are owned by its family, and `flush` moves
+// them to the graphics family with a release barrier on the transfer queue and an acquire
+// barrier on the graphics queue
+pub struct Uploader {
+    device: Arc<Device>,
+    transfer_queue: Arc<Queue>,
+    graphics_queue: Arc<Queue>,
+    // a ring of staging buffers; a slot is reused once the GPU has released it
+    staging_buffers: Vec<StagingBuffer>,
+    // slots recorded into the pending batch, which the GPU doesn't know about yet
+    reserved: Vec<bool>,
+    next_staging_buffer: usize,
+    pending: Option<AutoCommandBufferBuilder>,
+    // uploads that need a graphics queue, e.g. for blits
+    pending_graphics: Option<AutoCommandBufferBuilder>,
+    // written by the pending batch and handed over to the graphics family by `flush`; empty
+    // when the transfer queue is the graphics queue
+    transferred_buffers: Vec<Arc<dyn BufferAccess + Send + Sync>>,
+    transferred_images: Vec<Arc<ImmutableImage<Format>>>,
+    // images whose first level the pending graphics batch uploads; `flush` fills in the rest of
+    // their mip chains after it
+    mipmapped_images: Vec<Arc<ImmutableImage<Format>>>,
+}
+
+impl Uploader {
+    pub fn new(
+        device: Arc<Device>,
+        transfer_queue: Arc<Queue>,
+        graphics_queue: Arc<Queue>,
+    ) -> Uploader {
+        let staging_buffers = (0..config::STAGING_BUFFER_COUNT)
+            .map(|i| {
+                let buffer = unsafe {
+                    CpuAccessibleBuffer::uninitialized_array(
+                        device.clone(),
+                        config::STAGING_BUFFER_SIZE,
+                        BufferUsage::transfer_source(),
+                        false,
+                    )
+                    .expect("failed to create staging buffer")
+                };
+                debug_utils::set_buffer_name(&*device, &*buffer, &format!("staging buffer {}", i));
+                buffer
+            })
+            .collect();
+        Uploader {
+            device,
+            transfer_queue,
+            graphics_queue,
+            staging_buffers,
+            reserved: vec![false; config::STAGING_BUFFER_COUNT],
+            next_staging_buffer: 0,
+            pending: None,
+            pending_graphics: None,
+            transferred_buffers: Vec::new(),
+            transferred_images: Vec::new(),
+            mipmapped_images: Vec::new(),
+        }
+    }
+
+    pub fn transfer_queue(&self) -> &Arc<Queue> {
+        &self.transfer_queue
+    }
+
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    pub fn has_pending_uploads(&self) -> bool {
+        self.pending.is_some() || self.pending_graphics.is_some()
+    }
+
+    pub fn upload_buffer<T: Pod>(
+        &mut self,
+        data: &[T],
+        usage: BufferUsage,
+    ) -> Arc<ImmutableBuffer<[T]>> {
+        assert!(!data.is_empty(), "can't upload an empty buffer");
+        // `T: Pod` makes every byte of `data` initialised
+        let bytes = unsafe {
+            std::slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data))
+        };
+        let staging = self.stage(bytes);
+        let (buffer, initialization) = unsafe {
+            ImmutableBuffer::<[T]>::raw(
+                self.device.clone(),
+                bytes.len(),
+                BufferUsage {
+                    transfer_destination: true,
+                    ..usage
+                },
+                iter::once(self.transfer_queue.family()),
+            )
+        }
+        .expect("failed to allocate device-local buffer");
+        let source = unsafe { staging.reinterpret::<[T]>() };
+        self.builder()
+            .copy_buffer(source, initialization)
+            .expect("failed to record buffer upload");
+        if self.needs_ownership_transfer() {
+            self.transferred_buffers.push(buffer.clone());
+        }
+        buffer
+    }
+
+    // `data` holds the tightly packed texels of `format`; the image has a single mip level
+    pub fn upload_image(
+        &mut self,
+        data: &[u8],
+        dimensions: Dimensions,
+        format: Format,
+        usage: ImageUsage,
+    ) -> Arc<ImmutableImage<Format>> {
+        self.upload_image_levels(&[data], dimensions, format, usage)
+    }
+
+    // `levels` holds the tightly packed texels of each mip level, largest first
+    pub fn upload_image_levels(
+        &mut self,
+        levels: &[&[u8]],
+        dimensions: Dimensions,
+        format: Format,
+        usage: ImageUsage,
+    ) -> Arc<ImmutableImage<Format>> {
+        let mipmaps = MipmapsCount::Specific(levels.len() as u32);
+        let family = self.transfer_queue.family();
+        let (image, initialization) = self.create_image(dimensions, format, mipmaps, usage, family);
+        for (level, data) in levels.iter().enumerate() {
+            assert!(!data.is_empty(), "can't upload an empty mip level");
+            let staging = self.stage(data);
+            self.builder()
+                .copy_buffer_to_image_dimensions(
+                    staging,
+                    initialization.clone(),
+                    [0, 0, 0],
+                    mip_dimensions(dimensions, level as u32),
+                    0,
+                    1,
+                    level as u32,
+                )
+                .expect("failed to record image upload");
+        }
+        if self.needs_ownership_transfer() {
+            self.transferred_images.push(image.clone());
+        }
+        image
+    }
+
+    // uploads the first mip level and fills in a full mip chain by blitting each level from the
+    // previous one; blits need a graphics queue, so these uploads are recorded there instead of
+    // on the transfer queue. `format` must support linear filtering of optimally tiled images
+    pub fn upload_image_mipmapped(
+        &mut self,
+        data: &[u8],
+        dimensions: Dimensions,
+        format: Format,
+        usage: ImageUsage,
+    ) -> Arc<ImmutableImage<Format>> {
+        assert!(!data.is_empty(), "can't upload an empty image");
+        let staging = self.stage(data);
+        let usage = ImageUsage {
+            transfer_source: true,
+            ..usage
+        };
+        let family = self.graphics_queue.family();
+        let (image, initialization) =
+            self.create_image(dimensions, format, MipmapsCount::Log2, usage, family);
+        self.graphics_builder()
+            .copy_buffer_to_image_dimensions(
+                staging,
+                initialization,
+                [0, 0, 0],
+                mip_dimensions(dimensions, 0),
+                0,
+                1,
+                0,
+            )
+            .expect("failed to record image upload");
+        self.mipmapped_images.push(image.clone());
+        image
+    }
+
+    // submits the pending batches; the future is signalled once every upload recorded since
+    // the last flush has completed
+    pub fn flush(&mut self) -> Box<dyn GpuFuture> {
+        for reserved in self.reserved.iter_mut() {
+            *reserved = false;
+        }
+        let mut future = Box::new(sync::now(self.device.clone())) as Box<dyn GpuFuture>;
+        if let Some(builder) = self.pending.take() {
+            let copied = builder
+                .build()
+                .unwrap()
+                .execute(self.transfer_queue.clone())
+                .expect("failed to submit uploads");
+            let submitted: Box<dyn GpuFuture> = match self.ownership_transfers() {
+                Some((release, acquire)) => Box::new(
+                    copied
+                        .then_execute(self.transfer_queue.clone(), release)
+                        .expect("failed to submit queue family release")
+                        .then_signal_semaphore_and_flush()
+                        .expect("failed to flush uploads")
+                        .then_execute(self.graphics_queue.clone(), acquire)
+                        .expect("failed to submit queue family acquire")
+                        .then_signal_semaphore_and_flush()
+                        .expect("failed to flush queue family acquire"),
+                ),
+                None => Box::new(
+                    copied
+                        .then_signal_semaphore_and_flush()
+                        .expect("failed to flush uploads"),
+                ),
+            };
+            future = Box::new(future.join(submitted));
+        }
+        if let Some(builder) = self.pending_graphics.take() {
+            let copied = builder
+                .build()
+                .unwrap()
+                .execute(self.graphics_queue.clone())
+                .expect("failed to submit uploads");
+            let images = mem::take(&mut self.mipmapped_images);
+            let submitted: Box<dyn GpuFuture> = if images.is_empty() {
+                Box::new(
+                    copied
+                        .then_signal_semaphore_and_flush()
+                        .expect("failed to flush uploads"),
+                )
+            } else {
+                Box::new(
+                    copied
+                        .then_execute(self.graphics_queue.clone(), self.mipmap_blits(images))
+                        .expect("failed to submit mipmap generation")
+                        .then_signal_semaphore_and_flush()
+                        .expect("failed to flush uploads"),
+                )
+            };
+            future = Box::new(future.join(submitted));
+        }
+        future
+    }
+
+    // fills in each image's mip chain by blitting every level from the one before. vulkano
+    // tracks layouts per image rather than per mip level, so a blit between two levels of the
+    // same image can't go through its command buffer builder
+    fn mipmap_blits(&self, images: Vec<Arc<ImmutableImage<Format>>>) -> RawCommandBuffer {
+        let resources: Vec<Arc<dyn Any + Send + Sync>> = vec![Arc::new(images.clone())];
+        // the first levels were copied by the command buffer submitted to the graphics queue
+        // just before, which leaves every level in the images' final layout; the barriers
+        // around each blit order it after the previous one, and return every level to that
+        // layout, which is what vulkano expects the images to be in
+        unsafe {
+            RawCommandBuffer::new(
+                &self.device,
+                self.graphics_queue.family(),
+                resources,
+                |builder| {
+                    for image in &images {
+                        record_mipmap_blits(builder, image);
+                    }
+                },
+            )
+        }
+    }
+
+    fn needs_ownership_transfer(&self) -> bool {
+        self.transfer_queue.family() != self.graphics_queue.family()
+    }
+
+    // the release and acquire halves of handing the pending batch's resources from the
+    // transfer family to the graphics family
+    fn ownership_transfers(&mut self) -> Option<(RawCommandBuffer, RawCommandBuffer)> {
+        let buffers = mem::take(&mut self.transferred_buffers);
+        let images = mem::take(&mut self.transferred_images);
+        if buffers.is_empty() && images.is_empty() {
+            return None;
+        }
+        let release = self.ownership_barrier(
+            &self.transfer_queue,
+            &buffers,
+            &images,
+            (
+                PipelineStages {
+                    transfer: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    transfer_write: true,
+                    ..AccessFlagBits::none()
+                },
+            ),
+            (
+                PipelineStages {
+                    bottom_of_pipe: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits::none(),
+            ),
+        );
+        let acquire = self.ownership_barrier(
+            &self.graphics_queue,
+            &buffers,
+            &images,
+            (
+                PipelineStages {
+                    top_of_pipe: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits::none(),
+            ),
+            (
+                PipelineStages {
+                    all_commands: true,
+                    ..PipelineStages::none()
+                },
+                AccessFlagBits {
+                    memory_read: true,
+                    ..AccessFlagBits::none()
+                },
+            ),
+        );
+        Some((release, acquire))
+    }
+
+    fn ownership_barrier(
+        &self,
+        queue: &Queue,
+        buffers: &[Arc<dyn BufferAccess + Send + Sync>],
+        images: &[Arc<ImmutableImage<Format>>],
+        source: (PipelineStages, AccessFlagBits),
+        destination: (PipelineStages, AccessFlagBits),
+    ) -> RawCommandBuffer {
+        let families = Some((
+            self.transfer_queue.family().id(),
+            self.graphics_queue.family().id(),
+        ));
+        let resources: Vec<Arc<dyn Any + Send + Sync>> =
+            vec![Arc::new(buffers.to_vec()), Arc::new(images.to_vec())];
+        // the barriers only hand over resources whose copies were submitted to the transfer
+        // queue just before the release, and `flush` orders the acquire after it with a
+        // semaphore; the images are already in their final layout
+        unsafe {
+            RawCommandBuffer::new(&self.device, queue.family(), resources, |builder| {
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                for buffer in buffers {
+                    barrier.add_buffer_memory_barrier(
+                        &**buffer,
+                        source.0,
+                        source.1,
+                        destination.0,
+                        destination.1,
+                        false,
+                        families,
+                        0,
+                        buffer.size(),
+                    );
+                }
+                for image in images {
+                    barrier.add_image_memory_barrier(
+                        &**image,
+                        0..image.mipmap_levels(),
+                        0..image.dimensions().array_layers(),
+                        source.0,
+                        source.1,
+                        destination.0,
+                        destination.1,
+                        false,
+                        families,
+                        ImageLayout::ShaderReadOnlyOptimal,
+                        ImageLayout::ShaderReadOnlyOptimal,
+                    );
+                }
+                builder.pipeline_barrier(&barrier);
+            })
+        }
+    }
+
+    fn builder(&mut self) -> &mut AutoCommandBufferBuilder {
+        let device = &self.device;
+        let family = self.transfer_queue.family();
+        self.pending.get_or_insert_with(|| {
+            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), family).unwrap()
+        })
+    }
+
+    fn graphics_builder(&mut self) -> &mut AutoCommandBufferBuilder {
+        let device = &self.device;
+        let family = self.graphics_queue.family();
+        self.pending_graphics.get_or_insert_with(|| {
+            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), family).unwrap()
+        })
+    }
+
+    fn create_image(
+        &self,
+        dimensions: Dimensions,
+        format: Format,
+        mipmaps: MipmapsCount,
+        usage: ImageUsage,
+        family: QueueFamily,
+    ) -> (
+        Arc<ImmutableImage<Format>>,
+        Arc<ImmutableImageInitialization<Format>>,
+    ) {
+        let (image, initialization) = ImmutableImage::uninitialized(
+            self.device.clone(),
+            dimensions,
+            format,
+            mipmaps,
+            ImageUsage {
+                transfer_destination: true,
+                ..usage
+            },
+            ImageLayout::ShaderReadOnlyOptimal,
+            iter::once(family),
+        )
+        .expect("failed to allocate device-local image");
+        (image, Arc::new(initialization))
+    }
+
+    // copies `bytes` into the next free staging buffer; uploads that don't fit in a slot, or
+    // arrive while every slot is in use, get a dedicated staging buffer instead
+    fn stage(&mut self, bytes: &[u8]) -> BufferSlice<[u8], StagingBuffer> {
+        if bytes.len() <= config::STAGING_BUFFER_SIZE {
+            for _ in 0..self.staging_buffers.len() {
+                let index = self.next_staging_buffer;
+                self.next_staging_buffer = (index + 1) % self.staging_buffers.len();
+                if self.reserved[index] {
+                    continue;
+                }
+                let staging_buffer = &self.staging_buffers[index];
+                // fails while the GPU is still reading from a previous batch
+                if let Ok(mut mapping) = staging_buffer.write() {
+                    mapping[..bytes.len()].copy_from_slice(bytes);
+                    drop(mapping);
+                    self.reserved[index] = true;
+                    return BufferSlice::from_typed_buffer_access(staging_buffer.clone())
+                        .slice(0..bytes.len())
+                        .unwrap();
+                }
+            }
+        }
+        let staging_buffer = CpuAccessibleBuffer::from_iter(
+            self.device.clone(),
+            BufferUsage::transfer_source(),
+            false,
+            bytes.iter().cloned(),
+        )
+        .expect("failed to create staging buffer");
+        BufferSlice::from_typed_buffer_access(staging_buffer)
+    }
+}
+
+// level `n` is blitted from level `n - 1` once that is a transfer source; each level goes back
+// to `ShaderReadOnlyOptimal` once it has been read from, or written to for the last level
+unsafe fn record_mipmap_blits(
+    builder: &mut UnsafeCommandBufferBuilder<StandardCommandPoolBuilder>,
+    image: &ImmutableImage<Format>,
+) {
+    let levels = image.mipmap_levels();
+    let layers = image.dimensions().array_layers();
+    let transfer = PipelineStages {
+        transfer: true,
+        ..PipelineStages::none()
+    };
+    let all_commands = PipelineStages {
+        all_commands: true,
+        ..PipelineStages::none()
+    };
+    let transfer_read = AccessFlagBits {
+        transfer_read: true,
+        ..AccessFlagBits::none()
+    };
+    let transfer_write = AccessFlagBits {
+        transfer_write: true,
+        ..AccessFlagBits::none()
+    };
+    let memory_write = AccessFlagBits {
+        memory_write: true,
+        ..AccessFlagBits::none()
+    };
+    let memory_read = AccessFlagBits {
+        memory_read: true,
+        ..AccessFlagBits::none()
+    };
+    let add_barrier = |barrier: &mut UnsafeCommandBufferBuilderPipelineBarrier,
+                       level: u32,
+                       source: (PipelineStages, AccessFlagBits),
+                       destination: (PipelineStages, AccessFlagBits),
+                       layouts: (ImageLayout, ImageLayout)| {
+        barrier.add_image_memory_barrier(
+            image,
+            level..level + 1,
+            0..layers,
+            source.0,
+            source.1,
+            destination.0,
+            destination.1,
+            false,
+            None,
+            layouts.0,
+            layouts.1,
+        );
+    };
+
+    for level in 1..levels {
+        let mut before = UnsafeCommandBufferBuilderPipelineBarrier::new();
+        // the first level was last written by the upload, later ones by the previous blit
+        let (source, source_layout) = if level == 1 {
+            (
+                (all_commands, memory_write),
+                ImageLayout::ShaderReadOnlyOptimal,
+            )
+        } else {
+            ((transfer, transfer_write), ImageLayout::TransferDstOptimal)
+        };
+        add_barrier(
+            &mut before,
+            level - 1,
+            source,
+            (transfer, transfer_read),
+            (source_layout, ImageLayout::TransferSrcOptimal),
+        );
+        // whatever the level held is overwritten
+        add_barrier(
+            &mut before,
+            level,
+            (all_commands, memory_write),
+            (transfer, transfer_write),
+            (ImageLayout::Undefined, ImageLayout::TransferDstOptimal),
+        );
+        builder.pipeline_barrier(&before);
+
+        let [src_width, src_height, _] = mip_dimensions(image.dimensions(), level - 1);
+        let [dst_width, dst_height, _] = mip_dimensions(image.dimensions(), level);
+        builder.blit_image(
+            image,
+            ImageLayout::TransferSrcOptimal,
+            image,
+            ImageLayout::TransferDstOptimal,
+            iter::once(UnsafeCommandBufferBuilderImageBlit {
+                aspects: UnsafeCommandBufferBuilderImageAspect {
+                    color: true,
+                    depth: false,
+                    stencil: false,
+                },
+                source_mip_level: level - 1,
+                destination_mip_level: level,
+                source_base_array_layer: 0,
+                destination_base_array_layer: 0,
+                layer_count: layers,
+                source_top_left: [0, 0, 0],
+                source_bottom_right: [src_width as i32, src_height as i32, 1],
+                destination_top_left: [0, 0, 0],
+                destination_bottom_right: [dst_width as i32, dst_height as i32, 1],
+            }),
+            Filter::Linear,
+        );
+
+        let mut after = UnsafeCommandBufferBuilderPipelineBarrier::new();
+        add_barrier(
+            &mut after,
+            level - 1,
+            (transfer, AccessFlagBits::none()),
+            (all_commands, memory_read),
+            (
+                ImageLayout::TransferSrcOptimal,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        );
+        if level == levels - 1 {
+            add_barrier(
+                &mut after,
+                level,
+                (transfer, transfer_write),
+                (all_commands, memory_read),
+                (
+                    ImageLayout::TransferDstOptimal,
+                    ImageLayout::ShaderReadOnlyOptimal,
+                ),
+            );
+        }
+        builder.pipeline_barrier(&after);
+    }
+}
+
+fn mip_dimensions(dimensions: Dimensions, level: u32) -> [u32; 3] {
+    let [width, height, depth] = dimensions.width_height_depth();
+    [
+        (width >> level).max(1),
+        (height >> level).max(1),
+        (depth >> level).max(1),
+    ]
+}
@@ -0,0 +1,110 @@
+use crate::app::config;
+use crate::app::paths;
+
+use std::path::{Path, PathBuf};
+
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::monitor::MonitorHandle;
+use winit::window::{Icon, Window, WindowBuilder};
+
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub title: String,
+    pub inner_size: [u32; 2],
+    pub min_inner_size: Option<[u32; 2]>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub transparent: bool,
+    pub icon_path: Option<PathBuf>,
+    pub position: Option<[i32; 2]>,
+    pub monitor: Option<usize>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> WindowConfig {
+        WindowConfig {
+            title: String::from(config::WINDOW_TITLE),
+            inner_size: [config::DEFAULT_WIDTH, config::DEFAULT_HEIGHT],
+            min_inner_size: Some([config::MIN_WIDTH, config::MIN_HEIGHT]),
+            resizable: config::RESIZABLE,
+            decorations: config::DECORATIONS,
+            transparent: config::TRANSPARENT,
+            icon_path: config::WINDOW_ICON_PATH.map(paths::resource_path),
+            position: config::WINDOW_POSITION,
+            monitor: config::WINDOW_MONITOR,
+        }
+    }
+}
+
+impl WindowConfig {
+    // sizes are logical so that the window keeps the same apparent size on HiDPI monitors;
+    // the window is created hidden and only shown once `place` has positioned it
+    pub fn window_builder(&self) -> WindowBuilder {
+        let mut builder = WindowBuilder::new()
+            .with_title(self.title.clone())
+            .with_inner_size(LogicalSize::new(self.inner_size[0], self.inner_size[1]))
+            .with_resizable(self.resizable)
+            .with_decorations(self.decorations)
+            .with_transparent(self.transparent)
+            .with_window_icon(self.icon_path.as_ref().and_then(|p| load_icon(p)))
+            .with_visible(false);
+        if let Some([width, height]) = self.min_inner_size {
+            builder = builder.with_min_inner_size(LogicalSize::new(width, height));
+        }
+        builder
+    }
+
+    pub fn place<T>(&self, window: &Window, event_loop: &EventLoopWindowTarget<T>) {
+        let monitor = self.choose_monitor(event_loop);
+        let monitor_position = monitor.position();
+        let position = match self.position {
+            Some([x, y]) => PhysicalPosition::new(monitor_position.x + x, monitor_position.y + y),
+            None => {
+                let monitor_size = monitor.size();
+                let window_size = window.outer_size();
+                PhysicalPosition::new(
+                    monitor_position.x + (monitor_size.width as i32 - window_size.width as i32) / 2,
+                    monitor_position.y
+                        + (monitor_size.height as i32 - window_size.height as i32) / 2,
+                )
+            }
+        };
+        window.set_outer_position(position);
+        window.set_visible(true);
+    }
+
+    fn choose_monitor<T>(&self, event_loop: &EventLoopWindowTarget<T>) -> MonitorHandle {
+        match self.monitor {
+            Some(index) => event_loop
+                .available_monitors()
+                .nth(index)
+                .unwrap_or_else(|| {
+                    println!(
+                        "WARNING: Monitor {} requested, but not available! Using the primary monitor.",
+                        index
+                    );
+                    event_loop.primary_monitor()
+                }),
+            None => event_loop.primary_monitor(),
+        }
+    }
+}
+
+fn load_icon(path: &Path) -> Option<Icon> {
+    let image = match image::open(path) {
+        Ok(image) => image.into_rgba8(),
+        Err(e) => {
+            println!(
+                "WARNING: Failed to load window icon {}: {}",
+                path.display(),
+                e
+            );
+            return None;
+        }
+    };
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height)
+        .map_err(|e| println!("WARNING: Invalid window icon {}: {}", path.display(), e))
+        .ok()
+}
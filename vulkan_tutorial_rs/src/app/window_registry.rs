@@ -0,0 +1,59 @@
+use crate::app::AppWindow;
+
+use winit::window::WindowId;
+
+use std::collections::HashMap;
+
+pub struct WindowRegistry {
+    windows: HashMap<WindowId, AppWindow>,
+}
+
+impl Default for WindowRegistry {
+    fn default() -> WindowRegistry {
+        WindowRegistry::new()
+    }
+}
+
+impl WindowRegistry {
+    pub fn new() -> WindowRegistry {
+        WindowRegistry {
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, window: AppWindow) {
+        self.windows.insert(window.id(), window);
+    }
+
+    pub fn remove(&mut self, id: WindowId) -> Option<AppWindow> {
+        self.windows.remove(&id)
+    }
+
+    pub fn get(&self, id: WindowId) -> Option<&AppWindow> {
+        self.windows.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: WindowId) -> Option<&mut AppWindow> {
+        self.windows.get_mut(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AppWindow> {
+        self.windows.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut AppWindow> {
+        self.windows.values_mut()
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = AppWindow> + '_ {
+        self.windows.drain().map(|(_, window)| window)
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+}
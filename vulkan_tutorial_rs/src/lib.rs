@@ -0,0 +1,29 @@
+pub mod app;
+mod log;
+pub mod vulkano_ext;
+
+extern crate cgmath;
+extern crate egui;
+extern crate gltf;
+extern crate image;
+extern crate notify;
+extern crate num;
+extern crate shaderc;
+extern crate tobj;
+extern crate vk_sys;
+extern crate vulkano;
+extern crate vulkano_shaders;
+extern crate vulkano_win;
+extern crate winit;
+
+pub use app::{
+    App, AppBuilder, AppWindow, Application, AxisBinding, ButtonBinding, Camera, Context, DebugUi,
+    DescriptorSetCache, DeviceSelector, FlyController, Frame, FrameCommandBuffer, FrameError,
+    FrameProfile, FrameStats, FrameTimeSummary, FrameUniforms, InitError, Input, InputBindings,
+    LogicalDevice, LoopTimer, Material, Mesh, MeshData, MeshDemo, Model, ModelData, ModelError,
+    OffscreenTarget, OrbitController, PhysicalDeviceInfo, Pod, PresentPolicy, ProfileHistory,
+    Profiler, ProfilerOverlay, Projection, QueueFamilies, RegionProfile, RenderTarget, Renderer,
+    ScreenshotCapture, ScreenshotError, ShaderCompiler, ShaderError, ShaderWatcher, SwapchainInfo,
+    Texture, TextureError, UniformBuffer, UniformSubbuffer, Uploader, Vertex, WindowConfig,
+    WindowRegistry,
+};
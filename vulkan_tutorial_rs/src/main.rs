@@ -1,16 +1,11 @@
-mod app;
-mod log;
-mod vulkano_ext;
+extern crate vulkan_tutorial_rs;
 
-extern crate num;
-extern crate vulkano;
-extern crate vulkano_shaders;
-extern crate vulkano_win;
-extern crate winit;
-
-use app::App;
+use vulkan_tutorial_rs::{App, MeshDemo};
 
 fn main() {
-    let app = App::new();
-    app.run();
+    let app = App::builder()
+        .with_input_bindings(MeshDemo::input_bindings())
+        .build()
+        .expect("failed to initialise app");
+    app.run(MeshDemo::new());
 }
@@ -0,0 +1,273 @@
+use vulkan_tutorial_rs::app::camera::{orthographic, perspective};
+use vulkan_tutorial_rs::app::config;
+use vulkan_tutorial_rs::{
+    AxisBinding, ButtonBinding, Camera, FlyController, Input, OrbitController, Projection,
+};
+
+use cgmath::{
+    Deg, EuclideanSpace, InnerSpace, Matrix4, MetricSpace, Point3, Rad, Vector3, Vector4,
+};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, VirtualKeyCode};
+
+use std::time::Duration;
+
+// normalised device coordinates of a view space point
+fn project(matrix: Matrix4<f32>, point: [f32; 3]) -> Vector3<f32> {
+    let clip = matrix * Vector4::new(point[0], point[1], point[2], 1.0);
+    clip.truncate() / clip.w
+}
+
+fn assert_close(actual: Vector3<f32>, expected: [f32; 3]) {
+    assert!(
+        (actual - Vector3::from(expected)).magnitude() < 1e-4,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+fn camera() -> Camera {
+    Camera::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Projection::Perspective {
+            fovy: Deg(90.0).into(),
+            near: 0.1,
+            far: 100.0,
+        },
+    )
+}
+
+// the bindings `Context::input` starts with, and nothing held
+fn input() -> Input {
+    Input::new(config::input_bindings())
+}
+
+#[test]
+fn perspective_maps_near_and_far_planes_to_vulkan_depth_range() {
+    let matrix = perspective(Deg(60.0).into(), 1.0, 0.5, 50.0);
+    assert_close(project(matrix, [0.0, 0.0, -0.5]), [0.0, 0.0, 0.0]);
+    assert_close(project(matrix, [0.0, 0.0, -50.0]), [0.0, 0.0, 1.0]);
+    let middle = project(matrix, [0.0, 0.0, -5.0]).z;
+    assert!(middle > 0.0 && middle < 1.0);
+}
+
+#[test]
+fn perspective_points_y_down_and_scales_x_by_aspect_ratio() {
+    // with a 90 degree field of view the frustum's edges are at 45 degrees
+    let matrix = perspective(Deg(90.0).into(), 2.0, 0.1, 10.0);
+    assert_close(project(matrix, [0.0, 0.1, -0.1]), [0.0, -1.0, 0.0]);
+    assert_close(project(matrix, [0.2, 0.0, -0.1]), [1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn orthographic_maps_box_to_vulkan_clip_volume() {
+    let matrix = orthographic(-2.0, 4.0, -1.0, 3.0, 1.0, 11.0);
+    assert_close(project(matrix, [-2.0, -1.0, -1.0]), [-1.0, 1.0, 0.0]);
+    assert_close(project(matrix, [4.0, 3.0, -11.0]), [1.0, -1.0, 1.0]);
+    assert_close(project(matrix, [1.0, 1.0, -6.0]), [0.0, 0.0, 0.5]);
+}
+
+#[test]
+fn orthographic_projection_width_follows_aspect_ratio() {
+    let projection = Projection::Orthographic {
+        height: 2.0,
+        near: 0.0,
+        far: 1.0,
+    };
+    assert_close(
+        project(projection.matrix(1.5), [1.5, 1.0, 0.0]),
+        [1.0, -1.0, 0.0],
+    );
+}
+
+#[test]
+fn resize_updates_aspect_ratio_unless_minimised() {
+    let mut camera = camera();
+    camera.resize([1600, 900]);
+    assert!((camera.aspect_ratio() - 16.0 / 9.0).abs() < 1e-6);
+    camera.resize([0, 0]);
+    assert!((camera.aspect_ratio() - 16.0 / 9.0).abs() < 1e-6);
+}
+
+#[test]
+fn default_orientation_looks_down_negative_z() {
+    let camera = camera();
+    assert_close(camera.forward(), [0.0, 0.0, -1.0]);
+    assert_close(camera.right(), [1.0, 0.0, 0.0]);
+    assert_close(camera.up(), [0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn look_at_centres_target_in_view() {
+    let mut camera = camera();
+    camera.position = Point3::new(3.0, 2.0, 1.0);
+    let target = Point3::new(-1.0, 0.5, 4.0);
+    camera.look_at(target);
+    let direction = (target - camera.position).normalize();
+    assert_close(camera.forward(), direction.into());
+    let distance = camera.position.distance(target);
+    let view = camera.view() * target.to_homogeneous();
+    assert_close(view.truncate(), [0.0, 0.0, -distance]);
+}
+
+#[test]
+fn pitch_is_clamped_short_of_vertical() {
+    let mut camera = camera();
+    camera.set_orientation(Rad(0.0), Deg(120.0).into());
+    assert!(camera.pitch() < Deg(90.0).into());
+    assert!(camera.forward().z < 0.0);
+    camera.look_at(Point3::new(0.0, -10.0, 0.0));
+    assert!(camera.pitch() > Deg(-90.0).into());
+}
+
+#[test]
+fn orbit_keeps_camera_at_distance_looking_at_target() {
+    let target = Point3::new(1.0, 2.0, 3.0);
+    let mut orbit = OrbitController::new(target, 5.0);
+    let mut camera = camera();
+    orbit.update(&input(), &mut camera);
+    assert_close(camera.position.to_vec(), [1.0, 2.0, 8.0]);
+
+    orbit.rotate(200.0, -100.0);
+    orbit.update(&input(), &mut camera);
+    assert!((camera.position.distance(target) - 5.0).abs() < 1e-4);
+    let direction = (target - camera.position).normalize();
+    assert_close(camera.forward(), direction.into());
+    // dragging right and up moves the camera to the target's left and below it
+    assert!(camera.position.x < target.x);
+    assert!(camera.position.y < target.y);
+}
+
+#[test]
+fn orbit_rotates_only_while_select_action_held() {
+    let mut orbit = OrbitController::new(Point3::new(0.0, 0.0, 0.0), 2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.mouse_motion((40.0, 0.0));
+    orbit.update(&input, &mut camera);
+    assert_eq!(orbit.yaw(), Rad(0.0));
+    input.end_frame();
+
+    input.mouse_button(MouseButton::Left, ElementState::Pressed);
+    input.mouse_motion((10.0, 0.0));
+    orbit.update(&input, &mut camera);
+    assert!((orbit.yaw().0 + 10.0 * config::MOUSE_SENSITIVITY).abs() < 1e-6);
+    input.end_frame();
+
+    input.mouse_button(MouseButton::Left, ElementState::Released);
+    input.mouse_motion((40.0, 0.0));
+    orbit.update(&input, &mut camera);
+    assert!((orbit.yaw().0 + 10.0 * config::MOUSE_SENSITIVITY).abs() < 1e-6);
+}
+
+#[test]
+fn orbit_zooms_with_scroll_lines_and_pixels() {
+    let mut orbit = OrbitController::new(Point3::new(0.0, 0.0, 0.0), 2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+    orbit.update(&input, &mut camera);
+    assert!((orbit.distance() - 2.0 * (1.0 - config::ORBIT_ZOOM_STEP)).abs() < 1e-6);
+    assert!((camera.position.to_vec().magnitude() - orbit.distance()).abs() < 1e-6);
+    input.end_frame();
+
+    input.mouse_wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+        0.0,
+        -f64::from(config::PIXELS_PER_SCROLL_LINE),
+    )));
+    orbit.update(&input, &mut camera);
+    assert!((orbit.distance() - 2.0).abs() < 1e-6);
+    input.end_frame();
+
+    // nothing scrolled since the last update
+    orbit.update(&input, &mut camera);
+    assert!((orbit.distance() - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn orbit_zoom_is_clamped() {
+    let mut orbit = OrbitController::new(Point3::new(0.0, 0.0, 0.0), 2.0);
+    orbit.zoom(1.0);
+    assert!((orbit.distance() - 2.0 * (1.0 - config::ORBIT_ZOOM_STEP)).abs() < 1e-6);
+    orbit.zoom(-1.0);
+    assert!((orbit.distance() - 2.0).abs() < 1e-6);
+    orbit.zoom(1000.0);
+    assert_eq!(orbit.distance(), config::ORBIT_MIN_DISTANCE);
+    orbit.zoom(-1000.0);
+    assert_eq!(orbit.distance(), config::ORBIT_MAX_DISTANCE);
+}
+
+#[test]
+fn fly_moves_along_held_keys_at_speed() {
+    let fly = FlyController::new(2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.key(VirtualKeyCode::W, ElementState::Pressed);
+    fly.update(&input, &mut camera, Duration::from_millis(500));
+    assert_close(camera.position.to_vec(), [0.0, 0.0, -1.0]);
+    input.end_frame();
+
+    // diagonal movement is no faster, nor is holding two keys for the same direction
+    input.key(VirtualKeyCode::D, ElementState::Pressed);
+    input.key(VirtualKeyCode::Up, ElementState::Pressed);
+    fly.update(&input, &mut camera, Duration::from_millis(500));
+    assert!((camera.position.distance(Point3::new(0.0, 0.0, -1.0)) - 1.0).abs() < 1e-4);
+    input.end_frame();
+
+    input.focus_lost();
+    let position = camera.position;
+    fly.update(&input, &mut camera, Duration::from_secs(1));
+    assert_eq!(camera.position, position);
+}
+
+#[test]
+fn fly_opposite_keys_cancel_out() {
+    let fly = FlyController::new(2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.key(VirtualKeyCode::Space, ElementState::Pressed);
+    input.key(VirtualKeyCode::LControl, ElementState::Pressed);
+    fly.update(&input, &mut camera, Duration::from_secs(1));
+    assert_eq!(camera.position, Point3::new(0.0, 0.0, 0.0));
+
+    input.key(VirtualKeyCode::LControl, ElementState::Released);
+    fly.update(&input, &mut camera, Duration::from_secs(1));
+    assert_close(camera.position.to_vec(), [0.0, 2.0, 0.0]);
+}
+
+#[test]
+fn fly_looks_around_while_look_action_held() {
+    let fly = FlyController::new(2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.mouse_motion((100.0, 0.0));
+    fly.update(&input, &mut camera, Duration::from_secs(0));
+    assert_eq!(camera.forward(), Vector3::new(0.0, 0.0, -1.0));
+    input.end_frame();
+
+    input.mouse_button(MouseButton::Right, ElementState::Pressed);
+    input.mouse_motion((50.0, -20.0));
+    fly.update(&input, &mut camera, Duration::from_secs(0));
+    // moving the mouse right turns right and moving it up looks up
+    assert!(camera.forward().x > 0.0);
+    assert!(camera.forward().y > 0.0);
+}
+
+#[test]
+fn controllers_follow_rebound_axes() {
+    let fly = FlyController::new(2.0);
+    let mut camera = camera();
+    let mut input = input();
+    input.bindings_mut().bind_axis(
+        "move_z",
+        AxisBinding::Buttons {
+            positive: ButtonBinding::Key(VirtualKeyCode::E),
+            negative: ButtonBinding::Key(VirtualKeyCode::Q),
+        },
+    );
+    input.key(VirtualKeyCode::Q, ElementState::Pressed);
+    fly.update(&input, &mut camera, Duration::from_secs(1));
+    assert_close(camera.position.to_vec(), [0.0, -2.0, 0.0]);
+}
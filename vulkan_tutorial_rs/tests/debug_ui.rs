@@ -0,0 +1,99 @@
+use vulkan_tutorial_rs::app::debug_ui::input::{egui_key, egui_modifiers, DebugUiInput};
+
+use egui::{Event, Key, Modifiers, PointerButton, Pos2, Vec2};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode};
+
+#[test]
+fn pointer_positions_are_in_points() {
+    let mut input = DebugUiInput::new(2.0);
+    input.cursor_moved(PhysicalPosition::new(100.0, 50.0));
+    input.mouse_button(MouseButton::Left, ElementState::Pressed);
+
+    let raw = input.take([800, 600], 1.0);
+    assert_eq!(
+        raw.events,
+        vec![
+            Event::PointerMoved(Pos2::new(50.0, 25.0)),
+            Event::PointerButton {
+                pos: Pos2::new(50.0, 25.0),
+                button: PointerButton::Primary,
+                pressed: true,
+                modifiers: Modifiers::default(),
+            },
+        ]
+    );
+    assert_eq!(raw.pixels_per_point, Some(2.0));
+    assert_eq!(raw.screen_rect.unwrap().size(), Vec2::new(400.0, 300.0));
+}
+
+#[test]
+fn buttons_are_ignored_without_a_pointer_position() {
+    let mut input = DebugUiInput::new(1.0);
+    input.mouse_button(MouseButton::Left, ElementState::Pressed);
+    input.cursor_moved(PhysicalPosition::new(10.0, 10.0));
+    input.cursor_left();
+    input.mouse_button(MouseButton::Left, ElementState::Released);
+
+    let raw = input.take([800, 600], 0.0);
+    assert_eq!(
+        raw.events,
+        vec![
+            Event::PointerMoved(Pos2::new(10.0, 10.0)),
+            Event::PointerGone
+        ]
+    );
+}
+
+#[test]
+fn events_are_handed_over_once() {
+    let mut input = DebugUiInput::new(1.0);
+    input.character('a');
+    input.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -1.0));
+    let raw = input.take([800, 600], 0.0);
+    assert_eq!(raw.events, vec![Event::Text("a".to_string())]);
+    assert!(raw.scroll_delta.y < 0.0);
+
+    let raw = input.take([800, 600], 0.1);
+    assert!(raw.events.is_empty());
+    assert_eq!(raw.scroll_delta, Vec2::default());
+}
+
+#[test]
+fn control_characters_are_not_text() {
+    let mut input = DebugUiInput::new(1.0);
+    input.character('\u{8}');
+    input.character('\r');
+    assert!(input.take([800, 600], 0.0).events.is_empty());
+}
+
+#[test]
+fn keys_carry_the_current_modifiers() {
+    let mut input = DebugUiInput::new(1.0);
+    input.modifiers_changed(ModifiersState::SHIFT);
+    input.key(VirtualKeyCode::Tab, ElementState::Pressed);
+    // not a key egui handles
+    input.key(VirtualKeyCode::F5, ElementState::Pressed);
+
+    let raw = input.take([800, 600], 0.0);
+    let shift = egui_modifiers(ModifiersState::SHIFT);
+    assert!(shift.shift && !shift.ctrl && !shift.alt);
+    assert_eq!(raw.modifiers, shift);
+    assert_eq!(
+        raw.events,
+        vec![Event::Key {
+            key: Key::Tab,
+            pressed: true,
+            modifiers: shift,
+        }]
+    );
+}
+
+#[test]
+fn keys_map_to_egui() {
+    assert_eq!(egui_key(VirtualKeyCode::Back), Some(Key::Backspace));
+    assert_eq!(egui_key(VirtualKeyCode::Return), Some(Key::Enter));
+    assert_eq!(egui_key(VirtualKeyCode::Left), Some(Key::ArrowLeft));
+    assert_eq!(egui_key(VirtualKeyCode::F1), None);
+}
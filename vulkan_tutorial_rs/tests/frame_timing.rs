@@ -0,0 +1,71 @@
+use vulkan_tutorial_rs::{FrameStats, LoopTimer};
+
+use std::time::{Duration, Instant};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn summary_is_none_without_frames() {
+    assert!(FrameStats::new(4).summary().is_none());
+}
+
+#[test]
+fn summary_reports_min_average_max_and_p99() {
+    let mut stats = FrameStats::new(100);
+    for i in 1..=100 {
+        stats.record(ms(i));
+    }
+    let summary = stats.summary().unwrap();
+    assert_eq!(summary.min, ms(1));
+    assert_eq!(summary.max, ms(100));
+    assert_eq!(summary.average, Duration::from_micros(50_500));
+    assert_eq!(summary.p99, ms(99));
+}
+
+#[test]
+fn history_drops_oldest_frames() {
+    let mut stats = FrameStats::new(2);
+    stats.record(ms(100));
+    stats.record(ms(10));
+    stats.record(ms(20));
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats.summary().unwrap().max, ms(20));
+}
+
+#[test]
+fn fixed_updates_accumulate_partial_steps() {
+    let mut timer = LoopTimer::new(100, 8, None);
+    assert_eq!(timer.advance(ms(5)), 0);
+    assert!((timer.alpha() - 0.5).abs() < 1e-9);
+    assert_eq!(timer.advance(ms(25)), 3);
+    assert!(timer.alpha().abs() < 1e-9);
+}
+
+#[test]
+fn fixed_updates_are_limited_per_frame() {
+    let mut timer = LoopTimer::new(100, 4, None);
+    assert_eq!(timer.advance(ms(1000)), 4);
+    assert_eq!(timer.advance(ms(0)), 0);
+}
+
+#[test]
+fn frame_rate_cap_delays_next_frame() {
+    let mut timer = LoopTimer::new(60, 8, Some(50));
+    let start = Instant::now();
+    assert!(timer.is_frame_due(start));
+    assert_eq!(timer.begin_frame(start), None);
+    assert!(!timer.is_frame_due(start + ms(10)));
+    assert!(timer.is_frame_due(start + ms(20)));
+    assert_eq!(timer.begin_frame(start + ms(25)), Some(ms(25)));
+}
+
+#[test]
+fn uncapped_frames_are_always_due() {
+    let mut timer = LoopTimer::new(60, 8, None);
+    let start = Instant::now();
+    timer.begin_frame(start);
+    assert!(timer.is_frame_due(start));
+    assert_eq!(timer.next_frame_at(), None);
+}
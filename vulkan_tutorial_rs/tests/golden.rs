@@ -0,0 +1,209 @@
+use vulkan_tutorial_rs::app::config;
+use vulkan_tutorial_rs::app::golden::{self, GoldenError, Tolerance};
+use vulkan_tutorial_rs::app::mesh::builtin;
+use vulkan_tutorial_rs::app::paths;
+use vulkan_tutorial_rs::{
+    App, FrameUniforms, Mesh, MeshData, OffscreenTarget, PhysicalDeviceInfo, Renderer,
+    ScreenshotCapture, Texture, Vertex,
+};
+
+use cgmath::{Matrix4, Rad, SquareMatrix, Vector3};
+
+use image::RgbaImage;
+
+use vulkano::format::Format;
+use vulkano::instance::{InstanceExtensions, PhysicalDeviceType};
+use vulkano::sync::GpuFuture;
+
+const DIMENSIONS: [u32; 2] = [256, 256];
+const FORMAT: Format = Format::R8G8B8A8Unorm;
+const TEXTURE_SIZE: u32 = 8;
+
+struct Scene {
+    meshes: Vec<MeshData<Vertex>>,
+    uniforms: FrameUniforms,
+}
+
+// the references are only meaningful for the rasterizer that produced them, lavapipe, so these
+// tests run on a software implementation and pass without doing anything where there is none,
+// unless `REQUIRE_GOLDEN=1`
+fn software_app() -> Option<App> {
+    let missing = if InstanceExtensions::supported_by_core().is_err() {
+        "the Vulkan loader isn't available"
+    } else {
+        let app = App::builder()
+            .headless()
+            .with_validation(false)
+            .with_device_selector(|a, b| is_software(a).cmp(&is_software(b)).then_with(|| a.cmp(b)))
+            .build();
+        match app {
+            Ok(ref app) if !is_software(app.context().physical_device_info()) => {
+                "no software rasterizer is available"
+            }
+            Ok(app) => return Some(app),
+            Err(_) => "no Vulkan device is available",
+        }
+    };
+    if golden::is_flag_set(golden::REQUIRE_ENVIRONMENT_VARIABLE) {
+        panic!(
+            "{}; install lavapipe to run the golden image tests",
+            missing
+        );
+    }
+    println!("{}, skipping", missing);
+    None
+}
+
+fn is_software(info: &PhysicalDeviceInfo) -> bool {
+    info.physical_device().ty() == PhysicalDeviceType::Cpu
+}
+
+fn scene(name: &str) -> Scene {
+    match name {
+        "triangle" => Scene {
+            meshes: vec![builtin::triangle()],
+            uniforms: FrameUniforms::default(),
+        },
+        "textured_quad" => Scene {
+            meshes: vec![builtin::quad()],
+            uniforms: FrameUniforms::new(
+                Matrix4::from_angle_z(Rad(0.3)),
+                Matrix4::identity(),
+                Matrix4::identity(),
+                0.0,
+            ),
+        },
+        // without a projection, so the cube is kept within Vulkan's [0, 1] depth range
+        "depth_tested_cube" => Scene {
+            meshes: vec![builtin::cube()],
+            uniforms: FrameUniforms::new(
+                Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.5))
+                    * Matrix4::from_angle_x(Rad(0.6))
+                    * Matrix4::from_angle_y(Rad(0.8))
+                    * Matrix4::from_scale(0.5),
+                Matrix4::identity(),
+                Matrix4::identity(),
+                0.0,
+            ),
+        },
+        _ => panic!("unknown scene {}", name),
+    }
+}
+
+fn checkerboard() -> Vec<u8> {
+    (0..TEXTURE_SIZE * TEXTURE_SIZE)
+        .flat_map(|i| {
+            if (i % TEXTURE_SIZE + i / TEXTURE_SIZE) % 2 == 0 {
+                vec![255, 255, 255, 255]
+            } else {
+                vec![64, 64, 64, 255]
+            }
+        })
+        .collect()
+}
+
+// `transparent` renders as a window with a non-opaque composite alpha would
+fn render(app: &App, scene: &Scene, transparent: bool) -> RgbaImage {
+    let context = app.context();
+    let logical_device = context.logical_device();
+    let queue = logical_device.graphics_queue();
+
+    let mut uploader = logical_device.uploader();
+    let meshes: Vec<_> = scene
+        .meshes
+        .iter()
+        .map(|data| Mesh::upload(&mut uploader, data))
+        .collect();
+    let texture = Texture::from_rgba8(&mut uploader, &checkerboard(), [TEXTURE_SIZE; 2]);
+    uploader
+        .flush()
+        .then_signal_fence_and_flush()
+        .expect("failed to flush uploads")
+        .wait(None)
+        .expect("failed to upload scene");
+
+    let target = OffscreenTarget::new(logical_device, DIMENSIONS, FORMAT, 1, transparent);
+    let mut renderer = Renderer::new(logical_device, &target);
+    let meshes: Vec<_> = meshes.iter().collect();
+    let draw = renderer.draw_offscreen(logical_device, &target, &meshes, scene.uniforms, &texture);
+    let (capture, copy) = ScreenshotCapture::record(
+        logical_device.device(),
+        queue.family(),
+        target.image().clone(),
+        FORMAT,
+        DIMENSIONS,
+        !transparent,
+    )
+    .expect("failed to record image copy");
+    vulkano::sync::now(logical_device.device().clone())
+        .then_execute(queue.clone(), draw)
+        .unwrap()
+        .then_execute(queue.clone(), copy)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .expect("failed to submit scene")
+        .wait(None)
+        .expect("failed to render scene");
+    capture.to_rgba8().expect("failed to read rendered image")
+}
+
+fn check_scene(name: &str, tolerance: Tolerance) {
+    let app = match software_app() {
+        Some(app) => app,
+        None => return,
+    };
+    let image = render(&app, &scene(name), false);
+    match golden::check(
+        name,
+        &image,
+        &tolerance,
+        &paths::resource_path(config::GOLDEN_DIRECTORY),
+        &paths::data_path(config::GOLDEN_OUTPUT_DIRECTORY),
+    ) {
+        Ok(()) => (),
+        // references are generated with `UPDATE_GOLDEN=1` on lavapipe and committed
+        Err(ref e @ GoldenError::MissingReference { .. })
+            if !golden::is_flag_set(golden::REQUIRE_ENVIRONMENT_VARIABLE) =>
+        {
+            println!("{}, skipping", e)
+        }
+        Err(e) => panic!("scene {} doesn't match its golden image: {}", name, e),
+    }
+}
+
+#[test]
+fn triangle() {
+    check_scene("triangle", Tolerance::default());
+}
+
+#[test]
+fn textured_quad() {
+    // texture filtering differs between implementations along the checker edges
+    check_scene(
+        "textured_quad",
+        Tolerance {
+            max_differing_pixels: 64,
+            ..Tolerance::default()
+        },
+    );
+}
+
+#[test]
+fn depth_tested_cube() {
+    check_scene("depth_tested_cube", Tolerance::default());
+}
+
+#[test]
+fn background_alpha_follows_the_target() {
+    let app = match software_app() {
+        Some(app) => app,
+        None => return,
+    };
+    // the triangle covers the centre but not the corners
+    let opaque = render(&app, &scene("triangle"), false);
+    assert_eq!(opaque.get_pixel(0, 0)[3], 255);
+    assert_eq!(opaque.get_pixel(128, 128)[3], 255);
+    let transparent = render(&app, &scene("triangle"), true);
+    assert_eq!(transparent.get_pixel(0, 0)[3], 0);
+    assert_eq!(transparent.get_pixel(128, 128)[3], 255);
+}
@@ -0,0 +1,131 @@
+use vulkan_tutorial_rs::app::golden::{self, GoldenError, Tolerance};
+
+use image::{Rgba, RgbaImage};
+
+use std::fs;
+use std::path::PathBuf;
+
+fn solid(color: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_pixel(4, 4, Rgba(color))
+}
+
+fn temporary_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!(
+        "vulkan_tutorial_rs_golden_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+}
+
+#[test]
+fn identical_images_match() {
+    let image = solid([10, 20, 30, 255]);
+    let comparison = golden::compare(&image, &image, &Tolerance::default()).unwrap();
+    assert_eq!(comparison.differing_pixels, 0);
+    assert_eq!(comparison.max_channel_difference, 0);
+    assert_eq!(comparison.max_perceptual_distance, 0.0);
+}
+
+#[test]
+fn differences_within_tolerance_match() {
+    let expected = solid([100, 100, 100, 255]);
+    let actual = solid([104, 100, 98, 255]);
+    let comparison = golden::compare(&expected, &actual, &Tolerance::default()).unwrap();
+    assert_eq!(comparison.max_channel_difference, 4);
+    assert_eq!(comparison.differing_pixels, 0);
+}
+
+#[test]
+fn differing_pixels_are_counted_and_marked() {
+    let expected = solid([0, 0, 0, 255]);
+    let mut actual = expected.clone();
+    actual.put_pixel(1, 2, Rgba([255, 255, 255, 255]));
+    let tolerance = Tolerance::default();
+    let comparison = golden::compare(&expected, &actual, &tolerance).unwrap();
+    assert_eq!(comparison.differing_pixels, 1);
+    assert_eq!(comparison.max_channel_difference, 255);
+    assert!(comparison.max_perceptual_distance > 0.9);
+    assert_eq!(*comparison.diff.get_pixel(1, 2), Rgba([255, 0, 0, 255]));
+    assert_ne!(*comparison.diff.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    assert!(!comparison.passes(&tolerance));
+    assert!(comparison.passes(&Tolerance {
+        max_differing_pixels: 1,
+        ..tolerance
+    }));
+}
+
+#[test]
+fn perceptual_distance_weighs_brightness_over_hue() {
+    let grey = Rgba([128, 128, 128, 255]);
+    let brighter = Rgba([148, 148, 148, 255]);
+    let bluer = Rgba([128, 128, 148, 255]);
+    assert!(
+        golden::perceptual_distance(&grey, &brighter) > golden::perceptual_distance(&grey, &bluer)
+    );
+    assert_eq!(golden::perceptual_distance(&grey, &grey), 0.0);
+}
+
+#[test]
+fn perceptual_tolerance_can_fail_small_channel_differences() {
+    let expected = solid([100, 100, 100, 255]);
+    let actual = solid([110, 110, 110, 255]);
+    let tolerance = Tolerance {
+        perceptual: 0.01,
+        ..Tolerance::default()
+    };
+    let comparison = golden::compare(&expected, &actual, &tolerance).unwrap();
+    assert_eq!(comparison.differing_pixels, 16);
+}
+
+#[test]
+fn different_dimensions_dont_match() {
+    let expected = RgbaImage::new(4, 4);
+    let actual = RgbaImage::new(4, 5);
+    match golden::compare(&expected, &actual, &Tolerance::default()) {
+        Err(GoldenError::DimensionMismatch { expected, actual }) => {
+            assert_eq!(expected, (4, 4));
+            assert_eq!(actual, (4, 5));
+        }
+        _ => panic!("expected a dimension mismatch"),
+    }
+}
+
+#[test]
+fn check_fails_on_missing_references_and_writes_mismatch_images() {
+    let references = temporary_directory("references");
+    let output = temporary_directory("output");
+    let tolerance = Tolerance::default();
+
+    let image = solid([0, 0, 0, 255]);
+    match golden::check("scene", &image, &tolerance, &references, &output) {
+        Err(GoldenError::MissingReference { reference, actual }) => {
+            assert_eq!(reference, references.join("scene.png"));
+            assert_eq!(actual, output.join("scene.actual.png"));
+            assert!(!reference.exists());
+            assert!(actual.exists());
+        }
+        _ => panic!("expected a missing reference"),
+    }
+
+    fs::create_dir_all(&references).unwrap();
+    image.save(references.join("scene.png")).unwrap();
+    fs::remove_dir_all(&output).unwrap();
+    golden::check("scene", &image, &tolerance, &references, &output).unwrap();
+    assert!(!output.exists());
+
+    let changed = solid([255, 255, 255, 255]);
+    match golden::check("scene", &changed, &tolerance, &references, &output) {
+        Err(GoldenError::Mismatch {
+            differing_pixels, ..
+        }) => assert_eq!(differing_pixels, 16),
+        _ => panic!("expected a mismatch"),
+    }
+    for suffix in &["actual", "expected", "diff"] {
+        assert!(output.join(format!("scene.{}.png", suffix)).exists());
+    }
+
+    let _ = fs::remove_dir_all(&references);
+    let _ = fs::remove_dir_all(&output);
+}
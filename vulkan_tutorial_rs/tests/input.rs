@@ -0,0 +1,193 @@
+use vulkan_tutorial_rs::app::config;
+use vulkan_tutorial_rs::{AxisBinding, ButtonBinding, Input, InputBindings};
+
+use cgmath::Vector2;
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, VirtualKeyCode};
+
+const JUMP: ButtonBinding = ButtonBinding::Key(VirtualKeyCode::Space);
+const FIRE: ButtonBinding = ButtonBinding::Mouse(MouseButton::Left);
+
+fn input() -> Input {
+    let mut bindings = InputBindings::new();
+    bindings.bind_action("jump", JUMP);
+    bindings.bind_action("jump", ButtonBinding::Key(VirtualKeyCode::W));
+    bindings.bind_action("fire", FIRE);
+    bindings.bind_axis(
+        "move",
+        AxisBinding::Buttons {
+            positive: ButtonBinding::Key(VirtualKeyCode::D),
+            negative: ButtonBinding::Key(VirtualKeyCode::A),
+        },
+    );
+    bindings.bind_axis("move", AxisBinding::Device(0));
+    bindings.bind_axis("zoom", AxisBinding::ScrollY);
+    bindings.bind_axis("look", AxisBinding::MouseX);
+    Input::new(bindings)
+}
+
+#[test]
+fn pressed_and_released_last_one_frame_and_held_until_release() {
+    let mut input = input();
+    input.key(VirtualKeyCode::Space, ElementState::Pressed);
+    assert!(input.was_pressed("jump"));
+    assert!(input.is_held("jump"));
+    assert!(!input.was_released("jump"));
+
+    input.end_frame();
+    assert!(!input.was_pressed("jump"));
+    assert!(input.is_held("jump"));
+
+    input.key(VirtualKeyCode::Space, ElementState::Released);
+    assert!(input.was_released("jump"));
+    assert!(!input.is_held("jump"));
+    input.end_frame();
+    assert!(!input.was_released("jump"));
+}
+
+#[test]
+fn key_repeats_are_not_presses() {
+    let mut input = input();
+    input.key(VirtualKeyCode::Space, ElementState::Pressed);
+    input.end_frame();
+    input.key(VirtualKeyCode::Space, ElementState::Pressed);
+    assert!(!input.was_pressed("jump"));
+    assert!(input.is_held("jump"));
+}
+
+#[test]
+fn tap_within_one_frame_is_pressed_and_released() {
+    let mut input = input();
+    input.mouse_button(MouseButton::Left, ElementState::Pressed);
+    input.mouse_button(MouseButton::Left, ElementState::Released);
+    assert!(input.was_pressed("fire"));
+    assert!(input.was_released("fire"));
+    assert!(!input.is_held("fire"));
+    assert!(input.was_button_pressed(FIRE));
+}
+
+#[test]
+fn any_binding_triggers_an_action() {
+    let mut input = input();
+    input.key(VirtualKeyCode::W, ElementState::Pressed);
+    assert!(input.is_held("jump"));
+    assert!(!input.is_button_held(JUMP));
+    assert!(!input.is_held("fire"));
+    assert!(!input.is_held("unknown"));
+    assert_eq!(input.axis("unknown"), 0.0);
+}
+
+#[test]
+fn button_axes_cancel_out_and_sum_with_device_axes() {
+    let mut input = input();
+    input.key(VirtualKeyCode::D, ElementState::Pressed);
+    assert_eq!(input.axis("move"), 1.0);
+    input.key(VirtualKeyCode::A, ElementState::Pressed);
+    assert_eq!(input.axis("move"), 0.0);
+    input.key(VirtualKeyCode::D, ElementState::Released);
+    assert_eq!(input.axis("move"), -1.0);
+
+    // device axes keep their last value across frames
+    input.device_motion(0, 0.25);
+    input.end_frame();
+    assert_eq!(input.axis("move"), -0.75);
+    assert_eq!(input.device_axis(0), 0.25);
+    assert_eq!(input.device_axis(1), 0.0);
+}
+
+#[test]
+fn deltas_accumulate_until_the_end_of_the_frame() {
+    let mut input = input();
+    input.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+    input.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 2.0));
+    input.mouse_motion((3.0, 4.0));
+    input.mouse_motion((1.0, -1.0));
+    assert_eq!(input.axis("zoom"), 3.0);
+    assert_eq!(input.axis("look"), 4.0);
+    assert_eq!(input.mouse_delta(), Vector2::new(4.0, 3.0));
+
+    input.end_frame();
+    assert_eq!(input.axis("zoom"), 0.0);
+    assert_eq!(input.axis("look"), 0.0);
+    assert_eq!(input.scroll_delta(), Vector2::new(0.0, 0.0));
+}
+
+#[test]
+fn cursor_delta_skips_leaving_and_reentering_the_window() {
+    let mut input = input();
+    input.cursor_moved(PhysicalPosition::new(10.0, 10.0));
+    assert_eq!(input.cursor_delta(), Vector2::new(0.0, 0.0));
+    input.cursor_moved(PhysicalPosition::new(15.0, 8.0));
+    assert_eq!(input.cursor_delta(), Vector2::new(5.0, -2.0));
+
+    input.cursor_left();
+    assert_eq!(input.cursor_position(), None);
+    input.cursor_moved(PhysicalPosition::new(500.0, 500.0));
+    assert_eq!(input.cursor_delta(), Vector2::new(5.0, -2.0));
+    assert_eq!(
+        input.cursor_position(),
+        Some(PhysicalPosition::new(500.0, 500.0))
+    );
+}
+
+#[test]
+fn losing_focus_releases_everything() {
+    let mut input = input();
+    input.key(VirtualKeyCode::Space, ElementState::Pressed);
+    input.mouse_button(MouseButton::Left, ElementState::Pressed);
+    input.end_frame();
+    input.focus_lost();
+    assert!(input.was_released("jump"));
+    assert!(input.was_released("fire"));
+    assert!(!input.is_held("jump"));
+    assert!(!input.is_held("fire"));
+}
+
+#[test]
+fn rebinding_takes_effect_immediately() {
+    let mut input = input();
+    input.key(VirtualKeyCode::J, ElementState::Pressed);
+    assert!(!input.is_held("jump"));
+
+    input.bindings_mut().unbind_action("jump");
+    input
+        .bindings_mut()
+        .bind_action("jump", ButtonBinding::Key(VirtualKeyCode::J));
+    assert!(input.is_held("jump"));
+    assert_eq!(
+        input.bindings().action("jump"),
+        &[ButtonBinding::Key(VirtualKeyCode::J)]
+    );
+    assert_eq!(
+        input
+            .bindings()
+            .actions_bound_to(ButtonBinding::Key(VirtualKeyCode::J)),
+        vec!["jump"]
+    );
+}
+
+#[test]
+fn binding_twice_is_ignored() {
+    let mut bindings = InputBindings::new();
+    bindings.bind_action("jump", JUMP);
+    bindings.bind_action("jump", JUMP);
+    bindings.bind_axis("zoom", AxisBinding::ScrollY);
+    bindings.bind_axis("zoom", AxisBinding::ScrollY);
+    assert_eq!(bindings.action("jump").len(), 1);
+    assert_eq!(bindings.axis("zoom").len(), 1);
+}
+
+#[test]
+fn default_bindings_bind_each_button_to_one_action_at_most() {
+    let bindings = config::input_bindings();
+    for &action in &["confirm", "cancel", "select", "look"] {
+        for &button in bindings.action(action) {
+            assert_eq!(bindings.actions_bound_to(button), vec![action]);
+        }
+        assert!(!bindings.action(action).is_empty());
+    }
+    for &axis in &["move_x", "move_y", "move_z", "look_x", "look_y", "zoom"] {
+        assert!(!bindings.axis(axis).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use vulkan_tutorial_rs::app::init::{
+    create_app_info, create_instance, get_physical_device_infos, pick_physical_device_info,
+};
+use vulkan_tutorial_rs::app::DeviceSelector;
+
+use vulkano::device::Features;
+use vulkano::instance::InstanceExtensions;
+
+// these tests need a Vulkan loader; on machines without one they pass without doing anything
+fn vulkan_available() -> bool {
+    let available = InstanceExtensions::supported_by_core().is_ok();
+    if !available {
+        println!("Vulkan loader not available, skipping");
+    }
+    available
+}
+
+#[test]
+fn instance_loads_only_requested_extensions() {
+    if !vulkan_available() {
+        return;
+    }
+    let instance = create_instance(&create_app_info(), &InstanceExtensions::none(), false)
+        .expect("failed to create Vulkan instance");
+    assert_eq!(*instance.loaded_extensions(), InstanceExtensions::none());
+}
+
+#[test]
+fn device_selection_without_surfaces_picks_a_valid_device() {
+    if !vulkan_available() {
+        return;
+    }
+    let instance = create_instance(&create_app_info(), &InstanceExtensions::none(), false)
+        .expect("failed to create Vulkan instance");
+    let infos = get_physical_device_infos(&instance, &[]);
+    if !infos.iter().any(|d| d.is_valid()) {
+        println!("No valid physical device, skipping");
+        return;
+    }
+    let selector: DeviceSelector = Box::new(|a, b| a.cmp(b));
+    let picked = pick_physical_device_info(infos, &Features::none(), &selector)
+        .expect("a valid device was available");
+    assert!(picked.is_valid());
+    assert!(picked.is_headless());
+}
@@ -0,0 +1,133 @@
+use vulkan_tutorial_rs::app::texture::ktx2::{self, Ktx2Error};
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n',
+];
+const VK_FORMAT_R8G8B8A8_SRGB: u32 = 43;
+
+// builds a 2D texture whose level `i` is filled with the byte `i`
+fn ktx2_file(width: u32, height: u32, level_count: u32, supercompression: u32) -> Vec<u8> {
+    let stored_levels = level_count.max(1);
+    let mut header = IDENTIFIER.to_vec();
+    for value in &[
+        VK_FORMAT_R8G8B8A8_SRGB,
+        1,
+        width,
+        height,
+        0,
+        0,
+        1,
+        level_count,
+        supercompression,
+    ] {
+        header.extend(&value.to_le_bytes());
+    }
+    // empty data format descriptor, key/value data and supercompression global data
+    header.extend(&[0; 32]);
+
+    let mut offset = (header.len() + stored_levels as usize * 24) as u64;
+    let mut data = Vec::new();
+    for level in 0..stored_levels {
+        let length = (((width >> level).max(1) * (height >> level).max(1)) * 4) as u64;
+        header.extend(&offset.to_le_bytes());
+        header.extend(&length.to_le_bytes());
+        header.extend(&length.to_le_bytes());
+        data.extend(std::iter::repeat(level as u8).take(length as usize));
+        offset += length;
+    }
+    header.extend(data);
+    header
+}
+
+#[test]
+fn parses_levels_largest_first() {
+    let file = ktx2_file(4, 2, 3, 0);
+    let texture = ktx2::parse(&file).unwrap();
+    assert_eq!(texture.vk_format, VK_FORMAT_R8G8B8A8_SRGB);
+    assert_eq!([texture.width, texture.height], [4, 2]);
+    assert!(!texture.generate_mipmaps);
+    let lengths: Vec<_> = texture.levels.iter().map(|level| level.len()).collect();
+    assert_eq!(lengths, vec![32, 8, 4]);
+    for (i, level) in texture.levels.iter().enumerate() {
+        assert!(level.iter().all(|&byte| byte == i as u8));
+    }
+}
+
+#[test]
+fn zero_level_count_requests_generated_mipmaps() {
+    let file = ktx2_file(4, 4, 0, 0);
+    let texture = ktx2::parse(&file).unwrap();
+    assert_eq!(texture.levels.len(), 1);
+    assert!(texture.generate_mipmaps);
+}
+
+#[test]
+fn rejects_other_files() {
+    assert_eq!(
+        ktx2::parse(b"\x89PNG\r\n\x1a\n").unwrap_err(),
+        Ktx2Error::InvalidIdentifier
+    );
+}
+
+#[test]
+fn rejects_truncated_files() {
+    let file = ktx2_file(4, 4, 1, 0);
+    assert_eq!(
+        ktx2::parse(&file[..file.len() - 1]).unwrap_err(),
+        Ktx2Error::Truncated
+    );
+    assert_eq!(ktx2::parse(&file[..40]).unwrap_err(), Ktx2Error::Truncated);
+}
+
+#[test]
+fn rejects_supercompression() {
+    let file = ktx2_file(4, 4, 1, 2);
+    assert_eq!(
+        ktx2::parse(&file).unwrap_err(),
+        Ktx2Error::UnsupportedSupercompression(2)
+    );
+}
+
+#[test]
+fn rejects_more_levels_than_fit() {
+    let file = ktx2_file(4, 2, 4, 0);
+    assert_eq!(
+        ktx2::parse(&file).unwrap_err(),
+        Ktx2Error::TooManyLevels { levels: 4, max: 3 }
+    );
+    assert_eq!(ktx2::max_mip_levels(1, 1), 1);
+    assert_eq!(ktx2::max_mip_levels(1024, 768), 11);
+}
+
+#[test]
+fn checks_level_lengths_against_the_format() {
+    let file = ktx2_file(4, 2, 3, 0);
+    let texture = ktx2::parse(&file).unwrap();
+    assert_eq!(texture.check_level_lengths(4, (1, 1)), Ok(()));
+    // 4x4 blocks of 16 bytes; partial blocks take up a whole one
+    assert_eq!(
+        texture.check_level_lengths(16, (4, 4)),
+        Err(Ktx2Error::InvalidLevelLength {
+            level: 0,
+            expected: 16,
+            actual: 32,
+        })
+    );
+}
+
+#[test]
+fn rejects_levels_of_the_wrong_length() {
+    let mut file = ktx2_file(4, 4, 3, 0);
+    // byteLength of the second level index entry
+    let entry = 80 + 24 + 8;
+    file[entry..entry + 8].copy_from_slice(&3u64.to_le_bytes());
+    let texture = ktx2::parse(&file).unwrap();
+    assert_eq!(
+        texture.check_level_lengths(4, (1, 1)),
+        Err(Ktx2Error::InvalidLevelLength {
+            level: 1,
+            expected: 16,
+            actual: 3,
+        })
+    );
+}
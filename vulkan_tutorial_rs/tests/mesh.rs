@@ -0,0 +1,39 @@
+use vulkan_tutorial_rs::app::mesh::builtin;
+use vulkan_tutorial_rs::{MeshData, Vertex};
+
+fn assert_valid(data: &MeshData<Vertex>) {
+    assert_eq!(data.indices.len() % 3, 0);
+    assert!(data
+        .indices
+        .iter()
+        .all(|&index| (index as usize) < data.vertices.len()));
+}
+
+#[test]
+fn triangle() {
+    let data = builtin::triangle();
+    assert_valid(&data);
+    assert_eq!(data.vertices.len(), 3);
+    assert_eq!(data.indices.len(), 3);
+}
+
+#[test]
+fn quad() {
+    let data = builtin::quad();
+    assert_valid(&data);
+    assert_eq!(data.vertices.len(), 4);
+    assert_eq!(data.indices.len(), 6);
+}
+
+#[test]
+fn cube() {
+    let data = builtin::cube();
+    assert_valid(&data);
+    assert_eq!(data.vertices.len(), 24);
+    assert_eq!(data.indices.len(), 36);
+    // every vertex lies on the unit cube's surface
+    assert!(data
+        .vertices
+        .iter()
+        .all(|v| v.position.iter().any(|c| c.abs() == 0.5)));
+}
@@ -0,0 +1,265 @@
+use vulkan_tutorial_rs::app::mesh::{builtin, processing};
+use vulkan_tutorial_rs::app::model::TextureSource;
+use vulkan_tutorial_rs::{MeshData, ModelData, ModelError, Vertex};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// a single triangle with positions (0,0,0), (1,0,0), (0,1,0), u16 indices and a green material,
+// placed by its node at z = 5
+const TRIANGLE_GLTF: &str = r#"{
+    "asset": { "version": "2.0" },
+    "scene": 0,
+    "scenes": [{ "nodes": [0] }],
+    "nodes": [{ "mesh": 0, "translation": [0.0, 0.0, 5.0] }],
+    "meshes": [{
+        "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1, "material": 0 }]
+    }],
+    "materials": [{
+        "name": "green",
+        "pbrMetallicRoughness": { "baseColorFactor": [0.0, 1.0, 0.0, 1.0] }
+    }],
+    "buffers": [{
+        "byteLength": 44,
+        "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA="
+    }],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+    ],
+    "accessors": [
+        {
+            "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+            "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+        },
+        { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+    ]
+}"#;
+
+// the triangle without indices but with normals, placed by a node that doubles its size
+const SCALED_GLTF: &str = r#"{
+    "asset": { "version": "2.0" },
+    "scene": 0,
+    "scenes": [{ "nodes": [0] }],
+    "nodes": [{ "mesh": 0, "scale": [2.0, 2.0, 2.0] }],
+    "meshes": [{
+        "primitives": [{ "attributes": { "POSITION": 0, "NORMAL": 1 } }]
+    }],
+    "buffers": [{
+        "byteLength": 72,
+        "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/AAAAAAAAAAAAAIA/"
+    }],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 36 }
+    ],
+    "accessors": [
+        {
+            "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+            "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+        },
+        { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" }
+    ]
+}"#;
+
+// a quad made of two triangles that repeat two of its corners
+const QUAD_OBJ: &str = "mtllib quad.mtl
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+usemtl red
+f 1/1 2/2 3/3
+f 3/3 4/4 1/1
+";
+
+const QUAD_MTL: &str = "newmtl red
+Kd 1 0 0
+d 0.5
+map_Kd red.png
+";
+
+fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("vulkan_tutorial_rs_{}", name));
+    fs::create_dir_all(&directory).unwrap();
+    for (file, contents) in files {
+        fs::write(directory.join(file), contents).unwrap();
+    }
+    directory
+}
+
+fn vertex(position: [f32; 3]) -> Vertex {
+    Vertex {
+        position,
+        ..Vertex::default()
+    }
+}
+
+fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn deduplicate_merges_identical_vertices() {
+    let mut data = MeshData {
+        vertices: vec![
+            vertex([0.0, 0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            vertex([0.0, 0.0, 0.0]),
+            vertex([0.0, 1.0, 0.0]),
+        ],
+        indices: vec![0, 1, 3, 2, 1, 3],
+    };
+    processing::deduplicate(&mut data);
+    assert_eq!(data.vertices.len(), 3);
+    assert_eq!(data.indices, vec![0, 1, 2, 0, 1, 2]);
+}
+
+#[test]
+fn generated_normals_face_counter_clockwise_winding() {
+    let quad = builtin::quad();
+    for vertex in &quad.vertices {
+        assert_close(&vertex.normal, &[0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+fn cube_normals_point_outwards() {
+    for vertex in &builtin::cube().vertices {
+        let outwards: f32 = vertex
+            .normal
+            .iter()
+            .zip(&vertex.position)
+            .map(|(n, p)| n * p)
+            .sum();
+        assert_close(&[outwards], &[0.5]);
+    }
+}
+
+#[test]
+fn generated_tangents_follow_u() {
+    let quad = builtin::quad();
+    for vertex in &quad.vertices {
+        assert_close(&vertex.tangent, &[1.0, 0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+fn tangents_without_texture_coordinates_are_perpendicular() {
+    let mut data = MeshData {
+        vertices: vec![
+            vertex([0.0, 0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            vertex([0.0, 1.0, 0.0]),
+        ],
+        indices: vec![0, 1, 2],
+    };
+    processing::generate_normals(&mut data);
+    processing::generate_tangents(&mut data);
+    for vertex in &data.vertices {
+        let dot: f32 = vertex
+            .normal
+            .iter()
+            .zip(&vertex.tangent)
+            .map(|(n, t)| n * t)
+            .sum();
+        assert_close(&[dot], &[0.0]);
+    }
+}
+
+#[test]
+fn loads_obj_with_materials() {
+    let directory = write_files("obj", &[("quad.obj", QUAD_OBJ), ("quad.mtl", QUAD_MTL)]);
+    let model = ModelData::load(&directory.join("quad.obj")).unwrap();
+
+    assert_eq!(model.materials.len(), 1);
+    let material = &model.materials[0];
+    assert_eq!(material.name, "red");
+    assert_eq!(material.base_color, [1.0, 0.0, 0.0, 0.5]);
+    match material.base_color_texture {
+        Some(TextureSource::Path(ref path)) => assert_eq!(path, &directory.join("red.png")),
+        ref other => panic!("unexpected texture {:?}", other),
+    }
+
+    assert_eq!(model.meshes.len(), 1);
+    let mesh = &model.meshes[0];
+    assert_eq!(mesh.material, Some(0));
+    assert_eq!(mesh.data.vertices.len(), 4);
+    assert_eq!(mesh.data.indices.len(), 6);
+    for vertex in &mesh.data.vertices {
+        assert_eq!(vertex.color, [1.0, 0.0, 0.0]);
+        assert_close(&vertex.normal, &[0.0, 0.0, 1.0]);
+    }
+    // v is flipped so the texture's top row is at v = 0
+    let first = mesh
+        .data
+        .vertices
+        .iter()
+        .find(|v| v.position == [-1.0, -1.0, 0.0])
+        .unwrap();
+    assert_eq!(first.uv, [0.0, 1.0]);
+}
+
+#[test]
+fn loads_gltf_with_embedded_buffer() {
+    let directory = write_files("gltf", &[("triangle.gltf", TRIANGLE_GLTF)]);
+    let model = ModelData::load(&directory.join("triangle.gltf")).unwrap();
+
+    assert_eq!(model.materials.len(), 1);
+    assert_eq!(model.materials[0].name, "green");
+    assert_eq!(model.materials[0].base_color, [0.0, 1.0, 0.0, 1.0]);
+
+    assert_eq!(model.meshes.len(), 1);
+    let mesh = &model.meshes[0];
+    assert_eq!(mesh.material, Some(0));
+    assert_eq!(mesh.data.indices, vec![0, 1, 2]);
+    let positions: Vec<_> = mesh.data.vertices.iter().map(|v| v.position).collect();
+    assert_eq!(
+        positions,
+        vec![[0.0, 0.0, 5.0], [1.0, 0.0, 5.0], [0.0, 1.0, 5.0]]
+    );
+    for vertex in &mesh.data.vertices {
+        assert_eq!(vertex.color, [0.0, 1.0, 0.0]);
+        assert_close(&vertex.normal, &[0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+fn gltf_normals_stay_unit_length_under_scaling() {
+    let directory = write_files("gltf_scaled", &[("triangle.gltf", SCALED_GLTF)]);
+    let model = ModelData::load(&directory.join("triangle.gltf")).unwrap();
+    let vertices = &model.meshes[0].data.vertices;
+    assert_eq!(vertices[1].position, [2.0, 0.0, 0.0]);
+    for vertex in vertices {
+        assert_close(&vertex.normal, &[0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+fn rejects_out_of_range_gltf_indices() {
+    // the third index is 7
+    let gltf = TRIANGLE_GLTF.replace("AAABAAIAAAA=", "AAABAAcAAAA=");
+    let directory = write_files("gltf_bad_index", &[("triangle.gltf", &gltf)]);
+    match ModelData::load(&directory.join("triangle.gltf")) {
+        Err(ModelError::IndexOutOfRange {
+            index,
+            vertex_count,
+        }) => assert_eq!((index, vertex_count), (7, 3)),
+        other => panic!("unexpected result {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn rejects_unknown_extensions() {
+    match ModelData::load(Path::new("model.fbx")) {
+        Err(ModelError::UnsupportedFormat(path)) => assert_eq!(path, Path::new("model.fbx")),
+        other => panic!("unexpected result {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,30 @@
+use vulkan_tutorial_rs::app::config;
+use vulkan_tutorial_rs::app::paths;
+
+use std::env;
+use std::path::Path;
+
+#[test]
+fn resource_path_finds_source_tree_files_from_target_directory() {
+    // test executables run from somewhere under `target/`
+    let path = paths::resource_path("assets/icon.png");
+    assert!(path.is_file(), "{} not found", path.display());
+    assert!(path.ends_with("assets/icon.png"));
+}
+
+#[test]
+fn missing_resource_resolves_next_to_executable() {
+    let path = paths::resource_path("no/such/resource.png");
+    let executable = env::current_exe().unwrap();
+    assert_eq!(
+        path.parent().unwrap().parent().unwrap().parent(),
+        executable.parent()
+    );
+    assert!(path.ends_with("no/such/resource.png"));
+}
+
+#[test]
+fn data_path_is_under_app_data_directory() {
+    let path = paths::data_path("screenshots");
+    assert!(path.ends_with(Path::new(config::DATA_DIRECTORY_NAME).join("screenshots")));
+}
@@ -0,0 +1,126 @@
+use vulkan_tutorial_rs::app::pipeline_cache::{self, PipelineCacheError, PipelineCacheKey};
+
+const KEY: PipelineCacheKey = PipelineCacheKey {
+    vendor_id: 0x10de,
+    device_id: 0x1f08,
+    driver_version: 0x1d4c_8000,
+    uuid: [7; 16],
+};
+
+// what a driver returns from vkGetPipelineCacheData: its header followed by opaque data
+fn driver_data(key: &PipelineCacheKey, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(&32u32.to_le_bytes());
+    data.extend(&1u32.to_le_bytes());
+    data.extend(&key.vendor_id.to_le_bytes());
+    data.extend(&key.device_id.to_le_bytes());
+    data.extend(&key.uuid);
+    data.extend(payload);
+    data
+}
+
+#[test]
+fn round_trips_driver_data() {
+    let data = driver_data(&KEY, &[1, 2, 3, 4, 5]);
+    let file = pipeline_cache::encode(&KEY, &data);
+    assert_eq!(pipeline_cache::decode(&KEY, &file).unwrap(), &data[..]);
+}
+
+#[test]
+fn rejects_other_devices_and_drivers() {
+    let file = pipeline_cache::encode(&KEY, &driver_data(&KEY, &[1, 2, 3]));
+    let other_keys = [
+        PipelineCacheKey {
+            vendor_id: 0x1002,
+            ..KEY
+        },
+        PipelineCacheKey {
+            device_id: 0x1f09,
+            ..KEY
+        },
+        PipelineCacheKey {
+            driver_version: 0x1d4c_8001,
+            ..KEY
+        },
+        PipelineCacheKey {
+            uuid: [8; 16],
+            ..KEY
+        },
+    ];
+    for key in &other_keys {
+        match pipeline_cache::decode(key, &file) {
+            Err(PipelineCacheError::KeyMismatch) => {}
+            other => panic!("expected a key mismatch, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn rejects_driver_data_for_another_device() {
+    // a file whose own header matches but whose driver data came from elsewhere
+    let other = PipelineCacheKey {
+        uuid: [9; 16],
+        ..KEY
+    };
+    let file = pipeline_cache::encode(&KEY, &driver_data(&other, &[1, 2, 3]));
+    match pipeline_cache::decode(&KEY, &file) {
+        Err(PipelineCacheError::KeyMismatch) => {}
+        other => panic!("expected a key mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_truncated_and_corrupt_files() {
+    let file = pipeline_cache::encode(&KEY, &driver_data(&KEY, &[1, 2, 3, 4]));
+
+    match pipeline_cache::decode(&KEY, &file[..file.len() - 1]) {
+        Err(PipelineCacheError::Truncated) => {}
+        other => panic!("expected a truncated file, got {:?}", other),
+    }
+    match pipeline_cache::decode(&KEY, &file[..20]) {
+        Err(PipelineCacheError::Truncated) => {}
+        other => panic!("expected a truncated file, got {:?}", other),
+    }
+
+    let mut corrupt = file.clone();
+    *corrupt.last_mut().unwrap() ^= 0xff;
+    match pipeline_cache::decode(&KEY, &corrupt) {
+        Err(PipelineCacheError::ChecksumMismatch) => {}
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_other_files() {
+    match pipeline_cache::decode(&KEY, b"not a pipeline cache") {
+        Err(PipelineCacheError::InvalidIdentifier) => {}
+        other => panic!("expected an invalid identifier, got {:?}", other),
+    }
+    match pipeline_cache::decode(&KEY, &[]) {
+        Err(PipelineCacheError::InvalidIdentifier) => {}
+        other => panic!("expected an invalid identifier, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_driver_data_without_a_valid_header() {
+    let file = pipeline_cache::encode(&KEY, &[0; 16]);
+    match pipeline_cache::decode(&KEY, &file) {
+        Err(PipelineCacheError::Truncated) => {}
+        other => panic!("expected a truncated file, got {:?}", other),
+    }
+}
+
+#[test]
+fn file_name_identifies_the_device_and_driver() {
+    let name = KEY.file_name();
+    assert!(name.starts_with("pipeline_cache_10de_1f08_1d4c8000_0707"));
+    assert_ne!(
+        name,
+        PipelineCacheKey {
+            driver_version: 1,
+            ..KEY
+        }
+        .file_name()
+    );
+}
@@ -0,0 +1,100 @@
+use vulkan_tutorial_rs::app::profiler::ticks_to_duration;
+use vulkan_tutorial_rs::{FrameProfile, ProfileHistory, RegionProfile};
+
+use std::time::Duration;
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn region(name: &str, depth: usize, cpu: Duration, gpu: Option<Duration>) -> RegionProfile {
+    RegionProfile {
+        name: name.to_string(),
+        depth,
+        cpu,
+        gpu,
+    }
+}
+
+#[test]
+fn ticks_are_scaled_by_the_timestamp_period() {
+    assert_eq!(
+        ticks_to_duration(1_000, 3_000, 64, 1.0),
+        Duration::from_nanos(2_000)
+    );
+    assert_eq!(
+        ticks_to_duration(1_000, 3_000, 64, 52.083),
+        Duration::from_nanos(104_166)
+    );
+}
+
+#[test]
+fn ticks_wrap_within_the_valid_bits() {
+    // a 32-bit counter that wrapped between the two timestamps
+    assert_eq!(
+        ticks_to_duration(0xffff_fff0, 0x10, 32, 1.0),
+        Duration::from_nanos(0x20)
+    );
+    assert_eq!(
+        ticks_to_duration(u64::MAX, 9, 64, 1.0),
+        Duration::from_nanos(10)
+    );
+}
+
+#[test]
+fn ticks_ignore_bits_above_the_valid_bits() {
+    assert_eq!(
+        ticks_to_duration(0xdead_0000_0000_0010, 0xbeef_0000_0000_0030, 36, 1.0),
+        Duration::from_nanos(0x20)
+    );
+}
+
+#[test]
+fn history_keeps_the_most_recent_frames() {
+    let mut history = ProfileHistory::new(2);
+    assert!(history.average().is_none());
+    for ms in 1..=3 {
+        history.record(FrameProfile {
+            cpu: millis(ms),
+            gpu: None,
+            regions: Vec::new(),
+        });
+    }
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.last().unwrap().cpu, millis(3));
+    assert_eq!(history.average().unwrap().cpu, Duration::from_micros(2_500));
+}
+
+#[test]
+fn history_averages_regions_by_name_and_depth() {
+    let mut history = ProfileHistory::new(4);
+    history.record(FrameProfile {
+        cpu: millis(4),
+        gpu: Some(millis(6)),
+        regions: vec![
+            region("scene", 0, millis(2), Some(millis(4))),
+            region("shadows", 1, millis(1), Some(millis(2))),
+        ],
+    });
+    // no GPU timings for this frame, and a region the first frame didn't have
+    history.record(FrameProfile {
+        cpu: millis(6),
+        gpu: None,
+        regions: vec![
+            region("scene", 0, millis(4), None),
+            region("ui", 0, millis(2), None),
+        ],
+    });
+
+    let average = history.average().unwrap();
+    assert_eq!(average.cpu, millis(5));
+    assert_eq!(average.gpu, Some(millis(6)));
+    assert_eq!(
+        average.regions,
+        vec![
+            region("scene", 0, millis(3), Some(millis(4))),
+            region("shadows", 1, millis(1), Some(millis(2))),
+            region("ui", 0, millis(2), None),
+        ]
+    );
+}
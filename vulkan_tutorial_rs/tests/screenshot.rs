@@ -0,0 +1,66 @@
+use vulkan_tutorial_rs::app::screenshot::{self, ScreenshotError};
+
+use vulkano::format::Format;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn swaps_bgra_to_rgba() {
+    let data = [10, 20, 30, 40, 1, 2, 3, 4];
+    for &format in &[Format::B8G8R8A8Unorm, Format::B8G8R8A8Srgb] {
+        let pixels = screenshot::convert_to_rgba8(format, &data, false).unwrap();
+        assert_eq!(pixels, vec![30, 20, 10, 40, 3, 2, 1, 4]);
+    }
+}
+
+#[test]
+fn copies_rgba() {
+    let data = [10, 20, 30, 40];
+    let pixels = screenshot::convert_to_rgba8(Format::R8G8B8A8Srgb, &data, false).unwrap();
+    assert_eq!(pixels, data.to_vec());
+}
+
+#[test]
+fn opaque_images_get_full_alpha() {
+    let pixels = screenshot::convert_to_rgba8(Format::B8G8R8A8Srgb, &[1, 2, 3, 0], true).unwrap();
+    assert_eq!(pixels, vec![3, 2, 1, 255]);
+}
+
+#[test]
+fn converts_10_bit_channels() {
+    // red 1023, green 512, blue 0, alpha 3
+    let packed: u32 = 1023 | 512 << 10 | 3 << 30;
+    let pixels =
+        screenshot::convert_to_rgba8(Format::A2B10G10R10UnormPack32, &packed.to_ne_bytes(), false)
+            .unwrap();
+    assert_eq!(pixels, vec![255, 128, 0, 255]);
+
+    // the same bits with red and blue swapped
+    let pixels =
+        screenshot::convert_to_rgba8(Format::A2R10G10B10UnormPack32, &packed.to_ne_bytes(), false)
+            .unwrap();
+    assert_eq!(pixels, vec![0, 128, 255, 255]);
+}
+
+#[test]
+fn rejects_unsupported_formats() {
+    assert!(!screenshot::is_supported_format(Format::R16G16B16A16Sfloat));
+    match screenshot::convert_to_rgba8(Format::R16G16B16A16Sfloat, &[0; 8], false) {
+        Err(ScreenshotError::UnsupportedFormat(Format::R16G16B16A16Sfloat)) => {}
+        other => panic!("expected an unsupported format, got {:?}", other),
+    }
+}
+
+#[test]
+fn file_names_are_timestamped_in_utc() {
+    assert_eq!(
+        screenshot::file_name(UNIX_EPOCH),
+        "screenshot_1970-01-01_00-00-00.000.png"
+    );
+    // 2020-02-29 23:59:58.250
+    let time = UNIX_EPOCH + Duration::from_millis(1_583_020_798_250);
+    assert_eq!(
+        screenshot::file_name(time),
+        "screenshot_2020-02-29_23-59-58.250.png"
+    );
+}
@@ -0,0 +1,54 @@
+use vulkan_tutorial_rs::app::shader::{parse_diagnostics, Diagnostic, Severity};
+
+#[test]
+fn parses_errors_with_file_and_line() {
+    let messages = "/shaders/mesh.frag:12: error: 'colour' : undeclared identifier\n\
+                    /shaders/mesh.frag:12: error: '' : compilation terminated\n\
+                    2 errors generated.\n";
+    let diagnostics = parse_diagnostics(messages);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+        diagnostics[0],
+        Diagnostic {
+            file: String::from("/shaders/mesh.frag"),
+            line: Some(12),
+            severity: Severity::Error,
+            message: String::from("'colour' : undeclared identifier"),
+        }
+    );
+}
+
+#[test]
+fn parses_warnings() {
+    let diagnostics = parse_diagnostics("common.glsl:3: warning: unused variable\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].file, "common.glsl");
+    assert_eq!(diagnostics[0].line, Some(3));
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+}
+
+#[test]
+fn keeps_diagnostics_without_a_line_number() {
+    let diagnostics = parse_diagnostics("mesh.vert: error: #version: missing\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].file, "mesh.vert");
+    assert_eq!(diagnostics[0].line, None);
+    assert_eq!(diagnostics[0].message, "#version: missing");
+}
+
+#[test]
+fn skips_summary_lines() {
+    assert!(parse_diagnostics("1 error generated.\n").is_empty());
+    assert!(parse_diagnostics("").is_empty());
+}
+
+#[test]
+fn formats_diagnostics_like_the_compiler() {
+    let diagnostic = Diagnostic {
+        file: String::from("mesh.frag"),
+        line: Some(7),
+        severity: Severity::Error,
+        message: String::from("syntax error"),
+    };
+    assert_eq!(diagnostic.to_string(), "mesh.frag:7: error: syntax error");
+}
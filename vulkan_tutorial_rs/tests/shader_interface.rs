@@ -0,0 +1,233 @@
+use vulkan_tutorial_rs::app::paths;
+use vulkan_tutorial_rs::app::shader::interface::{
+    self, DescriptorBinding, DescriptorKind, ImageDimensions, ImageKind, InterfaceMismatch,
+    InterfaceVariable, ReflectionError,
+};
+use vulkan_tutorial_rs::ShaderCompiler;
+
+use vulkano::format::Format;
+
+use std::fs;
+
+const MESH_FRAGMENT_SHADER: &str = "
+#version 450
+
+layout(set = 0, binding = 1) uniform sampler2D tex;
+
+layout(location = 0) in vec3 frag_color;
+layout(location = 1) in vec2 frag_uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(frag_color, 1.0) * texture(tex, frag_uv);
+}
+";
+
+fn compile_file(name: &str) -> Vec<u32> {
+    let path = paths::resource_path("shaders").join(name);
+    ShaderCompiler::new().load(&path).unwrap().spirv
+}
+
+fn compile(name: &str, source: &str) -> Vec<u32> {
+    let directory = std::env::temp_dir().join("vulkan_tutorial_rs_shader_interface");
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    fs::write(&path, source).unwrap();
+    ShaderCompiler::new().load(&path).unwrap().spirv
+}
+
+fn variable(location: u32, format: Format) -> InterfaceVariable {
+    InterfaceVariable { location, format }
+}
+
+#[test]
+fn reflects_the_mesh_vertex_shader() {
+    let interface = interface::reflect(&compile_file("mesh.vert"), "main").unwrap();
+    assert_eq!(
+        interface.inputs,
+        vec![
+            variable(0, Format::R32G32B32Sfloat),
+            variable(1, Format::R32G32B32Sfloat),
+            variable(2, Format::R32G32Sfloat),
+        ]
+    );
+    assert_eq!(
+        interface.outputs,
+        vec![
+            variable(0, Format::R32G32B32Sfloat),
+            variable(1, Format::R32G32Sfloat),
+        ]
+    );
+    // three matrices and a float
+    assert_eq!(
+        interface.descriptors,
+        vec![DescriptorBinding {
+            set: 0,
+            binding: 0,
+            kind: DescriptorKind::UniformBuffer,
+            count: 1,
+            size: Some(196),
+        }]
+    );
+    assert_eq!(interface.push_constant_size, 0);
+}
+
+#[test]
+fn reflects_the_mesh_fragment_shader() {
+    let interface = interface::reflect(&compile_file("mesh.frag"), "main").unwrap();
+    assert_eq!(
+        interface.inputs,
+        vec![
+            variable(0, Format::R32G32B32Sfloat),
+            variable(1, Format::R32G32Sfloat),
+        ]
+    );
+    assert_eq!(
+        interface.outputs,
+        vec![variable(0, Format::R32G32B32A32Sfloat)]
+    );
+    assert_eq!(
+        interface.descriptors,
+        vec![DescriptorBinding {
+            set: 0,
+            binding: 1,
+            kind: DescriptorKind::CombinedImageSampler(ImageKind {
+                dimensions: ImageDimensions::TwoDimensional,
+                arrayed: false,
+            }),
+            count: 1,
+            size: None,
+        }]
+    );
+}
+
+#[test]
+fn matrices_and_arrays_take_a_location_per_column_and_element() {
+    let source = "
+#version 450
+layout(location = 0) in mat4 transform;
+layout(location = 4) in float weights[2];
+layout(push_constant) uniform PushConstants {
+    vec4 tint;
+    float scale;
+} push;
+void main() {
+    gl_Position = transform * push.tint * weights[0] * weights[1] * push.scale;
+}
+";
+    let interface = interface::reflect(&compile("matrix.vert", source), "main").unwrap();
+    let mut expected = vec![variable(0, Format::R32G32B32A32Sfloat); 4];
+    expected.extend(vec![variable(0, Format::R32Sfloat); 2]);
+    for (location, variable) in expected.iter_mut().enumerate() {
+        variable.location = location as u32;
+    }
+    assert_eq!(interface.inputs, expected);
+    assert!(interface.outputs.is_empty());
+    assert_eq!(interface.push_constant_size, 20);
+}
+
+#[test]
+fn accepts_changes_to_the_code_only() {
+    let expected = interface::reflect(&compile_file("mesh.frag"), "main").unwrap();
+    let source = MESH_FRAGMENT_SHADER.replace(
+        "vec4(frag_color, 1.0) * texture(tex, frag_uv)",
+        "texture(tex, frag_uv * 2.0).bgra",
+    );
+    let interface = interface::reflect(&compile("code.frag", &source), "main").unwrap();
+    assert_eq!(interface.check_compatible(&expected), Ok(()));
+}
+
+#[test]
+fn rejects_changed_inputs() {
+    let expected = interface::reflect(&compile_file("mesh.frag"), "main").unwrap();
+    let source = MESH_FRAGMENT_SHADER
+        .replace("in vec2 frag_uv", "in vec3 frag_uv")
+        .replace("texture(tex, frag_uv)", "texture(tex, frag_uv.xy)");
+    let interface = interface::reflect(&compile("inputs.frag", &source), "main").unwrap();
+    assert_eq!(
+        interface.check_compatible(&expected),
+        Err(InterfaceMismatch::Inputs {
+            expected: expected.inputs.clone(),
+            found: vec![
+                variable(0, Format::R32G32B32Sfloat),
+                variable(1, Format::R32G32B32Sfloat),
+            ],
+        })
+    );
+}
+
+#[test]
+fn rejects_descriptors_the_layout_lacks() {
+    let expected = interface::reflect(&compile_file("mesh.frag"), "main").unwrap();
+    let source = MESH_FRAGMENT_SHADER
+        .replace(
+            "uniform sampler2D tex;",
+            "uniform sampler2D tex;\nlayout(set = 0, binding = 2) uniform sampler2D detail;",
+        )
+        .replace(
+            "texture(tex, frag_uv)",
+            "texture(tex, frag_uv) * texture(detail, frag_uv)",
+        );
+    let interface = interface::reflect(&compile("descriptors.frag", &source), "main").unwrap();
+    assert_eq!(
+        interface.check_compatible(&expected),
+        Err(InterfaceMismatch::Descriptor { set: 0, binding: 2 })
+    );
+
+    let source = MESH_FRAGMENT_SHADER
+        .replace("sampler2D", "sampler3D")
+        .replace("texture(tex, frag_uv)", "texture(tex, vec3(frag_uv, 0.0))");
+    let interface = interface::reflect(&compile("dimensions.frag", &source), "main").unwrap();
+    assert_eq!(
+        interface.check_compatible(&expected),
+        Err(InterfaceMismatch::Descriptor { set: 0, binding: 1 })
+    );
+}
+
+#[test]
+fn rejects_uniform_blocks_larger_than_the_bound_buffer() {
+    let expected = interface::reflect(&compile_file("mesh.vert"), "main")
+        .unwrap()
+        .with_buffer_size(0, 0, 196);
+    let path = paths::resource_path("shaders").join("mesh.vert");
+    let source = fs::read_to_string(path)
+        .unwrap()
+        .replace("float time;", "float time;\n    float exposure;")
+        .replace(
+            "vec4(position, 1.0)",
+            "vec4(position * frame.exposure, 1.0)",
+        );
+    let interface = interface::reflect(&compile("block.vert", &source), "main").unwrap();
+    assert_eq!(
+        interface.check_compatible(&expected),
+        Err(InterfaceMismatch::BufferSize {
+            set: 0,
+            binding: 0,
+            size: 200,
+            available: 196,
+        })
+    );
+}
+
+#[test]
+fn rejects_malformed_modules() {
+    let header = vec![0x0723_0203, 0x0001_0000, 0, 1, 0];
+    let truncated_header = header[..4].to_vec();
+    // an OpEntryPoint that claims three words but has one
+    let mut truncated_instruction = header.clone();
+    truncated_instruction.push(3 << 16 | 15);
+    let mut empty_instruction = header;
+    empty_instruction.push(0);
+    for spirv in &[truncated_header, truncated_instruction, empty_instruction] {
+        assert_eq!(
+            interface::reflect(spirv, "main").unwrap_err(),
+            ReflectionError::Malformed
+        );
+    }
+    let spirv = compile_file("mesh.frag");
+    assert_eq!(
+        interface::reflect(&spirv, "other").unwrap_err(),
+        ReflectionError::MissingEntryPoint(String::from("other"))
+    );
+}
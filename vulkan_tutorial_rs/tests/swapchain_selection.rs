@@ -0,0 +1,212 @@
+use vulkan_tutorial_rs::app::config::DEPTH_FORMATS;
+use vulkan_tutorial_rs::app::swapchain_info::{
+    choose_composite_alpha, choose_depth_format, choose_dimensions, choose_format,
+    choose_image_count, choose_present_mode, choose_sample_count,
+};
+use vulkan_tutorial_rs::app::PresentPolicy;
+
+use vulkano::format::Format;
+use vulkano::image::ImageUsage;
+use vulkano::swapchain::{
+    Capabilities, ColorSpace, CompositeAlpha, PresentMode, SupportedCompositeAlpha,
+    SupportedPresentModes, SupportedSurfaceTransforms, SurfaceTransform,
+};
+
+fn capabilities() -> Capabilities {
+    Capabilities {
+        min_image_count: 2,
+        max_image_count: None,
+        current_extent: None,
+        min_image_extent: [1, 1],
+        max_image_extent: [4096, 4096],
+        max_image_array_layers: 1,
+        supported_transforms: SupportedSurfaceTransforms::none(),
+        current_transform: SurfaceTransform::Identity,
+        supported_composite_alpha: SupportedCompositeAlpha::none(),
+        supported_usage_flags: ImageUsage::none(),
+        supported_formats: vec![(Format::B8G8R8A8Unorm, ColorSpace::SrgbNonLinear)],
+        present_modes: SupportedPresentModes::none(),
+    }
+}
+
+#[test]
+fn format_prefers_bgra_srgb() {
+    let formats = vec![
+        (Format::R8G8B8A8Unorm, ColorSpace::SrgbNonLinear),
+        (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear),
+    ];
+    assert_eq!(
+        *choose_format(&formats),
+        (Format::B8G8R8A8Srgb, ColorSpace::SrgbNonLinear)
+    );
+}
+
+#[test]
+fn format_falls_back_to_first() {
+    let formats = vec![
+        (Format::A2B10G10R10UnormPack32, ColorSpace::SrgbNonLinear),
+        (Format::R8G8B8A8Unorm, ColorSpace::SrgbNonLinear),
+    ];
+    assert_eq!(
+        *choose_format(&formats),
+        (Format::A2B10G10R10UnormPack32, ColorSpace::SrgbNonLinear)
+    );
+}
+
+#[test]
+fn present_mode_prefers_mailbox() {
+    let modes = SupportedPresentModes {
+        mailbox: true,
+        fifo: true,
+        ..SupportedPresentModes::none()
+    };
+    assert_eq!(
+        choose_present_mode(&modes, PresentPolicy::Mailbox),
+        PresentMode::Mailbox
+    );
+}
+
+#[test]
+fn present_mode_falls_back_to_fifo() {
+    let modes = SupportedPresentModes {
+        fifo: true,
+        ..SupportedPresentModes::none()
+    };
+    assert_eq!(
+        choose_present_mode(&modes, PresentPolicy::Mailbox),
+        PresentMode::Fifo
+    );
+}
+
+#[test]
+fn present_mode_fifo_policy_ignores_mailbox() {
+    let modes = SupportedPresentModes {
+        mailbox: true,
+        fifo: true,
+        ..SupportedPresentModes::none()
+    };
+    assert_eq!(
+        choose_present_mode(&modes, PresentPolicy::Fifo),
+        PresentMode::Fifo
+    );
+}
+
+#[test]
+fn present_mode_immediate_policy_falls_back_to_mailbox() {
+    let modes = SupportedPresentModes {
+        mailbox: true,
+        fifo: true,
+        ..SupportedPresentModes::none()
+    };
+    assert_eq!(
+        choose_present_mode(&modes, PresentPolicy::Immediate),
+        PresentMode::Mailbox
+    );
+}
+
+#[test]
+fn composite_alpha_is_opaque_unless_transparent() {
+    let supported = SupportedCompositeAlpha {
+        opaque: true,
+        pre_multiplied: true,
+        ..SupportedCompositeAlpha::none()
+    };
+    assert_eq!(
+        choose_composite_alpha(&supported, false),
+        CompositeAlpha::Opaque
+    );
+    assert_eq!(
+        choose_composite_alpha(&supported, true),
+        CompositeAlpha::PreMultiplied
+    );
+}
+
+#[test]
+fn composite_alpha_falls_back_to_opaque_when_transparency_unsupported() {
+    let supported = SupportedCompositeAlpha {
+        opaque: true,
+        ..SupportedCompositeAlpha::none()
+    };
+    assert_eq!(
+        choose_composite_alpha(&supported, true),
+        CompositeAlpha::Opaque
+    );
+}
+
+#[test]
+fn dimensions_use_current_extent_when_fixed() {
+    let c = Capabilities {
+        current_extent: Some([800, 600]),
+        ..capabilities()
+    };
+    assert_eq!(choose_dimensions(&c, [1024, 768]), [800, 600]);
+}
+
+#[test]
+fn dimensions_clamp_window_size_to_surface_limits() {
+    let c = Capabilities {
+        min_image_extent: [64, 64],
+        max_image_extent: [1920, 1080],
+        ..capabilities()
+    };
+    assert_eq!(choose_dimensions(&c, [3840, 32]), [1920, 64]);
+}
+
+#[test]
+fn image_count_is_one_above_minimum() {
+    assert_eq!(choose_image_count(&capabilities()), 3);
+}
+
+#[test]
+fn image_count_respects_maximum() {
+    let c = Capabilities {
+        max_image_count: Some(2),
+        ..capabilities()
+    };
+    assert_eq!(choose_image_count(&c), 2);
+}
+
+#[test]
+fn depth_format_prefers_d32() {
+    assert_eq!(
+        choose_depth_format(DEPTH_FORMATS, |_| true),
+        Some(Format::D32Sfloat)
+    );
+}
+
+#[test]
+fn depth_format_falls_back_in_preference_order() {
+    assert_eq!(
+        choose_depth_format(DEPTH_FORMATS, |format| format != Format::D32Sfloat),
+        Some(Format::D32Sfloat_S8Uint)
+    );
+    assert_eq!(
+        choose_depth_format(DEPTH_FORMATS, |format| format == Format::D24Unorm_S8Uint),
+        Some(Format::D24Unorm_S8Uint)
+    );
+}
+
+#[test]
+fn depth_format_is_none_without_support() {
+    assert_eq!(choose_depth_format(DEPTH_FORMATS, |_| false), None);
+}
+
+#[test]
+fn sample_count_uses_request_when_supported() {
+    // 1, 2, 4 and 8 samples
+    assert_eq!(choose_sample_count(4, 0b1111), 4);
+}
+
+#[test]
+fn sample_count_is_lowered_to_supported_power_of_two() {
+    assert_eq!(choose_sample_count(8, 0b0011), 2);
+    assert_eq!(choose_sample_count(6, 0b1111), 4);
+    assert_eq!(choose_sample_count(16, 0b0101), 4);
+}
+
+#[test]
+fn sample_count_falls_back_to_one() {
+    assert_eq!(choose_sample_count(4, 0b0001), 1);
+    assert_eq!(choose_sample_count(0, 0b1111), 1);
+    assert_eq!(choose_sample_count(1, 0b1111), 1);
+}
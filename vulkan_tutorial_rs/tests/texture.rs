@@ -0,0 +1,109 @@
+use vulkan_tutorial_rs::{App, Texture};
+
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBuffer};
+use vulkano::instance::InstanceExtensions;
+use vulkano::sync::GpuFuture;
+
+const SIZE: u32 = 16;
+
+// these tests need a Vulkan device, e.g. lavapipe; on machines without one they pass without
+// doing anything
+fn app() -> Option<App> {
+    if InstanceExtensions::supported_by_core().is_err() {
+        println!("Vulkan loader not available, skipping");
+        return None;
+    }
+    match App::builder().headless().with_validation(false).build() {
+        Ok(app) => Some(app),
+        Err(e) => {
+            println!("No Vulkan device available ({}), skipping", e);
+            None
+        }
+    }
+}
+
+// alternating black and white texels
+fn checkerboard() -> Vec<u8> {
+    let mut pixels = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for i in 0..SIZE * SIZE {
+        let value = if (i % SIZE + i / SIZE) % 2 == 0 {
+            255
+        } else {
+            0
+        };
+        pixels.extend_from_slice(&[value, value, value, 255]);
+    }
+    pixels
+}
+
+#[test]
+fn generated_mipmaps_average_the_level_above() {
+    let app = match app() {
+        Some(app) => app,
+        None => return,
+    };
+    let context = app.context();
+    let logical_device = context.logical_device();
+    let device = logical_device.device();
+    let queue = logical_device.graphics_queue();
+
+    let texture = {
+        let mut uploader = logical_device.uploader();
+        let texture = Texture::from_rgba8(&mut uploader, &checkerboard(), [SIZE, SIZE]);
+        uploader
+            .flush()
+            .then_signal_fence_and_flush()
+            .expect("failed to flush uploads")
+            .wait(None)
+            .expect("failed to upload texture");
+        texture
+    };
+    let levels = texture.image().mipmap_levels();
+    if levels == 1 {
+        println!("The device can't blit sRGB RGBA8 images, skipping");
+        return;
+    }
+    assert_eq!(levels, 5);
+
+    let buffer = CpuAccessibleBuffer::from_iter(
+        device.clone(),
+        BufferUsage::transfer_destination(),
+        true,
+        [0u8; 4].iter().cloned(),
+    )
+    .unwrap();
+    let mut builder =
+        AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family()).unwrap();
+    builder
+        .copy_image_to_buffer_dimensions(
+            texture.image().clone(),
+            buffer.clone(),
+            [0, 0, 0],
+            [1, 1, 1],
+            0,
+            1,
+            levels - 1,
+        )
+        .unwrap();
+    builder
+        .build()
+        .unwrap()
+        .execute(queue.clone())
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .unwrap()
+        .wait(None)
+        .unwrap();
+
+    // half black and half white, filtered in linear space and stored sRGB encoded
+    let texel = buffer.read().unwrap();
+    for &channel in &texel[..3] {
+        assert!(
+            (i16::from(channel) - 188).abs() <= 4,
+            "smallest mip level is {:?}",
+            &texel[..]
+        );
+    }
+    assert_eq!(texel[3], 255);
+}
@@ -0,0 +1,21 @@
+use vulkan_tutorial_rs::FrameUniforms;
+
+use cgmath::{Matrix4, SquareMatrix, Vector3};
+
+use std::mem;
+
+#[test]
+fn frame_uniforms_match_std140_layout() {
+    // three mat4s, a float and padding up to the block's 16 byte alignment
+    assert_eq!(mem::size_of::<FrameUniforms>(), 3 * 64 + 16);
+    assert_eq!(mem::align_of::<FrameUniforms>(), 4);
+}
+
+#[test]
+fn frame_uniforms_store_matrices_column_major() {
+    let model = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0));
+    let uniforms = FrameUniforms::new(model, Matrix4::identity(), Matrix4::identity(), 1.5);
+    assert_eq!(uniforms.model[3], [1.0, 2.0, 3.0, 1.0]);
+    assert_eq!(uniforms.view, FrameUniforms::default().view);
+    assert_eq!(uniforms.time, 1.5);
+}
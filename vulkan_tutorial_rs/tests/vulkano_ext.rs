@@ -0,0 +1,43 @@
+use vulkan_tutorial_rs::vulkano_ext::{
+    message_severity_to_string, message_type_to_string, MESSAGE_SEVERITY_ERROR,
+    MESSAGE_SEVERITY_INFORMATION, MESSAGE_SEVERITY_VERBOSE, MESSAGE_SEVERITY_WARNING,
+    MESSAGE_TYPE_GENERAL, MESSAGE_TYPE_PERFORMANCE, MESSAGE_TYPE_VALIDATION,
+};
+
+#[test]
+fn severities_have_names() {
+    assert_eq!(
+        message_severity_to_string(MESSAGE_SEVERITY_VERBOSE),
+        "Verbose"
+    );
+    assert_eq!(
+        message_severity_to_string(MESSAGE_SEVERITY_INFORMATION),
+        "Info"
+    );
+    assert_eq!(
+        message_severity_to_string(MESSAGE_SEVERITY_WARNING),
+        "Warning"
+    );
+    assert_eq!(message_severity_to_string(MESSAGE_SEVERITY_ERROR), "Error");
+}
+
+#[test]
+fn types_have_names() {
+    assert_eq!(message_type_to_string(MESSAGE_TYPE_GENERAL), "General");
+    assert_eq!(
+        message_type_to_string(MESSAGE_TYPE_VALIDATION),
+        "Validation"
+    );
+    assert_eq!(
+        message_type_to_string(MESSAGE_TYPE_PERFORMANCE),
+        "Performance"
+    );
+}
+
+#[test]
+#[should_panic]
+fn combined_severities_are_not_named() {
+    let mut severity = MESSAGE_SEVERITY_ERROR;
+    severity.warning = true;
+    message_severity_to_string(severity);
+}